@@ -9,8 +9,24 @@ struct Cli {
     #[arg(short, long)]
     append: bool,
 
+    /// Tag the new snippet, e.g. sign, npc or manual. Can be given more than once
+    #[arg(short, long)]
+    tag: Vec<String>,
+
+    /// Where in the game the snippet is found
+    #[arg(short, long)]
+    location: Option<String>,
+
+    /// Where the snippet is on its source image: left or right and/or x,y,width,height in
+    /// pixels. Example: "right 120,40,300,64"
+    #[arg(short, long)]
+    region: Option<SourceRegion>,
+
     #[command(subcommand)]
-    command: Option<Commands>,
+    command: Commands,
+
+    #[command(flatten)]
+    paths: ConfigArgs,
 }
 
 #[derive(Subcommand)]
@@ -52,7 +68,8 @@ enum English {
 struct Page {
     number: usize,
     description: String,
-    /// Required for English words
+    /// Required for English words. For Tunic words, the word's glyphs in hex, segment list,
+    /// phoneme or integer notation
     word_text: Option<String>,
 }
 
@@ -60,7 +77,8 @@ struct Page {
 struct Screenshot {
     filename: String,
     description: String,
-    /// Required for English words
+    /// Required for English words. For Tunic words, the word's glyphs in hex, segment list,
+    /// phoneme or integer notation
     word_text: Option<String>,
 }
 
@@ -68,50 +86,57 @@ struct Screenshot {
 struct Other {
     text: String,
     description: String,
-    /// Required for English words
+    /// Required for English words. For Tunic words, the word's glyphs in hex, segment list,
+    /// phoneme or integer notation
     word_text: Option<String>,
 }
 
 fn main() {
     let cli = Cli::parse();
 
+    let config = FennecConfig::from_args(&cli.paths).unwrap_or_else(|error| error.exit());
+
+    if let Err(error) = add_snippet(&cli, &config) {
+        error.exit();
+    }
+}
+
+fn add_snippet(cli: &Cli, config: &FennecConfig) -> FennecResult<()> {
     println!("Loading notebook...");
-    match notebook_from_yaml_file(DEFAULT_NOTEBOOK_FILE) {
-        Ok((mut notebook, _yaml)) => {
-            println!("Prepending snippet...");
-
-            let snippet = match &cli.command {
-                Some(Commands::Tunic(args)) => tunic_word_snippet(args),
-                Some(Commands::English(args)) => english_word_snippet(args),
-                None => panic!("Missing command"), // Make this required by definition
-            };
-
-            println!("New snippet: {:?}", snippet);
-
-            if cli.append {
-                notebook.snippets.push(snippet);
-            } else {
-                notebook.snippets.insert(0, snippet);
-            }
-
-            println!("Saving notebook...");
-
-            match notebook_to_yaml_file(&notebook, DEFAULT_NOTEBOOK_FILE) {
-                Ok(_yaml) => println!("Notebook saved"),
-                Err(error) => {
-                    println!("Unable to save notebook file: {}", DEFAULT_NOTEBOOK_FILE);
-                    println!("{:?}", error);
-                }
-            };
-        }
-        Err(error) => {
-            println!("Unable to load notebook file: {}", DEFAULT_NOTEBOOK_FILE);
-            println!("{:?}", error);
-        }
+    let (mut notebook, _yaml) = notebook_from_yaml_file(&config.notebook)?;
+
+    if cli.append {
+        println!("Appending snippet...");
+    } else {
+        println!("Prepending snippet...");
+    }
+
+    let snippet = match &cli.command {
+        Commands::Tunic(args) => tunic_word_snippet(args)?,
+        Commands::English(args) => english_word_snippet(args)?,
     };
+    let snippet = snippet
+        .with_id(notebook.new_snippet_id())
+        .with_tags(cli.tag.clone())
+        .with_location(cli.location.clone())
+        .with_region(cli.region);
+
+    println!("New snippet: {:?}", snippet);
+
+    if cli.append {
+        notebook.snippets.push(snippet);
+    } else {
+        notebook.snippets.insert(0, snippet);
+    }
+
+    println!("Saving notebook...");
+    notebook_to_yaml_file(&notebook, &config.notebook)?;
+    println!("Notebook saved");
+
+    Ok(())
 }
 
-fn english_word_snippet(args: &English) -> Snippet {
+fn english_word_snippet(args: &English) -> FennecResult<Snippet> {
     let (source, description, word_text) = match args {
         English::Page(page) => (
             Source::ManualPageNumber(page.number),
@@ -133,41 +158,55 @@ fn english_word_snippet(args: &English) -> Snippet {
     let word = match word_text {
         Some(word_text) => word_text.into(),
         // TODO: Make this required more gracefully via clap
-        None => panic!("Missing text argument"),
+        None => {
+            return Err(FennecError::Validation(
+                "English words need the word's text".into(),
+            ))
+        }
     };
     let words = vec![word];
 
     let source = Some(source);
 
-    Snippet {
+    Ok(Snippet {
         words,
         source,
         description,
         ..Snippet::starting_snippet()
-    }
+    })
 }
 
-fn tunic_word_snippet(args: &Tunic) -> Snippet {
-    let word = vec![DEFAULT_GLYPH].into();
-    let words = vec![word].into();
-
-    let (source, description) = match args {
+fn tunic_word_snippet(args: &Tunic) -> FennecResult<Snippet> {
+    let (source, description, word_text) = match args {
         Tunic::Page(page) => (
             Source::ManualPageNumber(page.number),
             page.description.clone(),
+            page.word_text.clone(),
         ),
         Tunic::Screenshot(screenshot) => (
             Source::ScreenshotFilename(screenshot.filename.clone()),
             screenshot.description.clone(),
+            screenshot.word_text.clone(),
         ),
-        Tunic::Other(other) => (Source::Other(other.text.clone()), other.description.clone()),
+        Tunic::Other(other) => (
+            Source::Other(other.text.clone()),
+            other.description.clone(),
+            other.word_text.clone(),
+        ),
+    };
+
+    let word: Word = match word_text {
+        Some(word_text) => word_text.parse::<TunicWord>()?.into(),
+        None => vec![DEFAULT_GLYPH].into(),
     };
+    let words = vec![word];
+
     let source = Some(source);
 
-    Snippet {
+    Ok(Snippet {
         words,
         source,
         description,
         ..Snippet::starting_snippet()
-    }
+    })
 }
@@ -2,22 +2,51 @@ use bevy::prelude::*;
 use bevy_egui::{egui, EguiContext, EguiPlugin};
 use egui::*;
 
+use fennec::prelude::{notebook_from_yaml_file, ConfigArgs, FennecConfig, SourceRegion};
+
 struct AppState {
     example_image: Handle<bevy::prelude::Image>,
     example_image2: Handle<bevy::prelude::Image>,
+    example_region: Option<SourceRegion>,
 }
 
 impl FromWorld for AppState {
     fn from_world(world: &mut World) -> Self {
+        let (image_path, example_region) = match first_snippet_region() {
+            Some((image_path, region)) => (image_path, Some(region)),
+            None => ("manual_pages/page01.jpg".to_owned(), None),
+        };
+
         let asset_server = world.get_resource_mut::<AssetServer>().unwrap();
 
         Self {
             example_image: asset_server.load("resources/dungeonfont.png"),
-            example_image2: asset_server.load("sources/manual_pages/page01.jpg"),
+            example_image2: asset_server.load(format!("sources/{}", image_path).as_str()),
+            example_region,
         }
     }
 }
 
+/// Images are only handed to egui on the first frame
+#[derive(Default)]
+struct RenderedTextures {
+    is_initialized: bool,
+    example_image: egui::TextureId,
+    example_image2: egui::TextureId,
+}
+
+/// The image and region of the first notebook snippet that has both
+fn first_snippet_region() -> Option<(String, SourceRegion)> {
+    let config = FennecConfig::from_args(&ConfigArgs::default()).ok()?;
+    let (notebook, _yaml) = notebook_from_yaml_file(&config.notebook).ok()?;
+
+    notebook.snippets.iter().find_map(|snippet| {
+        let image_path = snippet.source.as_ref()?.image_path()?;
+
+        snippet.region.map(|region| (image_path, region))
+    })
+}
+
 fn main() {
     App::new()
         .add_plugins(DefaultPlugins)
@@ -31,32 +60,54 @@ fn main() {
 fn ui_example_system(
     mut egui_ctx: ResMut<EguiContext>,
     app_state: Local<AppState>,
-    mut rendered_texture_id: Local<egui::TextureId>,
-    mut rendered_texture_id2: Local<egui::TextureId>,
-    mut is_initialized: Local<bool>,
+    images: Res<Assets<bevy::prelude::Image>>,
+    mut textures: Local<RenderedTextures>,
     mut word_size: Local<egui::Vec2>,
     mut glyph_size: Local<egui::Vec2>,
 ) {
-    if !*is_initialized {
+    if !textures.is_initialized {
         *word_size = egui::vec2(0.0, 1.0);
         *glyph_size = egui::Vec2::new(0.45, 0.85);
-        *is_initialized = true;
-        *rendered_texture_id = egui_ctx.add_image(app_state.example_image.clone_weak());
-        *rendered_texture_id2 = egui_ctx.add_image(app_state.example_image2.clone_weak());
+        *textures = RenderedTextures {
+            is_initialized: true,
+            example_image: egui_ctx.add_image(app_state.example_image.clone_weak()),
+            example_image2: egui_ctx.add_image(app_state.example_image2.clone_weak()),
+        };
     }
 
     egui::Window::new("Images").show(egui_ctx.ctx_mut(), |ui| {
         ui.label("images");
 
         ui.add(egui::widgets::Image::new(
-            *rendered_texture_id,
+            textures.example_image,
             [256.0, 256.0],
         ));
 
-        ui.add(egui::widgets::Image::new(
-            *rendered_texture_id2,
-            [256.0, 256.0],
+        let image_size = images
+            .get(&app_state.example_image2)
+            .map(|image| image.size());
+
+        let display_size = match image_size {
+            Some(size) if size.x > 0.0 => egui::vec2(256.0, 256.0 * size.y / size.x),
+            _ => egui::vec2(256.0, 256.0),
+        };
+
+        let response = ui.add(egui::widgets::Image::new(
+            textures.example_image2,
+            display_size,
         ));
+
+        if let (Some(region), Some(size)) = (app_state.example_region, image_size) {
+            if let Some((left, top, width, height)) = region.area_within(size.x as u32, size.y as u32) {
+                let image_rect = response.rect;
+                let highlight = egui::Rect::from_min_size(
+                    image_rect.min + egui::vec2(left * image_rect.width(), top * image_rect.height()),
+                    egui::vec2(width * image_rect.width(), height * image_rect.height()),
+                );
+
+                ui.painter().rect_stroke(highlight, 0.0, Stroke::new(2.0, Color32::RED));
+            }
+        }
     });
 
     egui::Window::new("Render Params").show(egui_ctx.ctx_mut(), |ui| {
@@ -1,4 +1,4 @@
-use clap::{Args, Parser, Subcommand};
+use clap::{Args, Parser, Subcommand, ValueEnum};
 use colored::Colorize;
 
 use fennec::prelude::*;
@@ -8,30 +8,43 @@ use fennec::prelude::*;
 struct Cli {
     #[command(subcommand)]
     command: Commands,
+
+    #[command(flatten)]
+    paths: ConfigArgs,
 }
 
 #[derive(Subcommand)]
 enum Commands {
-    /// Create a new dictionary file. (Default: dictionary.yaml)
+    /// Create a new dictionary file. (Default: dictionary.yaml, or the one set in fennec.toml)
     Init,
     /// Search for a word's definition
     Word(WordCmd),
-    /// Add a new definition
+    /// Add a new definition, or another candidate reading for a word already defined
     Add(AddCmd),
+    /// Confirm one of a word's candidate readings
+    Confirm(ConfirmCmd),
+    /// Search for a phrase's definition
+    Phrase(PhraseCmd),
+    /// Define a phrase spanning several words
+    AddPhrase(AddPhraseCmd),
+    /// Find the Tunic words whose definitions contain some English text
+    Reverse(ReverseCmd),
 }
 
 #[derive(Args)]
 struct WordCmd {
-    /// The Tunic word to search for as a space-delimited sequence of integer values for its glyphs
-    /// Example: 1 27 339
-    glyphs: Vec<u16>,
+    /// The Tunic word to search for as a space-delimited sequence of glyphs in hex, segment list,
+    /// phoneme or integer notation
+    /// Example: 0x1100 1,3,9+15 fɑ 339
+    glyphs: Vec<Glyph>,
 }
 
 #[derive(Args)]
 struct AddCmd {
-    /// The Tunic word to be defined as a space-delimited sequence of integer values for its glyphs
-    /// Example: 1 27 339
-    glyphs: Vec<u16>,
+    /// The Tunic word to be defined as a space-delimited sequence of glyphs in hex, segment list,
+    /// phoneme or integer notation
+    /// Example: 0x1100 1,3,9+15 fɑ 339
+    glyphs: Vec<Glyph>,
 
     /// The new word's definition
     #[arg(short, long)]
@@ -41,16 +54,91 @@ struct AddCmd {
     #[arg(short, long, action = clap::ArgAction::Append, num_args(1))]
     note: Vec<String>,
 
-    /// Set the definition type. Options: tenative, confirmed, undefined. Default: tentative.
-    #[arg(short, long, name = "DEFINITION TYPE")]
-    _type: Option<String>,
+    /// Set the definition type
+    #[arg(short, long, name = "DEFINITION TYPE", value_enum)]
+    #[arg(default_value_t = DefinitionType::Tentative)]
+    _type: DefinitionType,
+
+    /// How sure you are of this reading, from 0 to 100
+    #[arg(short, long, value_parser = clap::value_parser!(u8).range(0..=100))]
+    confidence: Option<u8>,
+
+    /// Who proposed this reading
+    #[arg(short, long)]
+    author: Option<String>,
+
+    /// When this reading was proposed
+    /// Example: 2023-03-14
+    #[arg(long)]
+    date: Option<String>,
+
+    /// ID of a notebook snippet that supports this reading. Supports multiple uses.
+    #[arg(short, long, action = clap::ArgAction::Append, num_args(1))]
+    snippet: Vec<SnippetId>,
+}
+
+#[derive(Args)]
+struct ConfirmCmd {
+    /// The Tunic word as a space-delimited sequence of glyphs in hex, segment list, phoneme or
+    /// integer notation
+    /// Example: 0x1100 1,3,9+15 fɑ 339
+    glyphs: Vec<Glyph>,
+
+    /// Rank of the candidate to confirm, as listed by `define word`
+    #[arg(short, long, default_value_t = 1)]
+    rank: usize,
+}
+
+#[derive(Args)]
+struct ReverseCmd {
+    /// English words to look for in tentative and confirmed definitions, ignoring case.
+    /// Each one may be the start of a longer word.
+    /// Example: sword
+    #[arg(required = true)]
+    text: Vec<String>,
 }
 
+#[derive(Args)]
+struct PhraseCmd {
+    /// The Tunic words of the phrase separated by `/`, each a space-delimited sequence of
+    /// glyphs in hex, segment list, phoneme or integer notation
+    /// Example: "fɑ 1,3 / 339"
+    phrase: DictionaryPhrase,
+}
 
-fn initialize_dictionary() {
+#[derive(Args)]
+struct AddPhraseCmd {
+    /// The Tunic words of the phrase separated by `/`, each a space-delimited sequence of
+    /// glyphs in hex, segment list, phoneme or integer notation
+    /// Example: "fɑ 1,3 / 339"
+    phrase: DictionaryPhrase,
+
+    /// The phrase's definition, which is shown in place of its words' definitions
+    #[arg(short, long)]
+    definition: String,
+
+    /// Attach a note to the phrase. Supports multiple uses.
+    #[arg(short, long, action = clap::ArgAction::Append, num_args(1))]
+    note: Vec<String>,
+
+    /// Set the definition type
+    #[arg(short, long, name = "DEFINITION TYPE", value_enum)]
+    #[arg(default_value_t = DefinitionType::Tentative)]
+    _type: DefinitionType,
+}
+
+#[derive(Clone, ValueEnum)]
+enum DefinitionType {
+    Tentative,
+    Confirmed,
+    Undefined,
+}
+
+
+fn initialize_dictionary(config: &FennecConfig) -> FennecResult<()> {
     println!(
         "Initializing dictionary file: {}...",
-        DEFAULT_DICTIONARY_FILE
+        config.dictionary
     );
 
     let tunic_word: TunicWord = vec![DEFAULT_GLYPH, DEFAULT_GLYPH, DEFAULT_GLYPH].into();
@@ -59,68 +147,231 @@ fn initialize_dictionary() {
         .with_new_definition(&tunic_word, "An example Tunic word entry".into())
         .with_annotation(&tunic_word, "Example Note".into());
 
-    let yaml = serde_yaml::to_string(&dictionary).expect("Unable to serialize entry");
-    dictionary_to_yaml_file(&dictionary, DEFAULT_DICTIONARY_FILE).expect("Unable to save file");
+    let yaml = dictionary_to_yaml_file(&dictionary, &config.dictionary)?;
 
     println!("YAML output:");
     println!("{}", yaml);
 
     println!("Initialized dictionary file");
+
+    Ok(())
 }
 
-fn search_for_word(cmd: WordCmd) {
+fn search_for_word(cmd: WordCmd, config: &FennecConfig) -> FennecResult<()> {
     let word: TunicWord = cmd.glyphs.into();
     let dict_word: DictionaryWord = (&word).into();
     let readable_word: String = format_word_for_reading(&word);
 
+    let phoneme_table = phoneme_table_or_default(&config.phonemes)?;
+    let pronunciation = word.pronunciation(&phoneme_table);
+
     println!("Loading dictionary...");
 
-    match dictionary_from_yaml_file(DEFAULT_DICTIONARY_FILE) {
-        Ok((dictionary, _yaml)) => {
+    let (dictionary, _yaml) = dictionary_from_yaml_file(&config.dictionary)?;
+
+    println!(
+        "Searching the dictionary for word {} ...",
+        readable_word.green()
+    );
+
+    if let Some(entry) = dictionary.get(&dict_word) {
+        let definition: String = match entry.definition() {
+            Definition::Undefined => "Undefined".into(),
+            Definition::Tentative(text) => text,
+            Definition::Confirmed(text) => text,
+        };
+
+        println!("-----");
+        println!("  {}: {}", readable_word.green().bold(), definition.bold());
+        println!("  Pronounced: {}", pronunciation);
+        println!();
+        println!("  Candidates:");
+        for (index, candidate) in entry.candidates().iter().enumerate() {
+            let confirmed = if candidate.is_confirmed() { " (confirmed)" } else { "" };
+
             println!(
-                "Searching the dictionary for word {} ...",
-                readable_word.green()
+                "    {}. {} {}%{}",
+                index + 1,
+                candidate.text(),
+                candidate.confidence(),
+                confirmed
             );
 
-            if let Some(entry) = dictionary.get(&dict_word) {
-                let definition: String = match entry.definition() {
-                    Definition::Undefined => "Undefined".into(),
-                    Definition::Tentative(text) => text.clone(),
-                    Definition::Confirmed(text) => text.clone(),
-                };
-
-                println!("-----");
-                println!("  {}: {}", readable_word.green().bold(), definition.bold());
-                println!();
-                println!("  Notes:");
-                for note in entry.notes().iter() {
-                    println!("    - {}", note.as_text());
-                }
-                println!("-----");
-            } else {
-                println!("Word not found");
+            if let Some(author) = candidate.author() {
+                println!("       By: {}", author);
+            }
+            if let Some(date) = candidate.date() {
+                println!("       Date: {}", date);
+            }
+            if !candidate.snippets().is_empty() {
+                let snippets: Vec<String> =
+                    candidate.snippets().iter().map(SnippetId::to_reference).collect();
+
+                println!("       Snippets: {}", snippets.join(", "));
             }
         }
-        Err(error) => {
-            println!(
-                "Unable to load dictionary file: {}",
-                DEFAULT_DICTIONARY_FILE
-            );
-            println!("{:?}", error);
+        println!();
+        println!("  Notes:");
+        for note in entry.notes().iter() {
+            println!("    - {}", note.as_text());
+        }
+        println!("-----");
+    } else {
+        println!("Word not found");
+    }
+
+    Ok(())
+}
+
+fn add_definition(args: AddCmd, config: &FennecConfig) -> FennecResult<()> {
+    let candidate = match args._type {
+        DefinitionType::Undefined => None,
+        DefinitionType::Tentative => Some(Candidate::new(&args.definition)),
+        DefinitionType::Confirmed => Some(
+            Candidate::new(&args.definition)
+                .with_confidence(100)
+                .with_confirmed_as(true),
+        ),
+    };
+
+    let candidate = candidate.map(|candidate| {
+        let candidate = candidate
+            .with_author(args.author.clone())
+            .with_date(args.date.clone())
+            .with_snippets(args.snippet.clone());
+
+        match args.confidence {
+            Some(confidence) => candidate.with_confidence(confidence),
+            None => candidate,
+        }
+    });
+
+    let word: TunicWord = args.glyphs.into();
+    let word: DictionaryWord = word.into();
+
+    if !args.snippet.is_empty() {
+        println!("Loading notebook...");
+
+        let (notebook, _yaml) = notebook_from_yaml_file(&config.notebook)?;
+
+        if let Some(id) = args.snippet.iter().find(|id| notebook.position_of(id).is_none()) {
+            return Err(FennecError::Validation(format!("There is no snippet {}", id)));
+        }
+    }
+
+    println!("Loading dictionary...");
+
+    let (dictionary, _yaml) = dictionary_from_yaml_file(&config.dictionary)?;
+
+    println!("Adding definition...");
+
+    if candidate.is_none() && args.note.is_empty() && dictionary.get(&word).is_some() {
+        return Err(FennecError::Validation(format!(
+            "A definition already exists for {word}"
+        )));
+    }
+
+    let entry = dictionary.get(&word).cloned().unwrap_or_default();
+
+    if let Some(text) = candidate.as_ref().map(Candidate::text) {
+        if entry.candidates().iter().any(|existing| existing.text() == text) {
+            return Err(FennecError::Validation(format!(
+                "{word} already has the candidate \"{text}\""
+            )));
         }
+    }
+
+    let entry = match candidate {
+        Some(candidate) => entry.with_candidate(candidate),
+        None => entry,
     };
+
+    let entry = args
+        .note
+        .iter()
+        .fold(entry, |entry, text| entry.with_note(Note(text.to_string())));
+
+    let dictionary = dictionary.with_new_complete_definition(&word, &entry);
+    dictionary_to_yaml_file(&dictionary, &config.dictionary)?;
+
+    println!("Definition added for {word}: {entry}");
+
+    Ok(())
 }
 
-fn add_definition(args: AddCmd) {
-    let definition = args.definition;
+fn confirm_candidate(args: ConfirmCmd, config: &FennecConfig) -> FennecResult<()> {
+    let word: TunicWord = args.glyphs.into();
+    let word: DictionaryWord = word.into();
+
+    println!("Loading dictionary...");
+
+    let (dictionary, _yaml) = dictionary_from_yaml_file(&config.dictionary)?;
+
+    let entry = dictionary
+        .get(&word)
+        .ok_or_else(|| FennecError::Validation(format!("No definition exists for {word}")))?;
+
+    let index = args
+        .rank
+        .checked_sub(1)
+        .filter(|index| *index < entry.candidates().len())
+        .ok_or_else(|| {
+            FennecError::Validation(format!(
+                "{word} has {} candidate(s), so there is no rank {}",
+                entry.candidates().len(),
+                args.rank
+            ))
+        })?;
+
+    if entry.candidates()[index].is_confirmed() {
+        println!("Already confirmed");
+
+        return Ok(());
+    }
+
+    let entry = entry.clone().with_confirmation_toggled_at(index);
+    let dictionary = dictionary.with_new_complete_definition(&word, &entry);
+    dictionary_to_yaml_file(&dictionary, &config.dictionary)?;
+
+    println!("Confirmed for {word}: {entry}");
+
+    Ok(())
+}
+
+fn search_for_phrase(cmd: PhraseCmd, config: &FennecConfig) -> FennecResult<()> {
+    println!("Loading dictionary...");
+
+    let (dictionary, _yaml) = dictionary_from_yaml_file(&config.dictionary)?;
+
+    println!("Searching the dictionary for phrase {} ...", cmd.phrase.to_string().green());
+
+    if let Some(entry) = dictionary.get_phrase(&cmd.phrase) {
+        let definition: String = match entry.definition() {
+            Definition::Undefined => "Undefined".into(),
+            Definition::Tentative(text) => text,
+            Definition::Confirmed(text) => text,
+        };
+
+        println!("-----");
+        println!("  [{}]: {}", cmd.phrase.to_string().green().bold(), definition.bold());
+        println!();
+        println!("  Notes:");
+        for note in entry.notes().iter() {
+            println!("    - {}", note.as_text());
+        }
+        println!("-----");
+    } else {
+        println!("Phrase not found");
+    }
 
-    let def_type = args._type.unwrap_or("tentative".to_owned());
+    Ok(())
+}
 
-    let definition = match def_type.as_ref() {
-        "tentative" => Definition::Tentative(definition.to_owned()),
-        "confirmed" => Definition::Confirmed(definition.to_owned()),
-        "undefined" => Definition::Undefined,
-        _ => panic!("Unrecognized definition type: {def_type}"),
+fn add_phrase(args: AddPhraseCmd, config: &FennecConfig) -> FennecResult<()> {
+    let definition = match args._type {
+        DefinitionType::Tentative => Definition::Tentative(args.definition),
+        DefinitionType::Confirmed => Definition::Confirmed(args.definition),
+        DefinitionType::Undefined => Definition::Undefined,
     };
 
     let notes = args
@@ -131,39 +382,70 @@ fn add_definition(args: AddCmd) {
 
     let entry = Entry::new(definition, notes);
 
-    let word: TunicWord = args.glyphs.into();
-    let word: DictionaryWord = word.into();
+    println!("Loading dictionary...");
+
+    let (dictionary, _yaml) = dictionary_from_yaml_file(&config.dictionary)?;
+
+    println!("Adding phrase...");
+
+    if dictionary.get_phrase(&args.phrase).is_some() {
+        return Err(FennecError::Validation(format!(
+            "A definition already exists for the phrase {}",
+            args.phrase
+        )));
+    }
+
+    let dictionary = dictionary.with_phrase_entry(&args.phrase, &entry);
+    dictionary_to_yaml_file(&dictionary, &config.dictionary)?;
+
+    println!("Definition added for phrase {}: {entry}", args.phrase);
+
+    Ok(())
+}
+
+fn reverse_search(cmd: ReverseCmd, config: &FennecConfig) -> FennecResult<()> {
+    let text = cmd.text.join(" ");
 
     println!("Loading dictionary...");
 
-    match dictionary_from_yaml_file(DEFAULT_DICTIONARY_FILE) {
-        Ok((dictionary, _yaml)) => {
-            println!("Adding definition...");
-
-            match dictionary.get(&word) {
-                None => {
-                    let dictionary = dictionary.with_new_complete_definition(&word, &entry);
-                    dictionary_to_yaml_file(&dictionary, DEFAULT_DICTIONARY_FILE).expect("Unable to save file");
-                    println!("Definition added for {word}: {entry}");
-                },
-                Some(_existing_def) => panic!("A definition already exists for {word}. Aborting"),
-            };
-        }
-        Err(error) => {
-            println!(
-                "Unable to load dictionary file: {}",
-                DEFAULT_DICTIONARY_FILE
-            );
-            println!("{:?}", error);
-        }
-    };
+    let (dictionary, _yaml) = dictionary_from_yaml_file(&config.dictionary)?;
+
+    println!("Searching the dictionary for definitions of {} ...", text.green());
+
+    let words = ReverseIndex::new(&dictionary).lookup(&text);
+
+    if words.is_empty() {
+        println!("No definitions found");
+
+        return Ok(());
+    }
+
+    println!("-----");
+    for word in words.iter() {
+        let (definition_type, definition) = match dictionary.get(word).map(Entry::definition) {
+            Some(Definition::Tentative(text)) => ("Tentative", text),
+            Some(Definition::Confirmed(text)) => ("Confirmed", text),
+            _ => continue,
+        };
+        let readable_word = format_word_for_reading(&word.glyphs().into());
+
+        println!(
+            "  {}: {} ({})",
+            readable_word.green().bold(),
+            definition.bold(),
+            definition_type
+        );
+    }
+    println!("-----");
+
+    Ok(())
 }
 
 fn format_word_for_reading(word: &TunicWord) -> String {
     word
         .glyphs()
         .iter()
-        .map(|glyph| glyph.0.to_string())
+        .map(|glyph| glyph.to_string())
         .reduce(|word, glyph_value| word + " " + &glyph_value)
         .map_or("(Empty)".into(), |word| format!("[{}]", word))
 }
@@ -171,9 +453,19 @@ fn format_word_for_reading(word: &TunicWord) -> String {
 fn main() {
     let cli = Cli::parse();
 
-    match cli.command {
-        Commands::Add(cmd) => add_definition(cmd),
-        Commands::Init => initialize_dictionary(),
-        Commands::Word(cmd) => search_for_word(cmd),
+    let config = FennecConfig::from_args(&cli.paths).unwrap_or_else(|error| error.exit());
+
+    let result = match cli.command {
+        Commands::Add(cmd) => add_definition(cmd, &config),
+        Commands::Confirm(cmd) => confirm_candidate(cmd, &config),
+        Commands::Phrase(cmd) => search_for_phrase(cmd, &config),
+        Commands::AddPhrase(cmd) => add_phrase(cmd, &config),
+        Commands::Init => initialize_dictionary(&config),
+        Commands::Word(cmd) => search_for_word(cmd, &config),
+        Commands::Reverse(cmd) => reverse_search(cmd, &config),
+    };
+
+    if let Err(error) = result {
+        error.exit();
     }
 }
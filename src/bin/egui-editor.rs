@@ -0,0 +1,645 @@
+use bevy::prelude::{
+    default, App, AssetPlugin, AssetServer, Assets, DefaultPlugins, Handle, Image, Input, KeyCode,
+    PluginGroup, Res, ResMut, Resource, WindowDescriptor, WindowPlugin,
+};
+use bevy_egui::{egui, EguiContext, EguiPlugin};
+use clap::Parser;
+use std::fs;
+use std::time::Instant;
+
+use fennec::prelude::*;
+
+const GLYPH_SIZE: egui::Vec2 = egui::Vec2 { x: 24.0, y: 45.0 };
+const GLYPH_STROKE_WIDTH: f32 = 2.0;
+const WORD_PADDING: f32 = 4.0;
+
+/// How far from a segment, in points, the pointer still hovers it
+const SEGMENT_HOVER_TOLERANCE: f32 = 4.0;
+
+/// How much one scrolled point zooms the source image
+const ZOOM_SPEED: f32 = 0.002;
+
+/// Edit the notebook next to the snippet's source image, with the same keys as
+/// the terminal editor apart from the dictionary's. Click a segment of the
+/// selected glyph to toggle it. Drag the image to pan and scroll to zoom.
+#[derive(Parser)]
+#[command(author, version, about)]
+struct Cli {
+    #[command(flatten)]
+    paths: ConfigArgs,
+}
+
+#[derive(Resource)]
+struct EguiEditor {
+    file_editor: FileEditor,
+    dictionary: Dictionary,
+    dictionary_file: String,
+    phoneme_table: PhonemeTable,
+    file_watcher: FileWatcher,
+    last_watched: Instant,
+    viewport: ImageViewport,
+    shown_snippet: Option<SnippetId>,
+    shown_image_path: Option<String>,
+    /// Holding the handle keeps the image loaded
+    source_image: Option<Handle<Image>>,
+    /// Set until the viewport has been zoomed to a newly shown snippet's region,
+    /// which waits for its image to load
+    focus_pending: bool,
+}
+
+impl EguiEditor {
+    fn apply_input(&mut self, input: KeyInput) {
+        let event = self
+            .file_editor
+            .on_input(Box::new(move |editor| on_file_editor_input(editor, &input)));
+
+        if event != EditorEvent::NoOp {
+            self.file_editor = self.file_editor.clone().apply(event);
+        }
+    }
+
+    /// Changes to the notebook are asked about like in the terminal editor. The
+    /// dictionary isn't edited here, so it is just replaced.
+    fn reload_changed_files(&mut self) {
+        if self.last_watched.elapsed() < FILE_WATCH_INTERVAL {
+            return;
+        }
+
+        self.last_watched = Instant::now();
+
+        for file in self.file_watcher.changed_files() {
+            if file == self.file_editor.target_file() {
+                match notebook_from_yaml_file(&file) {
+                    Ok((notebook, _yaml)) => {
+                        let event = EditorEvent::ReportNotebookChangedOnDisk(notebook);
+                        self.file_editor = self.file_editor.clone().apply(event);
+                    }
+                    Err(error) => eprintln!("error: Failed to reload notebook: {}", error),
+                }
+            } else if file == self.dictionary_file {
+                match dictionary_from_yaml_file(&file) {
+                    Ok((dictionary, _yaml)) => self.dictionary = dictionary,
+                    Err(error) => eprintln!("error: Failed to reload dictionary: {}", error),
+                }
+            }
+        }
+    }
+
+    fn show_snippet(&mut self, snippet: Option<&Snippet>, asset_server: &AssetServer) {
+        let id = snippet.map(|snippet| snippet.id.clone());
+        let image_path = snippet
+            .and_then(|snippet| snippet.source.as_ref())
+            .and_then(Source::image_path);
+
+        if image_path != self.shown_image_path {
+            self.source_image = image_path.as_ref().map(|path| asset_server.load(path.as_str()));
+            self.shown_image_path = image_path;
+            self.focus_pending = true;
+        }
+
+        if id != self.shown_snippet {
+            self.shown_snippet = id;
+            self.focus_pending = true;
+        }
+    }
+}
+
+fn main() {
+    let cli = Cli::parse();
+
+    let config = FennecConfig::from_args(&cli.paths).unwrap_or_else(|error| error.exit());
+
+    let (dictionary, _yaml) =
+        dictionary_from_yaml_file(&config.dictionary).unwrap_or_else(|error| error.exit());
+
+    let phoneme_table =
+        phoneme_table_or_default(&config.phonemes).unwrap_or_else(|error| error.exit());
+
+    let notebook: Notebook = vec![Snippet::starting_snippet()].into();
+    let file_editor = FileEditor::new(notebook, &config.notebook)
+        .apply(EditorEvent::ConfirmLoadFromFileRequest);
+
+    // Bevy finds relative asset folders next to the executable, not the project
+    let sources = fs::canonicalize(&config.sources)
+        .map_or(config.sources.clone(), |path| path.to_string_lossy().into_owned());
+
+    // The same window size as the bracket-lib editor, from `fennec.toml`
+    let window_width = (config.window.width * config.window.tile_width) as f32;
+    let window_height = (config.window.height * config.window.tile_height) as f32;
+
+    let editor = EguiEditor {
+        file_editor,
+        dictionary,
+        dictionary_file: config.dictionary.clone(),
+        phoneme_table,
+        file_watcher: FileWatcher::new(&[&config.notebook, &config.dictionary]),
+        last_watched: Instant::now(),
+        viewport: ImageViewport::default(),
+        shown_snippet: None,
+        shown_image_path: None,
+        source_image: None,
+        focus_pending: false,
+    };
+
+    App::new()
+        .add_plugins(
+            DefaultPlugins
+                .set(AssetPlugin {
+                    asset_folder: sources,
+                    ..default()
+                })
+                .set(WindowPlugin {
+                    window: WindowDescriptor {
+                        title: "Tunic Language Toolkit".into(),
+                        width: window_width,
+                        height: window_height,
+                        ..default()
+                    },
+                    ..default()
+                }),
+        )
+        .add_plugin(EguiPlugin)
+        .insert_resource(editor)
+        .add_system(editor_system)
+        .run();
+}
+
+fn editor_system(
+    mut egui_context: ResMut<EguiContext>,
+    mut editor: ResMut<EguiEditor>,
+    keys: Res<Input<KeyCode>>,
+    asset_server: Res<AssetServer>,
+    images: Res<Assets<Image>>,
+) {
+    let editor = &mut *editor;
+
+    editor.reload_changed_files();
+
+    // Confirmed loads and saves go ahead on the next frame, key or no key
+    let mut inputs = key_inputs(&keys);
+
+    if inputs.is_empty() {
+        inputs.push(KeyInput::default());
+    }
+
+    for input in inputs {
+        editor.apply_input(input);
+    }
+
+    let view = editor.file_editor.to_view(&editor.dictionary);
+    let selected_snippet_view = view
+        .notebook_view
+        .snippet_views
+        .iter()
+        .find(|snippet_view| snippet_view.selected);
+
+    editor.show_snippet(
+        selected_snippet_view.map(|snippet_view| &snippet_view.snippet),
+        &asset_server,
+    );
+
+    let texture = editor.source_image.as_ref().and_then(|handle| {
+        images.get(handle).map(|image| {
+            let size = image.size();
+
+            (egui_context.add_image(handle.clone_weak()), (size.x, size.y))
+        })
+    });
+    let region = selected_snippet_view.and_then(|snippet_view| snippet_view.snippet.region);
+
+    let ctx = egui_context.ctx_mut().clone();
+
+    egui::TopBottomPanel::bottom("status").show(&ctx, |ui| status_ui(ui, &view));
+
+    egui::SidePanel::right("source_image")
+        .resizable(true)
+        .default_width(ctx.available_rect().width() / 2.0)
+        .show(&ctx, |ui| source_image_ui(ui, editor, texture, region));
+
+    let event = egui::CentralPanel::default()
+        .show(&ctx, |ui| {
+            notebook_ui(ui, &view.notebook_view, &editor.dictionary, &editor.phoneme_table)
+        })
+        .inner;
+
+    if event != EditorEvent::NoOp {
+        editor.file_editor = editor.file_editor.clone().apply(event);
+    }
+}
+
+/// Keys pressed since the last frame, as the terminal editor would see them
+fn key_inputs(keys: &Input<KeyCode>) -> Vec<KeyInput> {
+    let shift = keys.any_pressed([KeyCode::LShift, KeyCode::RShift]);
+    let control = keys.any_pressed([KeyCode::LControl, KeyCode::RControl]);
+
+    keys.get_just_pressed()
+        .filter_map(|key| virtual_key_code(*key))
+        .map(|key| KeyInput {
+            key: Some(key),
+            shift,
+            control,
+        })
+        .collect()
+}
+
+macro_rules! same_key_codes {
+    ($key:expr, $($name:ident),* $(,)?) => {
+        match $key {
+            $(KeyCode::$name => Some(VirtualKeyCode::$name),)*
+            _ => None,
+        }
+    };
+}
+
+/// Bevy and bracket-lib both name their keys after winit's. Only the keys the
+/// editors listen for are translated, leaving out F5 to F7, which are for the
+/// dictionary editor this editor doesn't have.
+fn virtual_key_code(key: KeyCode) -> Option<VirtualKeyCode> {
+    same_key_codes!(
+        key, A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z, Key0,
+        Key1, Key2, Key3, Key4, Key5, Key6, Key7, Key8, Key9, F1, F2, F3, F4, F8, F9, F10, F11,
+        F12, Up, Down, Left, Right, Home, End, Back, Delete, Return, Escape, Tab, Space, Plus,
+        Minus, Equals, Comma, Period, Slash, Backslash, Semicolon, Apostrophe, LBracket, RBracket,
+        Grave,
+    )
+}
+
+fn color((r, g, b): (u8, u8, u8)) -> egui::Color32 {
+    egui::Color32::from_rgb(r, g, b)
+}
+
+fn status_ui(ui: &mut egui::Ui, view: &FileEditorView) {
+    for (line_color, text) in file_state_lines(&view.state, "notebook", &view.target_file) {
+        ui.colored_label(color(line_color), text);
+    }
+
+    let notebook_view = &view.notebook_view;
+    let help_text = match notebook_view.state {
+        NotebookEditorState::SelectingSnippet => filters_help_text(&notebook_view.filters),
+        NotebookEditorState::EditingSnippet => {
+            "Tab: Details  Esc: Snippets  F2: Save  F3: Load  Ctrl+Z: Undo".to_owned()
+        }
+        NotebookEditorState::EditingSnippetDetails => notebook_view
+            .snippet_views
+            .iter()
+            .find(|snippet_view| snippet_view.selected)
+            .map_or(String::new(), |snippet_view| {
+                snippet_details_help_text(snippet_view).to_owned()
+            }),
+    };
+
+    ui.colored_label(color(GRAY40), help_text);
+}
+
+fn notebook_ui(
+    ui: &mut egui::Ui,
+    notebook_view: &NotebookView,
+    dictionary: &Dictionary,
+    phoneme_table: &PhonemeTable,
+) -> EditorEvent {
+    let selected_snippet_view = notebook_view
+        .snippet_views
+        .iter()
+        .find(|snippet_view| snippet_view.selected);
+
+    match (&notebook_view.state, selected_snippet_view) {
+        (NotebookEditorState::SelectingSnippet, _) => snippet_list_ui(ui, notebook_view),
+        (NotebookEditorState::EditingSnippet, Some(snippet_view)) => {
+            return snippet_ui(ui, snippet_view, dictionary, phoneme_table);
+        }
+        (NotebookEditorState::EditingSnippetDetails, Some(snippet_view)) => {
+            snippet_details_ui(ui, snippet_view)
+        }
+        _ => {}
+    }
+
+    EditorEvent::NoOp
+}
+
+fn snippet_list_ui(ui: &mut egui::Ui, notebook_view: &NotebookView) {
+    egui::ScrollArea::vertical().show(ui, |ui| {
+        for snippet_view in notebook_view.snippet_views.iter().filter(|view| view.retained) {
+            let description = format!(
+                "{}  {}",
+                snippet_view.snippet.id.to_reference(),
+                snippet_view.snippet.description
+            );
+            let description_color = color(description_color_for(snippet_view));
+            let response = ui.colored_label(description_color, description);
+
+            ui.small(snippet_source_to_label(snippet_view));
+
+            if snippet_view.selected {
+                response.scroll_to_me(Some(egui::Align::Center));
+            }
+        }
+    });
+}
+
+fn snippet_ui(
+    ui: &mut egui::Ui,
+    snippet_view: &SnippetView,
+    dictionary: &Dictionary,
+    phoneme_table: &PhonemeTable,
+) -> EditorEvent {
+    ui.heading(snippet_view.snippet.description.as_str());
+    ui.separator();
+
+    let mut event = EditorEvent::NoOp;
+
+    ui.horizontal_wrapped(|ui| {
+        for word_view in snippet_view.word_views.iter() {
+            let word_event = word_ui(ui, word_view);
+
+            if word_event != EditorEvent::NoOp {
+                event = word_event;
+            }
+        }
+    });
+
+    ui.separator();
+
+    let words = &snippet_view.snippet.words;
+
+    labelled_row(ui, "Translation", translation_of(words, dictionary));
+
+    let selected_word = snippet_view.word_views.iter().find(|word_view| word_view.selected);
+
+    if let Some(WordType::Tunic(tunic_word)) = selected_word.map(|view| &view.word.word_type) {
+        let definition = match dictionary.get(&tunic_word.into()).map(Entry::definition) {
+            Some(Definition::Confirmed(text)) | Some(Definition::Tentative(text)) => text,
+            _ => "(pending)".to_owned(),
+        };
+
+        labelled_row(ui, "Pronounced", tunic_word.pronunciation(phoneme_table));
+        labelled_row(ui, "Definition", definition);
+    }
+
+    labelled_row(ui, "Transcribed", snippet_view.transcribed.to_string());
+
+    event
+}
+
+fn labelled_row(ui: &mut egui::Ui, label: &str, text: String) {
+    ui.horizontal(|ui| {
+        ui.colored_label(color(GREEN), format!("{}:", label));
+        ui.label(text);
+    });
+}
+
+fn word_ui(ui: &mut egui::Ui, word_view: &WordView) -> EditorEvent {
+    ui.vertical(|ui| {
+        let event = match &word_view.word.word_type {
+            WordType::Tunic(_) => tunic_word_ui(ui, word_view),
+            WordType::English(english_word) => {
+                let text = match &word_view.line_editor_view {
+                    Some(line_editor_view) => line_editor_text(line_editor_view),
+                    None => english_word.text(),
+                };
+                let text_color = if word_view.selected { YELLOW } else { WHITE };
+
+                ui.label(egui::RichText::new(text).monospace().color(color(text_color)));
+
+                EditorEvent::NoOp
+            }
+        };
+
+        let definition = match &word_view.definition {
+            Definition::Confirmed(text) | Definition::Tentative(text) => text.clone(),
+            Definition::Undefined => String::new(),
+        };
+
+        ui.small(definition);
+
+        event
+    })
+    .inner
+}
+
+/// Hovering a segment of the selected glyph previews what clicking it would do
+fn tunic_word_ui(ui: &mut egui::Ui, word_view: &WordView) -> EditorEvent {
+    let glyph_count = word_view.glyph_views.len().max(1) as f32;
+    let (_, circle_radius) = reversal_circle(GLYPH_SIZE.x, GLYPH_SIZE.y);
+    let size = egui::vec2(
+        GLYPH_SIZE.x * glyph_count + WORD_PADDING * 2.0,
+        GLYPH_SIZE.y + circle_radius * 2.0 + WORD_PADDING * 2.0,
+    );
+
+    let editing_glyph =
+        word_view.selected && word_view.glyph_views.iter().any(|view| view.selected);
+    let sense = if editing_glyph { egui::Sense::click() } else { egui::Sense::hover() };
+
+    let (rect, response) = ui.allocate_exact_size(size, sense);
+    let painter = ui.painter();
+    let mut hovered_segment = None;
+    let word_color = if word_view.word.colored() { RED } else { WHITE };
+
+    if word_view.selected {
+        painter.rect_filled(rect, 2.0, color(GRAY15));
+    }
+
+    if word_view.word.has_border() {
+        painter.rect_stroke(rect, 0.0, egui::Stroke::new(1.0, color(word_color)));
+    }
+
+    for (index, glyph_view) in word_view.glyph_views.iter().enumerate() {
+        let glyph_rect = egui::Rect::from_min_size(
+            rect.min + egui::vec2(WORD_PADDING + index as f32 * GLYPH_SIZE.x, WORD_PADDING),
+            GLYPH_SIZE,
+        );
+        let glyph_color = if word_view.selected && glyph_view.selected {
+            YELLOW
+        } else {
+            word_color
+        };
+
+        // Drawn as stored, like the terminal editor, so the word line can be toggled too
+        let glyph = glyph_view.glyph;
+
+        paint_glyph(
+            painter,
+            glyph_rect,
+            &glyph,
+            egui::Stroke::new(GLYPH_STROKE_WIDTH, color(glyph_color)),
+        );
+
+        if !(editing_glyph && glyph_view.selected) {
+            continue;
+        }
+
+        hovered_segment = response.hover_pos().and_then(|position| {
+            let position = position - glyph_rect.min;
+
+            segment_at(
+                (position.x, position.y),
+                GLYPH_SIZE.x,
+                GLYPH_SIZE.y,
+                SEGMENT_HOVER_TOLERANCE,
+            )
+        });
+
+        if let Some(segment) = hovered_segment {
+            let preview_color = if glyph.segments().contains(&segment) { RED } else { GRAY60 };
+            let stroke = egui::Stroke::new(GLYPH_STROKE_WIDTH, color(preview_color));
+
+            paint_segment(painter, glyph_rect, segment, stroke);
+        }
+    }
+
+    on_glyph_mouse_input(hovered_segment, response.clicked())
+}
+
+fn paint_glyph(painter: &egui::Painter, rect: egui::Rect, glyph: &Glyph, stroke: egui::Stroke) {
+    for segment in glyph.segments() {
+        paint_segment(painter, rect, segment, stroke);
+    }
+}
+
+fn paint_segment(
+    painter: &egui::Painter,
+    rect: egui::Rect,
+    segment: Segment,
+    stroke: egui::Stroke,
+) {
+    let point = |(x, y): (f32, f32)| rect.min + egui::vec2(x * rect.width(), y * rect.height());
+
+    if segment == REVERSAL_SEGMENT {
+        let ((x, y), radius) = reversal_circle(rect.width(), rect.height());
+
+        painter.circle_stroke(rect.min + egui::vec2(x, y), radius, stroke);
+    } else if let Some((_, start, end)) = SEGMENT_LINES.iter().find(|line| line.0 == segment) {
+        painter.line_segment([point(*start), point(*end)], stroke);
+    }
+}
+
+fn snippet_details_ui(ui: &mut egui::Ui, snippet_view: &SnippetView) {
+    egui::Grid::new("snippet_details")
+        .num_columns(2)
+        .spacing([12.0, 8.0])
+        .show(ui, |ui| {
+            for (field, label, text) in snippet_detail_fields(snippet_view) {
+                let selected = field == snippet_view.selected_field;
+                let label_color = if selected { YELLOW } else { GREEN };
+                let text = match &snippet_view.line_editor_view {
+                    Some(line_editor_view) if selected => line_editor_text(line_editor_view),
+                    _ => text,
+                };
+
+                ui.colored_label(color(label_color), label.trim());
+                ui.monospace(text);
+                ui.end_row();
+            }
+        });
+
+    let snippet = &snippet_view.snippet;
+
+    ui.colored_label(
+        color(GRAY40),
+        format!(
+            "Created: {}  Modified: {}",
+            snippet.created.as_deref().unwrap_or("unknown"),
+            snippet.modified.as_deref().unwrap_or("unknown")
+        ),
+    );
+}
+
+/// Text being typed, with a bar at the cursor
+fn line_editor_text(view: &LineEditorView) -> String {
+    let split = view
+        .text
+        .char_indices()
+        .nth(view.cursor)
+        .map_or(view.text.len(), |(index, _)| index);
+
+    format!("{}|{}", &view.text[..split], &view.text[split..])
+}
+
+fn source_image_ui(
+    ui: &mut egui::Ui,
+    editor: &mut EguiEditor,
+    texture: Option<(egui::TextureId, (f32, f32))>,
+    region: Option<SourceRegion>,
+) {
+    let area = texture.and_then(|(_, (width, height))| {
+        region.and_then(|region| region.area_within(width as u32, height as u32))
+    });
+
+    ui.horizontal(|ui| {
+        if ui.button("Fit").clicked() {
+            editor.viewport = ImageViewport::default();
+        }
+
+        if ui.add_enabled(area.is_some(), egui::Button::new("Region")).clicked() {
+            editor.focus_pending = true;
+        }
+
+        ui.label(format!("{:.0}%", editor.viewport.zoom * 100.0));
+    });
+
+    let (frame, response) = ui.allocate_exact_size(ui.available_size(), egui::Sense::drag());
+    let frame_size = (frame.width(), frame.height());
+
+    let (texture_id, image_size) = match texture {
+        Some(texture) => texture,
+        None => {
+            ui.painter().text(
+                frame.center(),
+                egui::Align2::CENTER_CENTER,
+                "No source image",
+                egui::FontId::default(),
+                color(GRAY40),
+            );
+            return;
+        }
+    };
+
+    if editor.focus_pending {
+        editor.viewport = match area {
+            Some(area) => ImageViewport::focused_on(area, image_size, frame_size),
+            None => ImageViewport::default(),
+        };
+        editor.focus_pending = false;
+    }
+
+    if response.dragged() {
+        let delta = response.drag_delta();
+
+        editor.viewport = editor.viewport.panned_by((delta.x, delta.y));
+    }
+
+    if let Some(pointer) = response.hover_pos() {
+        let scroll = ui.input().scroll_delta.y;
+
+        if scroll != 0.0 {
+            let point = pointer - frame.min;
+
+            editor.viewport = editor.viewport.zoomed_at(
+                (scroll * ZOOM_SPEED).exp(),
+                (point.x, point.y),
+                image_size,
+                frame_size,
+            );
+        }
+    }
+
+    let to_screen = |(x, y, width, height): FrameRect| {
+        egui::Rect::from_min_size(frame.min + egui::vec2(x, y), egui::vec2(width, height))
+    };
+    let painter = ui.painter_at(frame);
+    let uv = egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0));
+
+    painter.image(
+        texture_id,
+        to_screen(editor.viewport.image_rect(image_size, frame_size)),
+        uv,
+        egui::Color32::WHITE,
+    );
+
+    if let Some(area) = area {
+        painter.rect_stroke(
+            to_screen(editor.viewport.area_rect(area, image_size, frame_size)),
+            0.0,
+            egui::Stroke::new(2.0, egui::Color32::RED),
+        );
+    }
+}
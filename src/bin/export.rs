@@ -0,0 +1,88 @@
+use clap::{Parser, ValueEnum};
+use std::fs;
+
+use fennec::prelude::*;
+
+/// Export notebook snippets as interlinear glosses: glyphs, phonetic reading
+/// and definitions on three aligned lines
+#[derive(Parser)]
+#[command(author, version, about)]
+struct Cli {
+    /// Output format
+    #[arg(short, long, value_enum, default_value_t = Format::Markdown)]
+    format: Format,
+
+    /// Write to this file instead of standard output
+    #[arg(short, long)]
+    output: Option<String>,
+
+    /// Only export the snippet with this ID. Can be given more than once
+    #[arg(short, long)]
+    snippet: Vec<SnippetId>,
+
+    /// Which candidate reading to show for each word: preferred, confirmed, a rank from 1 or
+    /// author:<name>
+    #[arg(long, default_value_t = Hypothesis::Preferred)]
+    hypothesis: Hypothesis,
+
+    #[command(flatten)]
+    paths: ConfigArgs,
+}
+
+#[derive(Clone, ValueEnum)]
+enum Format {
+    Markdown,
+    Text,
+    /// LaTeX using the gb4e package
+    Gb4e,
+    /// LaTeX using the expex package
+    Expex,
+}
+
+impl From<Format> for ExportFormat {
+    fn from(format: Format) -> Self {
+        match format {
+            Format::Markdown => ExportFormat::Markdown,
+            Format::Text => ExportFormat::Text,
+            Format::Gb4e => ExportFormat::Gb4e,
+            Format::Expex => ExportFormat::Expex,
+        }
+    }
+}
+
+fn main() {
+    let cli = Cli::parse();
+
+    let config = FennecConfig::from_args(&cli.paths).unwrap_or_else(|error| error.exit());
+
+    if let Err(error) = export(cli, &config) {
+        error.exit();
+    }
+}
+
+fn export(cli: Cli, config: &FennecConfig) -> FennecResult<()> {
+    let (notebook, _yaml) = notebook_from_yaml_file(&config.notebook)?;
+    let (dictionary, _yaml) = dictionary_from_yaml_file(&config.dictionary)?;
+    let dictionary = dictionary.as_hypothesis(&cli.hypothesis);
+
+    let phoneme_table = phoneme_table_or_default(&config.phonemes)?;
+
+    if let Some(id) = cli.snippet.iter().find(|id| notebook.position_of(id).is_none()) {
+        return Err(FennecError::Validation(format!("There is no snippet {}", id)));
+    }
+
+    let snippets = notebook
+        .snippets
+        .iter()
+        .filter(|snippet| cli.snippet.is_empty() || cli.snippet.contains(&snippet.id));
+
+    let exported = export_snippets(snippets, &dictionary, &phoneme_table, cli.format.into());
+
+    match &cli.output {
+        Some(output) => fs::write(output, exported).map_err(|error| FennecError::io(output, error)),
+        None => {
+            print!("{}", exported);
+            Ok(())
+        }
+    }
+}
@@ -1,52 +1,68 @@
+use clap::Parser;
+
 use fennec::prelude::*;
 
+#[derive(Parser)]
+#[command(author, version, about)]
+struct Cli {
+    #[command(flatten)]
+    paths: ConfigArgs,
+}
+
 fn main() -> BError {
-    let glyph_font = "tunic_glyphs.png";
-    let small_text_font = "dbyte_1x.png";
-    let large_text_font = "dbyte_2x.png";
-
-    let dictionary = match dictionary_from_yaml_file(DEFAULT_DICTIONARY_FILE) {
-        Ok((dict, _yaml)) => dict,
-        Err(error) => {
-            println!(
-                "Unable to load dictionary file: {}",
-                DEFAULT_DICTIONARY_FILE
-            );
-            println!("{:?}", error);
-            panic!("Aborting");
-        }
-    };
-
-    let state = FennecState::new(Snippet::starting_snippet(), dictionary);
+    let cli = Cli::parse();
+
+    let config = FennecConfig::from_args(&cli.paths).unwrap_or_else(|error| error.exit());
+
+    let WindowConfig {
+        width,
+        height,
+        tile_width,
+        tile_height,
+        ..
+    } = config.window;
+
+    let glyph_font = config.window.glyph_font.as_str();
+    let small_text_font = config.window.small_text_font.as_str();
+    let large_text_font = config.window.large_text_font.as_str();
+
+    let (dictionary, _yaml) =
+        dictionary_from_yaml_file(&config.dictionary).unwrap_or_else(|error| error.exit());
+
+    let phoneme_table =
+        phoneme_table_or_default(&config.phonemes).unwrap_or_else(|error| error.exit());
+
+    let state = FennecState::new(Snippet::starting_snippet(), dictionary, &config)
+        .with_phoneme_table(phoneme_table);
 
     let context = BTermBuilder::new()
         .with_title("Tunic Language Toolkit")
         .with_fps_cap(30.0)
-        .with_dimensions(DISPLAY_WIDTH, DISPLAY_HEIGHT)
-        .with_tile_dimensions(24, 32)
-        .with_resource_path("resources/")
-        .with_font(glyph_font, 24, 32)
+        .with_dimensions(width, height)
+        .with_tile_dimensions(tile_width, tile_height)
+        .with_resource_path(&config.window.resources)
+        .with_font(glyph_font, GLYPH_FONT_WIDTH, GLYPH_FONT_HEIGHT)
         .with_font(small_text_font, 6, 8)
         .with_font(large_text_font, 12, 16)
-        .with_simple_console_no_bg(DISPLAY_WIDTH, DISPLAY_HEIGHT, glyph_font) // 0
-        .with_simple_console_no_bg(DISPLAY_WIDTH, DISPLAY_HEIGHT, glyph_font)
-        .with_simple_console_no_bg(DISPLAY_WIDTH, DISPLAY_HEIGHT, glyph_font)
-        .with_simple_console_no_bg(DISPLAY_WIDTH, DISPLAY_HEIGHT, glyph_font)
-        .with_simple_console_no_bg(DISPLAY_WIDTH, DISPLAY_HEIGHT, glyph_font)
-        .with_simple_console_no_bg(DISPLAY_WIDTH, DISPLAY_HEIGHT, glyph_font)
-        .with_simple_console_no_bg(DISPLAY_WIDTH, DISPLAY_HEIGHT, glyph_font)
-        .with_simple_console_no_bg(DISPLAY_WIDTH, DISPLAY_HEIGHT, glyph_font)
-        .with_simple_console_no_bg(DISPLAY_WIDTH, DISPLAY_HEIGHT, glyph_font)
-        .with_simple_console_no_bg(DISPLAY_WIDTH, DISPLAY_HEIGHT, glyph_font)
-        .with_simple_console_no_bg(DISPLAY_WIDTH, DISPLAY_HEIGHT, glyph_font)
-        .with_simple_console_no_bg(DISPLAY_WIDTH, DISPLAY_HEIGHT, glyph_font)
-        .with_simple_console_no_bg(DISPLAY_WIDTH, DISPLAY_HEIGHT, glyph_font)
-        .with_simple_console_no_bg(DISPLAY_WIDTH, DISPLAY_HEIGHT, glyph_font)
-        .with_simple_console_no_bg(DISPLAY_WIDTH, DISPLAY_HEIGHT, glyph_font) // 14
-        .with_simple_console_no_bg(DISPLAY_WIDTH, DISPLAY_HEIGHT, glyph_font) // 15
-        .with_simple_console_no_bg(DISPLAY_WIDTH * 2, DISPLAY_HEIGHT * 2, small_text_font) // FILE_CONSOLE
-        .with_simple_console_no_bg(DISPLAY_WIDTH * 2, DISPLAY_HEIGHT * 2, small_text_font) // NOTEBOOK_CONSOLE
-        .with_simple_console_no_bg(DISPLAY_WIDTH * 2, DISPLAY_HEIGHT * 2, small_text_font) // SNIPPET_CONSOLE
+        .with_simple_console_no_bg(width, height, glyph_font) // 0
+        .with_simple_console_no_bg(width, height, glyph_font)
+        .with_simple_console_no_bg(width, height, glyph_font)
+        .with_simple_console_no_bg(width, height, glyph_font)
+        .with_simple_console_no_bg(width, height, glyph_font)
+        .with_simple_console_no_bg(width, height, glyph_font)
+        .with_simple_console_no_bg(width, height, glyph_font)
+        .with_simple_console_no_bg(width, height, glyph_font)
+        .with_simple_console_no_bg(width, height, glyph_font)
+        .with_simple_console_no_bg(width, height, glyph_font)
+        .with_simple_console_no_bg(width, height, glyph_font)
+        .with_simple_console_no_bg(width, height, glyph_font)
+        .with_simple_console_no_bg(width, height, glyph_font)
+        .with_simple_console_no_bg(width, height, glyph_font)
+        .with_simple_console_no_bg(width, height, glyph_font) // 14
+        .with_simple_console_no_bg(width, height, glyph_font) // 15
+        .with_simple_console_no_bg(width * 2, height * 2, small_text_font) // FILE_CONSOLE
+        .with_simple_console_no_bg(width * 2, height * 2, small_text_font) // NOTEBOOK_CONSOLE
+        .with_simple_console_no_bg(width * 2, height * 2, small_text_font) // SNIPPET_CONSOLE
         .build()?;
 
     main_loop(context, state)
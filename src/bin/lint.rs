@@ -0,0 +1,84 @@
+use clap::{Parser, ValueEnum};
+use serde::de::DeserializeOwned;
+use std::fs;
+use std::process;
+
+use fennec::prelude::*;
+
+/// Check the notebook and dictionary for transcription problems. Exits with 1
+/// when anything is found.
+#[derive(Parser)]
+#[command(author, version, about)]
+struct Cli {
+    /// Output format
+    #[arg(short, long, value_enum, default_value_t = Format::Text)]
+    format: Format,
+
+    #[command(flatten)]
+    paths: ConfigArgs,
+}
+
+#[derive(Clone, ValueEnum)]
+enum Format {
+    Text,
+    Json,
+}
+
+fn main() {
+    let cli = Cli::parse();
+
+    let config = FennecConfig::from_args(&cli.paths).unwrap_or_else(|error| error.exit());
+
+    let lints = lint_files(&config).unwrap_or_else(|error| error.exit());
+
+    match cli.format {
+        Format::Text => {
+            for lint in lints.iter() {
+                println!("{}", lint);
+            }
+
+            eprintln!("{} problem(s) found", lints.len());
+        }
+        Format::Json => {
+            let json = serde_json::to_string_pretty(&lints)
+                .unwrap_or_else(|error| FennecError::render(error).exit());
+
+            println!("{}", json);
+        }
+    }
+
+    if !lints.is_empty() {
+        process::exit(1);
+    }
+}
+
+fn lint_files(config: &FennecConfig) -> FennecResult<Vec<Lint>> {
+    let (notebook, _yaml): (Notebook, _) =
+        load_migrated(&config.notebook, &Migrations::for_notebooks())?;
+    // Reported the same as other tools show them
+    let notebook = notebook.with_missing_snippet_ids();
+    let (dictionary, dictionary_yaml): (Dictionary, _) =
+        load_migrated(&config.dictionary, &Migrations::for_dictionaries())?;
+
+    let mut lints = lint_notebook(&notebook, &dictionary);
+    lints.extend(lint_dictionary_source(&dictionary_yaml));
+
+    Ok(lints)
+}
+
+/// Migrates in memory only. Linting never writes backups.
+fn load_migrated<T: DeserializeOwned>(
+    target_file: &str,
+    migrations: &Migrations,
+) -> FennecResult<(T, serde_yaml::Value)> {
+    let yaml =
+        fs::read_to_string(target_file).map_err(|error| FennecError::io(target_file, error))?;
+    let value: serde_yaml::Value =
+        serde_yaml::from_str(&yaml).map_err(|error| FennecError::yaml(target_file, error))?;
+    let value = migrations.migrated(value)?;
+
+    let loaded: T = serde_yaml::from_value(value.clone())
+        .map_err(|error| FennecError::yaml(target_file, error))?;
+
+    Ok((loaded, value))
+}
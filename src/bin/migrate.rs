@@ -1,90 +1,97 @@
+use clap::{ArgGroup, Parser};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
 use std::fs;
 
-use fennec::prelude::DEFAULT_NOTEBOOK_FILE;
+use fennec::prelude::*;
 
-mod old_version {
-    use serde::{Deserialize, Serialize};
+/// Bring the notebook and dictionary files up to the current version
+#[derive(Parser)]
+#[command(author, version, about)]
+#[command(group(ArgGroup::new("mode").required(true).args(["dry_run", "in_place"])))]
+struct Cli {
+    /// Print the migrated files without writing anything
+    #[arg(long)]
+    dry_run: bool,
 
-    use fennec::prelude::{Glyph, Note, Source};
+    /// Overwrite the files, keeping each original as a .bak file alongside it
+    #[arg(long)]
+    in_place: bool,
 
-    #[derive(Clone, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
-    pub enum Word {
-        Tunic {
-            glyphs: Vec<Glyph>,
-            has_border: bool,
-            colored: bool,
-        },
-        English(String),
-    }
+    #[command(flatten)]
+    paths: ConfigArgs,
+}
 
-    #[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
-    pub struct Snippet {
-        pub words: Vec<Word>,
-        pub source: Option<Source>,
-        pub description: String,
-        pub notes: Vec<Note>,
-        pub transcribed: bool,
-    }
+fn main() {
+    let cli = Cli::parse();
 
-    #[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
-    pub struct Notebook {
-        pub snippets: Vec<Snippet>,
-    }
-}
+    let config = FennecConfig::from_args(&cli.paths).unwrap_or_else(|error| error.exit());
 
-impl old_version::Word {
-    pub fn migrated(&self) -> fennec::prelude::Word {
-        match self {
-            Self::Tunic {
-                glyphs,
-                has_border,
-                colored,
-            } => fennec::prelude::TunicWord::new(glyphs.clone())
-                .with_border_as(*has_border)
-                .with_colored_as(*colored)
-                .into(),
-            Self::English(text) => text.clone().into(),
-        }
+    let result =
+        migrate_file::<Notebook>(&config.notebook, &Migrations::for_notebooks(), cli.in_place)
+            .and_then(|_| {
+                migrate_file::<Dictionary>(
+                    &config.dictionary,
+                    &Migrations::for_dictionaries(),
+                    cli.in_place,
+                )
+            });
+
+    if let Err(error) = result {
+        error.exit();
     }
 }
 
-impl old_version::Snippet {
-    pub fn migrated(&self) -> fennec::prelude::Snippet {
-        let words: Vec<fennec::prelude::Word> =
-            self.words.iter().map(|word| word.migrated()).collect();
-
-        fennec::prelude::Snippet {
-            words,
-            source: self.source.clone(),
-            description: self.description.clone(),
-            notes: self.notes.clone(),
-            transcribed: self.transcribed.clone(),
-        }
+/// Round-trips the migrated file through `T` so that only files the current
+/// version can load are written. Progress goes to stderr, leaving stdout for
+/// the migrated YAML of a dry run.
+fn migrate_file<T: DeserializeOwned + Serialize>(
+    target_file: &str,
+    migrations: &Migrations,
+    in_place: bool,
+) -> FennecResult<()> {
+    let yaml =
+        fs::read_to_string(target_file).map_err(|error| FennecError::io(target_file, error))?;
+    let value: serde_yaml::Value =
+        serde_yaml::from_str(&yaml).map_err(|error| FennecError::yaml(target_file, error))?;
+
+    let version = migrations.version_of(&value);
+
+    if !migrations.needs_migration(&value) {
+        eprintln!("{}: already at version {}", target_file, version);
+        return Ok(());
     }
-}
 
-impl From<old_version::Notebook> for fennec::prelude::Notebook {
-    fn from(old_notebook: old_version::Notebook) -> Self {
-        let snippets: Vec<fennec::prelude::Snippet> = old_notebook
-            .snippets
-            .iter()
-            .map(|snip| snip.migrated())
-            .collect();
-
-        Self {
-            snippets,
-            version: fennec::prelude::notebooks::VERSION,
-        }
+    eprintln!(
+        "{}: version {} -> {}",
+        target_file,
+        version,
+        migrations.current_version()
+    );
+
+    for step in migrations.pending_steps(&value) {
+        eprintln!("  v{}: {}", step.from_version, step.description);
     }
-}
 
-fn main() {
-    let old_yaml = fs::read_to_string(DEFAULT_NOTEBOOK_FILE).unwrap();
-    let old_notebook: old_version::Notebook = serde_yaml::from_str(&old_yaml).unwrap();
+    let migrated: T = serde_yaml::from_value(migrations.migrated(value)?)
+        .map_err(|error| FennecError::yaml(target_file, error))?;
+
+    let migrated_yaml =
+        serde_yaml::to_string(&migrated).map_err(|error| FennecError::yaml(target_file, error))?;
 
-    let new_notebook: fennec::prelude::Notebook = old_notebook.into();
-    let new_yaml = serde_yaml::to_string(&new_notebook).unwrap();
+    if in_place {
+        // The original is only replaced once its backup is safely written
+        let backup_file = backup_file_for(target_file, version);
+        fs::copy(target_file, &backup_file)
+            .map_err(|error| FennecError::io(&backup_file, error))?;
+        eprintln!("Kept the original in {}", backup_file);
+
+        write_file(target_file, &migrated_yaml)?;
+        eprintln!("Saved {}", target_file);
+    } else {
+        // Both files go to stdout, so each starts its own document
+        print!("---\n{}", migrated_yaml);
+    }
 
-    //fs::write(target_file, &yaml)?;
-    print!("{}", new_yaml);
+    Ok(())
 }
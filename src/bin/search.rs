@@ -1,6 +1,8 @@
-use clap::{Args, Parser, Subcommand};
+use clap::{Args, Parser, Subcommand, ValueEnum};
 use colored::{ColoredString, Colorize};
 use std::collections::HashMap;
+use std::convert::Infallible;
+use std::str::FromStr;
 
 use fennec::prelude::*;
 
@@ -9,12 +11,37 @@ use fennec::prelude::*;
 struct Cli {
     #[command(subcommand)]
     command: Commands,
+
+    /// Which candidate reading to show for each word: preferred, confirmed, a rank from 1 or
+    /// author:<name>. Words without a matching candidate show as undefined
+    #[arg(long, global = true, default_value_t = Hypothesis::Preferred)]
+    hypothesis: Hypothesis,
+
+    /// Only search snippets with this tag. Can be given more than once
+    #[arg(long, global = true)]
+    tag: Vec<String>,
+
+    /// Only search snippets whose location contains this text
+    #[arg(long, global = true)]
+    location: Option<String>,
+
+    /// Only search snippets modified since this date or timestamp
+    /// Example: 2023-03-14
+    #[arg(long, global = true)]
+    since: Option<String>,
+
+    #[command(flatten)]
+    paths: ConfigArgs,
 }
 
 #[derive(Subcommand)]
 enum Commands {
     /// Search through every snippet in the notebook
     Snippets(Snippets),
+    /// Search for words matching a glyph pattern
+    Pattern(PatternArgs),
+    /// List notebook words and dictionary entries within a few segments of a word
+    Similar(Similar),
     /// Search for usage
     Usage(Usage),
     /// Find all snippets for a given manual page
@@ -35,19 +62,74 @@ struct Page {
 
 #[derive(Args)]
 struct Snippets {
-    /// Search for snippets that contain this Tunic word. Space-separated list of glyph values
-    word: Option<Vec<u16>>,
+    /// Search for snippets that contain this Tunic word. Space-separated list of glyphs in hex,
+    /// segment list, phoneme or integer notation
+    #[arg(required = true)]
+    word: Vec<Glyph>,
     /// Render words as their definition if available. Default: Render words as their glyph values
     #[arg(short, long)]
     define_inline: bool,
-    /// Use this as the definition of the word being searched for
+    /// Show the snippets as if this hypothesis held: preferred, confirmed, a rank from 1 or
+    /// author:<name>. Anything else is used as the definition of the word being searched for
     #[arg(short, long)]
-    as_if: Option<String>,
+    as_if: Option<AsIf>,
+}
+
+#[derive(Clone)]
+enum AsIf {
+    Hypothesis(Hypothesis),
+    Definition(String),
+}
+
+impl FromStr for AsIf {
+    type Err = Infallible;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        Ok(match text.parse() {
+            Ok(hypothesis) => Self::Hypothesis(hypothesis),
+            Err(_) => Self::Definition(text.to_owned()),
+        })
+    }
+}
+
+#[derive(Args)]
+struct PatternArgs {
+    /// Glyphs in any notation, where `?` is any glyph, `*` is any run of glyphs, `v:<glyph>`
+    /// is any glyph with the same vowel and `c:<glyph>` any glyph with the same consonant.
+    /// Separate consecutive words with `/`
+    /// Example: "fɑ * / ? c:k"
+    pattern: SearchPattern,
+    /// Only match words with a border
+    #[arg(long)]
+    border: bool,
+    /// Only match colored words
+    #[arg(long)]
+    colored: bool,
+    /// Render words as their definition if available. Default: Render words as their glyph values
+    #[arg(short, long)]
+    define_inline: bool,
+}
+
+#[derive(Args)]
+struct Similar {
+    /// Space-separated list of glyphs in hex, segment list, phoneme or integer notation
+    #[arg(required = true)]
+    word: Vec<Glyph>,
+    /// The most segments a listed word may differ by
+    #[arg(short, long, default_value_t = 2)]
+    distance: u32,
 }
 
 #[derive(Args)]
 struct Usage {
-    words: Option<String>,
+    /// What to count the usage of
+    #[arg(value_enum)]
+    usage_type: UsageType,
+}
+
+#[derive(Clone, ValueEnum)]
+enum UsageType {
+    Words,
 }
 
 #[derive(Subcommand)]
@@ -65,44 +147,61 @@ struct ListSnippets {
 fn main() {
     let cli = Cli::parse();
 
+    let config = FennecConfig::from_args(&cli.paths).unwrap_or_else(|error| error.exit());
+
     println!("Loading notebook...");
 
-    let (notebook, _yaml) = notebook_from_yaml_file(DEFAULT_NOTEBOOK_FILE)
-        .unwrap_or_else(|error| {
-            println!("Unable to load notebook file: {}", DEFAULT_NOTEBOOK_FILE);
-            println!("{:?}", error);
-            panic!("Search aborted");
-        });
+    let (mut notebook, _yaml) =
+        notebook_from_yaml_file(&config.notebook).unwrap_or_else(|error| error.exit());
+
+    let modified_since = cli
+        .since
+        .as_deref()
+        .map(parse_date_or_timestamp)
+        .transpose()
+        .unwrap_or_else(|error| error.exit());
+    let filter = SnippetFilter {
+        tags: cli.tag.clone(),
+        location: cli.location.clone(),
+        modified_since,
+    };
+    notebook.snippets.retain(|snippet| filter.retains(snippet));
+
+    let (dictionary, _yaml) =
+        dictionary_from_yaml_file(&config.dictionary).unwrap_or_else(|error| error.exit());
+    let hypothesis = match &cli.command {
+        Commands::Snippets(Snippets {
+            as_if: Some(AsIf::Hypothesis(hypothesis)),
+            ..
+        }) => hypothesis,
+        _ => &cli.hypothesis,
+    };
+    let dictionary = dictionary.as_hypothesis(hypothesis);
 
-    let (dictionary, _yaml) = dictionary_from_yaml_file(DEFAULT_DICTIONARY_FILE)
-        .unwrap_or_else(|error| {
-            println!("Unable to load dictionary file: {}", DEFAULT_NOTEBOOK_FILE);
-            println!("{:?}", error);
-            panic!("Search aborted");
-        });
+    let phoneme_table =
+        phoneme_table_or_default(&config.phonemes).unwrap_or_else(|error| error.exit());
 
     println!("Searching...");
 
     match cli.command {
-        Commands::Snippets(args) => search_snippets(notebook, dictionary, args),
+        Commands::Snippets(args) => search_snippets(notebook, dictionary, &phoneme_table, args),
+        Commands::Pattern(args) => search_pattern(notebook, dictionary, &phoneme_table, args),
+        Commands::Similar(args) => search_similar(notebook, dictionary, args),
         Commands::Usage(args) => search_usage(notebook, args),
-        Commands::Page(args) => search_by_page(notebook, dictionary, args),
+        Commands::Page(args) => search_by_page(notebook, dictionary, &phoneme_table, args),
         Commands::List(subcommand) => {
             match subcommand {
-                List::Snippets(args) => list_all_snippets(notebook, dictionary, args),
+                List::Snippets(args) => {
+                    list_all_snippets(notebook, dictionary, &phoneme_table, args)
+                }
             }
         }
     };
 }
 
 fn search_usage(notebook: Notebook, usage_args: Usage) {
-    let usage_type = usage_args
-        .words
-        .expect("Missing argument: type of usage to search");
-
-    match usage_type.as_str() {
-        "words" => search_word_usage(notebook),
-        _ => panic!("Unsupported usage type: {}", usage_type),
+    match usage_args.usage_type {
+        UsageType::Words => search_word_usage(notebook),
     };
 }
 
@@ -137,7 +236,12 @@ fn search_word_usage(notebook: Notebook) {
     }
 }
 
-fn search_by_page(notebook: Notebook, dictionary: Dictionary, args: Page) {
+fn search_by_page(
+    notebook: Notebook,
+    dictionary: Dictionary,
+    phoneme_table: &PhonemeTable,
+    args: Page,
+) {
     let page_number = args.number;
     let define_inline = args.define_inline;
 
@@ -157,26 +261,24 @@ fn search_by_page(notebook: Notebook, dictionary: Dictionary, args: Page) {
         })
         .collect();
 
-    // TODO: Refactor print_snippet to slash that argument count. May involve callbacks
-    let placeholder_word: Word = vec![0].into();
-
-    for (index, snippet) in matches.iter().enumerate() {
-        print_snippet(snippet, index, define_inline, Some(&placeholder_word), &dictionary);
+    for snippet in matches.iter() {
+        print_snippet(snippet, define_inline, &[], &dictionary, phoneme_table);
     }
 }
 
-fn search_snippets(notebook: Notebook, dictionary: Dictionary, search_args: Snippets) {
-    let word = search_args
-        .word
-        .expect("Missing argument: glyph values for word");
-
-    let word: Word = word.into();
+fn search_snippets(
+    notebook: Notebook,
+    dictionary: Dictionary,
+    phoneme_table: &PhonemeTable,
+    search_args: Snippets,
+) {
+    let word: Word = search_args.word.into();
 
     let define_inline = search_args.define_inline || search_args.as_if.is_some();
 
     println!("Looking for word {}", word);
 
-    let dictionary = if let Some(temporary_definition) = &search_args.as_if {
+    let dictionary = if let Some(AsIf::Definition(temporary_definition)) = &search_args.as_if {
         if let WordType::Tunic(tunic_word) = &word.word_type {
             dictionary.with_new_definition(&tunic_word, temporary_definition.clone())
         } else {
@@ -194,45 +296,131 @@ fn search_snippets(notebook: Notebook, dictionary: Dictionary, search_args: Snip
 
     println!("Found {} match(es)", matches.len());
 
-    for (index, snippet) in matches.iter().enumerate() {
-        print_snippet(snippet, index, define_inline, Some(&word), &dictionary);
+    for snippet in matches.iter() {
+        let highlighted: Vec<usize> = snippet
+            .words
+            .iter()
+            .enumerate()
+            .filter(|(_, w)| **w == word)
+            .map(|(position, _)| position)
+            .collect();
+
+        print_snippet(snippet, define_inline, &highlighted, &dictionary, phoneme_table);
     }
 }
 
-fn list_all_snippets(notebook: Notebook, dictionary: Dictionary, args: ListSnippets) {
-    let define_inline = args.define_inline;
+fn search_pattern(
+    notebook: Notebook,
+    dictionary: Dictionary,
+    phoneme_table: &PhonemeTable,
+    args: PatternArgs,
+) {
+    let pattern = args
+        .pattern
+        .with_border_as(args.border.then_some(true))
+        .with_colored_as(args.colored.then_some(true));
+
+    let matches: Vec<(&Snippet, Vec<usize>)> = notebook
+        .snippets
+        .iter()
+        .map(|snippet| {
+            let highlighted: Vec<usize> = pattern
+                .find_in(&snippet.words)
+                .into_iter()
+                .flatten()
+                .collect();
+
+            (snippet, highlighted)
+        })
+        .filter(|(_, highlighted)| !highlighted.is_empty())
+        .collect();
+
+    println!("Found {} match(es)", matches.len());
+
+    for (snippet, highlighted) in matches.iter() {
+        print_snippet(snippet, args.define_inline, highlighted, &dictionary, phoneme_table);
+    }
+}
+
+fn search_similar(notebook: Notebook, dictionary: Dictionary, args: Similar) {
+    let word: DictionaryWord = TunicWord::new(args.word).into();
 
-    for (index, snippet) in notebook.snippets.iter().enumerate() {
-        print_snippet(snippet, index, define_inline, None, &dictionary);
+    println!("Looking for words within {} segment(s) of {}", args.distance, word);
+
+    let similar = similar_words(&word, args.distance, &notebook, &dictionary);
+
+    println!("Found {} word(s)", similar.len());
+
+    for similar_word in similar {
+        let definition = similar_word
+            .entry
+            .as_ref()
+            .and_then(|entry| format_definition_for_reading(&entry.definition()))
+            .unwrap_or_else(|| "".normal());
+
+        println!(
+            " {:2} | {:4} use(s) | [{}] {}",
+            similar_word.distance,
+            similar_word.usages,
+            similar_word.word,
+            definition
+        );
     }
 }
 
-fn print_snippet(snippet: &Snippet, index: usize, define_inline: bool, selected_word: Option<&Word>, dictionary: &Dictionary) {
-    let source = snippet
-        .source
-        .clone()
-        .map_or("(None)".into(), |source| source.to_string());
+fn list_all_snippets(
+    notebook: Notebook,
+    dictionary: Dictionary,
+    phoneme_table: &PhonemeTable,
+    args: ListSnippets,
+) {
+    let define_inline = args.define_inline;
 
-    let sentence: Vec<ColoredString> = snippet
-        .words
+    for snippet in notebook.snippets.iter() {
+        print_snippet(snippet, define_inline, &[], &dictionary, phoneme_table);
+    }
+}
+
+/// Prints the snippet's interlinear gloss on one line, with each column as
+/// its definition or else its glyphs
+fn print_snippet(
+    snippet: &Snippet,
+    define_inline: bool,
+    highlighted: &[usize],
+    dictionary: &Dictionary,
+    phoneme_table: &PhonemeTable,
+) {
+    let gloss = InterlinearGloss::new(snippet, dictionary, phoneme_table);
+
+    let sentence: Vec<ColoredString> = gloss
+        .columns
         .iter()
-        .map(|w| {
-            let formatted_word = if define_inline {
-                format_word_for_reading_as_defined(&dictionary, w)
+        .map(|column| {
+            let formatted_column = if define_inline {
+                format_column_for_reading(column, &snippet.words)
             } else {
-                format_word_for_reading_as_glyphs(w)
+                column.glyphs.normal()
             };
 
-            let does_match = selected_word.map(|word| *w == *word).unwrap_or(false);
-
-            (formatted_word, does_match)
+            if column.words.clone().any(|position| highlighted.contains(&position)) {
+                formatted_column.underline().green()
+            } else {
+                formatted_column
+            }
         })
-        .map(|(w, matches)| if matches { w.underline().green() } else { w })
         .collect();
 
-    println!(" {:3}: {}", index, snippet.description.green().bold());
+    println!(" {}: {}", snippet.id.to_reference(), snippet.description.green().bold());
 
-    println!("      {}", source);
+    println!("      {}", gloss.source_text().unwrap_or_else(|| "(None)".into()));
+
+    if let Some(location) = &snippet.location {
+        println!("      Location: {}", location);
+    }
+
+    if !snippet.tags.is_empty() {
+        println!("      Tags: {}", snippet.tags_text().cyan());
+    }
 
     print!("      ");
     for word in sentence {
@@ -244,37 +432,31 @@ fn print_snippet(snippet: &Snippet, index: usize, define_inline: bool, selected_
     println!();
 }
 
-fn format_word_for_reading_as_defined(dictionary: &Dictionary, word: &Word) -> ColoredString {
-    match &word.word_type {
-        WordType::Tunic(word) => {
-            let dict_word: DictionaryWord = word.into();
-            if let Some(entry) = dictionary.get(&dict_word) {
-                match entry.definition() {
-                    Definition::Undefined => format_glyphs_for_reading(word.glyphs()).normal(),
-                    Definition::Tentative(text) => format!("{}", text).bright_yellow().underline(),
-                    Definition::Confirmed(text) => format!("{}", text).bright_yellow(),
-                }
-            } else {
-                format_glyphs_for_reading(word.glyphs()).normal()
-            }
-        }
-        WordType::English(word) => word.text().normal(),
+/// Phrases read as one definition in place of all their words
+fn format_column_for_reading(column: &GlossColumn, words: &[Word]) -> ColoredString {
+    let definition = match &words[column.words.start].word_type {
+        WordType::English(_) => None,
+        WordType::Tunic(_) => format_definition_for_reading(&column.definition),
+    };
+
+    match definition {
+        Some(definition) if column.is_phrase() => definition.italic(),
+        Some(definition) => definition,
+        None => column.glyphs.normal(),
+    }
+}
+
+fn format_definition_for_reading(definition: &Definition) -> Option<ColoredString> {
+    match definition {
+        Definition::Tentative(text) => Some(text.bright_yellow().underline()),
+        Definition::Confirmed(text) => Some(text.bright_yellow()),
+        Definition::Undefined => None,
     }
 }
 
 fn format_word_for_reading_as_glyphs(word: &Word) -> ColoredString {
     match &word.word_type {
-        WordType::Tunic(word) => {
-            format_glyphs_for_reading(word.glyphs()).normal()
-        }
+        WordType::Tunic(word) => word.to_string().normal(),
         WordType::English(word) => word.text().normal(),
     }
 }
-
-fn format_glyphs_for_reading(glyphs: Vec<Glyph>) -> String {
-    glyphs
-        .iter()
-        .map(|glyph| glyph.0.to_string())
-        .reduce(|word, glyph_value| word + " " + &glyph_value)
-        .map_or("(Empty)".into(), |word| format!("[{}]", word))
-}
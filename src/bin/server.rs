@@ -1,12 +1,38 @@
 #[macro_use] extern crate rocket;
 
-use rocket::fs::{FileServer, relative};
-use rocket::State;
+use chrono::{DateTime, Utc};
+use clap::Parser;
+use rocket::fs::FileServer;
+use rocket::http::{ContentType, Status};
+use rocket::request::{self, FromRequest, Request};
+use rocket::response::status::{BadRequest, Custom};
+use rocket::response::stream::{Event, EventStream};
+use rocket::serde::json::Json;
+use rocket::tokio::select;
+use rocket::tokio::sync::broadcast::{self, error::RecvError, Sender};
+use rocket::{Build, Rocket, Shutdown, State};
 use rocket_dyn_templates::{Template};
 use serde::Serialize;
+use std::convert::Infallible;
+use std::ops::Range;
+use std::process;
+use std::sync::{Arc, PoisonError, RwLock, RwLockReadGuard};
+use std::thread;
 
 use fennec::prelude::*;
 
+#[derive(Parser)]
+#[command(author, version, about)]
+struct Cli {
+    /// Token that editing requests must send as `Authorization: Bearer <token>`.
+    /// Without one, a new token is made and printed at launch
+    #[arg(long)]
+    token: Option<String>,
+
+    #[command(flatten)]
+    paths: ConfigArgs,
+}
+
 #[derive(Clone, Serialize)]
 struct AppContext<'a> {
     title: &'a str,
@@ -17,6 +43,13 @@ struct AppContext<'a> {
 struct DefinitionsContext<'a> {
     app: AppContext<'a>,
     entries: Vec<DictionaryEntry>,
+    phrases: Vec<PhraseEntry>,
+}
+
+#[derive(Clone, Serialize)]
+struct PhraseEntry {
+    phrase: String,
+    definition: String,
 }
 
 #[derive(Clone, Serialize)]
@@ -26,21 +59,104 @@ struct SnippetsContext<'a> {
 }
 
 #[derive(Clone, Serialize)]
+struct SimilarContext<'a> {
+    app: AppContext<'a>,
+    word: String,
+    distance: u32,
+    words: Vec<SimilarRow>,
+}
+
+#[derive(Clone, Serialize)]
+struct SimilarRow {
+    glyphs: Vec<Glyph>,
+    distance: u32,
+    usages: usize,
+    definition: String,
+}
+
+#[derive(Clone, Serialize)]
+struct ReverseContext<'a> {
+    app: AppContext<'a>,
+    text: String,
+    words: Vec<ReverseRow>,
+}
+
+#[derive(Clone, Serialize)]
+struct ReverseRow {
+    glyphs: Vec<Glyph>,
+    definition_type: String,
+    definition: String,
+}
+
+/// Shared by every request and the file watcher. Edits take the write locks,
+/// so they are applied and saved one at a time.
 struct RootContext<'a> {
     app: AppContext<'a>,
-    dictionary: Dictionary,
-    notebook: Notebook,
+    config: FennecConfig,
+    token: String,
+    dictionary: Arc<RwLock<Dictionary>>,
+    notebook: Arc<RwLock<Notebook>>,
+}
+
+impl RootContext<'_> {
+    /// A panic while holding a lock can't leave a half-edited notebook behind,
+    /// since edits are made on a copy, so poisoning is ignored
+    fn notebook(&self) -> RwLockReadGuard<'_, Notebook> {
+        self.notebook.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn dictionary(&self) -> RwLockReadGuard<'_, Dictionary> {
+        self.dictionary.read().unwrap_or_else(PoisonError::into_inner)
+    }
 }
 
 #[derive(Clone, Serialize)]
 struct SnippetRow {
+    id: SnippetId,
     source: String,
+    region: Option<RegionRow>,
     description: String,
     transcribed: bool,
-    notes: Vec<Note>,
+    tags: Vec<String>,
+    location: Option<String>,
+    created: Option<String>,
+    modified: Option<String>,
+    notes: Vec<NoteRow>,
     words: Vec<WordRow>,
 }
 
+/// The template crops the source image to the bounds, which are in the image's
+/// own pixels. Regions with only a side clip the image to that half instead.
+#[derive(Clone, Serialize)]
+struct RegionRow {
+    text: String,
+    bounds: Option<BoundingBox>,
+    side_clip: Option<String>,
+}
+
+impl RegionRow {
+    fn new(region: &SourceRegion) -> Self {
+        let side_clip = match (region.bounds, region.side) {
+            (None, Some(PageSide::Left)) => Some("inset(0 50% 0 0)".to_owned()),
+            (None, Some(PageSide::Right)) => Some("inset(0 0 0 50%)".to_owned()),
+            _ => None,
+        };
+
+        Self {
+            text: region.to_string(),
+            bounds: region.bounds,
+            side_clip,
+        }
+    }
+}
+
+#[derive(Clone, Serialize)]
+struct NoteRow {
+    text: String,
+    /// Snippets the note refers to, linked after it
+    references: Vec<SnippetId>,
+}
+
 #[derive(Clone, Serialize)]
 struct WordRow {
     word_type: String,
@@ -51,31 +167,204 @@ struct WordRow {
     glyphs: Vec<Glyph>,
     has_border: bool,
     colored: bool,
+    is_phrase: bool,
+    is_phrase_continuation: bool,
+    svg: String,
 }
 
 #[derive(Clone, Serialize)]
 struct DictionaryEntry {
     glyphs: Vec<Glyph>,
     definition: String,
+    candidates: Vec<CandidateRow>,
     notes: Vec<String>,
 }
 
+#[derive(Clone, Serialize)]
+struct CandidateRow {
+    text: String,
+    confidence: u8,
+    author: Option<String>,
+    date: Option<String>,
+    snippets: Vec<SnippetId>,
+    confirmed: bool,
+}
+
+/// Page size when the request doesn't give a `limit`
+const DEFAULT_PAGE_LIMIT: usize = 50;
+
+const MAX_PAGE_LIMIT: usize = 500;
+
+#[derive(Clone, Serialize)]
+struct ApiPage<T> {
+    total: usize,
+    offset: usize,
+    limit: usize,
+    items: Vec<T>,
+}
+
+impl<T> ApiPage<T> {
+    fn new(items: Vec<T>, offset: Option<usize>, limit: Option<usize>) -> Self {
+        let total = items.len();
+        let offset = offset.unwrap_or(0);
+        let limit = limit.unwrap_or(DEFAULT_PAGE_LIMIT).min(MAX_PAGE_LIMIT);
+
+        let items: Vec<T> = items.into_iter().skip(offset).take(limit).collect();
+
+        Self {
+            total,
+            offset,
+            limit,
+            items,
+        }
+    }
+}
+
+#[derive(Clone, Serialize)]
+struct ApiSnippet {
+    index: usize,
+    #[serde(flatten)]
+    snippet: Snippet,
+}
+
+#[derive(Clone, Serialize)]
+struct ApiDictionaryEntry {
+    word: String,
+    glyphs: Vec<Glyph>,
+    /// The definition under the requested hypothesis
+    definition: Definition,
+    entry: Entry,
+}
+
+impl ApiDictionaryEntry {
+    fn new(word: &DictionaryWord, entry: &Entry, hypothesis: &Hypothesis) -> Self {
+        Self {
+            word: word.to_string(),
+            glyphs: word.glyphs(),
+            definition: entry.definition_as(hypothesis),
+            entry: entry.clone(),
+        }
+    }
+}
+
+#[derive(Clone, Serialize)]
+struct ApiSearchResult {
+    index: usize,
+    /// Ranges of word positions in the snippet that matched
+    matches: Vec<Range<usize>>,
+    snippet: Snippet,
+}
+
+/// The `tag`, `location` and `since` query parameters both snippet listings
+/// take. `tag` can be given more than once, and `since` is a date or timestamp
+/// compared with when snippets were last modified.
+struct SnippetFilterQuery {
+    tags: Vec<String>,
+    location: Option<String>,
+    since: Option<String>,
+}
+
+impl SnippetFilterQuery {
+    fn into_filter(self) -> Result<SnippetFilter, BadRequest<String>> {
+        Ok(SnippetFilter {
+            tags: self.tags,
+            location: self.location,
+            modified_since: parse_since(self.since.as_deref())?,
+        })
+    }
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for SnippetFilterQuery {
+    type Error = Infallible;
+
+    async fn from_request(request: &'r Request<'_>) -> request::Outcome<Self, Self::Error> {
+        let text = |name: &str| request.query_value::<String>(name).and_then(Result::ok);
+        let tags = request
+            .query_value::<Vec<String>>("tag")
+            .and_then(Result::ok)
+            .unwrap_or_default();
+
+        request::Outcome::Success(Self {
+            tags,
+            location: text("location"),
+            since: text("since"),
+        })
+    }
+}
+
+/// Only editing routes take this, so reading never needs the token
+struct EditToken;
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for EditToken {
+    type Error = String;
+
+    async fn from_request(request: &'r Request<'_>) -> request::Outcome<Self, Self::Error> {
+        let token = request
+            .rocket()
+            .state::<RootContext<'static>>()
+            .map(|state| format!("Bearer {}", state.token));
+        let authorization = request.headers().get_one("Authorization");
+
+        match (token, authorization) {
+            (Some(token), Some(authorization)) if is_same_token(&token, authorization) => {
+                request::Outcome::Success(EditToken)
+            }
+            _ => request::Outcome::Failure((
+                Status::Unauthorized,
+                "Editing needs the server's token".to_owned(),
+            )),
+        }
+    }
+}
+
+/// Sends a `reload` event to open pages whenever the notebook or dictionary
+/// changes on disk
+#[get("/events")]
+fn events(updates: &State<Sender<String>>, mut shutdown: Shutdown) -> EventStream![] {
+    let mut receiver = updates.subscribe();
+
+    EventStream! {
+        loop {
+            let subject = select! {
+                update = receiver.recv() => match update {
+                    Ok(subject) => subject,
+                    Err(RecvError::Closed) => break,
+                    Err(RecvError::Lagged(_)) => continue,
+                },
+                _ = &mut shutdown => break,
+            };
+
+            yield Event::data(subject).event("reload");
+        }
+    }
+}
+
 #[get("/")]
 fn index(state: &State<RootContext>) -> Template {
     Template::render("index", state.app.clone())
 }
 
-#[get("/snippets")]
-fn snippets(state: &State<RootContext>) -> Template {
+#[get("/snippets?<hypothesis>")]
+fn snippets(
+    state: &State<RootContext>,
+    hypothesis: Option<&str>,
+    filter: SnippetFilterQuery,
+) -> Result<Template, BadRequest<String>> {
+    let hypothesis = parse_hypothesis(hypothesis)?;
+    let dictionary = state.dictionary().as_hypothesis(&hypothesis);
+    let word_style = SvgStyle::default().with_glyph_size(12.0, 22.0);
+    let filter = filter.into_filter()?;
+
     let snippets: Vec<SnippetRow> = state
-        .notebook
+        .notebook()
         .snippets
         .iter()
+        .filter(|snip| filter.retains(snip))
         .map(|snip| {
-            let source = match &snip.source {
-                Some(Source::ManualPageNumber(page_number)) => format!("/media/manual_pages/page{:0>2}.jpg", page_number),
-                Some(Source::ScreenshotFilename(file)) => format!("/media/screenshots/{file}"),
-                Some(Source::Other(_)) => "/media/404".to_owned(),
+            let source = match snip.source.as_ref().and_then(Source::image_path) {
+                Some(path) => format!("/media/{}", path),
                 None => "/media/404".to_owned(),
             };
 
@@ -83,12 +372,27 @@ fn snippets(state: &State<RootContext>) -> Template {
 
             let transcribed = snip.transcribed;
 
-            let notes = snip.notes.clone();
+            let notes: Vec<NoteRow> = snip
+                .notes
+                .iter()
+                .map(|note| NoteRow {
+                    text: note.as_text(),
+                    references: note.snippet_references(),
+                })
+                .collect();
+
+            // Every word of a phrase carries the phrase's definition, and the
+            // template shows it once at the first
+            let glosses = glosses_for(&snip.words, &dictionary);
 
-            let words: Vec<WordRow> = snip
-                .words
+            let words: Vec<WordRow> = glosses
                 .iter()
-                .map(|word| {
+                .flat_map(|gloss| gloss.words.clone().map(move |position| (gloss, position)))
+                .map(|(gloss, position)| {
+                    let word = &snip.words[position];
+                    let is_phrase = gloss.is_phrase();
+                    let is_phrase_continuation = position != gloss.words.start;
+
                     match &word.word_type {
                         WordType::English(english_word) => WordRow {
                             word_type: "English".to_owned(),
@@ -99,16 +403,19 @@ fn snippets(state: &State<RootContext>) -> Template {
                             glyphs: vec![],
                             has_border: false,
                             colored: false,
+                            is_phrase,
+                            is_phrase_continuation,
+                            svg: String::new(),
                         },
                         WordType::Tunic(tunic_word) => {
-                            let (has_definition, definition) = state
-                                .dictionary
-                                .get(&tunic_word.into())
+                            let (has_definition, definition) = gloss
+                                .entry
+                                .as_ref()
                                 .map(|entry| {
                                     let definition = match entry.definition() {
                                         Definition::Undefined => "[Undefined]".to_owned(),
-                                        Definition::Tentative(text) => text.clone(),
-                                        Definition::Confirmed(text) => text.clone(),
+                                        Definition::Tentative(text) => text,
+                                        Definition::Confirmed(text) => text,
                                     };
 
                                     (true, definition)
@@ -124,6 +431,9 @@ fn snippets(state: &State<RootContext>) -> Template {
                                 glyphs: tunic_word.glyphs(),
                                 has_border: tunic_word.has_border(),
                                 colored: tunic_word.colored(),
+                                is_phrase,
+                                is_phrase_continuation,
+                                svg: tunic_word_to_svg(tunic_word, &word_style),
                             }
                         }
                     }
@@ -131,9 +441,15 @@ fn snippets(state: &State<RootContext>) -> Template {
                 .collect();
 
             SnippetRow {
+                id: snip.id.clone(),
                 source,
+                region: snip.region.as_ref().map(RegionRow::new),
                 description,
                 transcribed,
+                tags: snip.tags.clone(),
+                location: snip.location.clone(),
+                created: snip.created.clone(),
+                modified: snip.modified.clone(),
                 notes,
                 words,
             }
@@ -145,30 +461,698 @@ fn snippets(state: &State<RootContext>) -> Template {
         snippets,
     };
 
-    Template::render("snippets", context)
+    Ok(Template::render("snippets", context))
 }
 
-#[get("/definitions")]
-fn definitions(state: &State<RootContext>) -> Template {
+#[get("/snippets/<id>/svg")]
+fn snippet_svg(state: &State<RootContext>, id: &str) -> Option<(ContentType, String)> {
+    let snippet = state.notebook().snippet_with_id(&id.parse().ok()?)?.clone();
+
+    Some((ContentType::SVG, snippet_to_svg(&snippet, &SvgStyle::default())))
+}
+
+#[get("/definitions?<hypothesis>")]
+fn definitions(
+    state: &State<RootContext>,
+    hypothesis: Option<&str>,
+) -> Result<Template, BadRequest<String>> {
+    let hypothesis = parse_hypothesis(hypothesis)?;
+
     let context = DefinitionsContext {
         app: state.app.clone(),
-        entries: to_dictionary_entries(&state.dictionary),
+        entries: to_dictionary_entries(&state.dictionary(), &hypothesis),
+        phrases: to_phrase_entries(&state.dictionary(), &hypothesis),
+    };
+
+    Ok(Template::render("definitions", context))
+}
+
+#[get("/similar?<word>&<distance>&<hypothesis>")]
+fn similar(
+    state: &State<RootContext>,
+    word: &str,
+    distance: Option<u32>,
+    hypothesis: Option<&str>,
+) -> Result<Template, BadRequest<String>> {
+    let query: DictionaryWord = word
+        .parse()
+        .map_err(|error: NotationError| BadRequest(Some(error.to_string())))?;
+    let distance = distance.unwrap_or(2);
+    let hypothesis = parse_hypothesis(hypothesis)?;
+    let dictionary = state.dictionary().as_hypothesis(&hypothesis);
+
+    let words: Vec<SimilarRow> = similar_words(&query, distance, &state.notebook(), &dictionary)
+        .into_iter()
+        .map(|similar_word| {
+            let definition = match similar_word.entry.as_ref().map(Entry::definition) {
+                Some(Definition::Tentative(text)) => text,
+                Some(Definition::Confirmed(text)) => text,
+                Some(Definition::Undefined) => "[Undefined]".to_owned(),
+                None => "".to_owned(),
+            };
+
+            SimilarRow {
+                glyphs: similar_word.word.glyphs(),
+                distance: similar_word.distance,
+                usages: similar_word.usages,
+                definition,
+            }
+        })
+        .collect();
+
+    let context = SimilarContext {
+        app: state.app.clone(),
+        word: query.to_string(),
+        distance,
+        words,
+    };
+
+    Ok(Template::render("similar", context))
+}
+
+#[get("/reverse?<text>")]
+fn reverse(state: &State<RootContext>, text: &str) -> Template {
+    let dictionary = state.dictionary();
+
+    let words: Vec<ReverseRow> = ReverseIndex::new(&dictionary)
+        .lookup(text)
+        .into_iter()
+        .filter_map(|word| {
+            let (definition_type, definition) = match dictionary.get(&word)?.definition() {
+                Definition::Tentative(text) => ("Tentative", text),
+                Definition::Confirmed(text) => ("Confirmed", text),
+                Definition::Undefined => return None,
+            };
+
+            Some(ReverseRow {
+                glyphs: word.glyphs(),
+                definition_type: definition_type.to_owned(),
+                definition,
+            })
+        })
+        .collect();
+
+    let context = ReverseContext {
+        app: state.app.clone(),
+        text: text.to_owned(),
+        words,
+    };
+
+    Template::render("reverse", context)
+}
+
+#[get("/api/snippets?<offset>&<limit>&<text>&<transcribed>&<manual_page>")]
+fn api_snippets(
+    state: &State<RootContext>,
+    offset: Option<usize>,
+    limit: Option<usize>,
+    text: Option<&str>,
+    transcribed: Option<bool>,
+    manual_page: Option<usize>,
+    filter: SnippetFilterQuery,
+) -> Result<Json<ApiPage<ApiSnippet>>, BadRequest<String>> {
+    let text = text.map(str::to_lowercase);
+    let filter = filter.into_filter()?;
+
+    let snippets: Vec<ApiSnippet> = state
+        .notebook()
+        .snippets
+        .iter()
+        .enumerate()
+        .filter(|(_, snippet)| {
+            text.as_ref()
+                .is_none_or(|text| snippet.description.to_lowercase().contains(text))
+                && transcribed.is_none_or(|transcribed| snippet.transcribed == transcribed)
+                && manual_page.is_none_or(|page_number| {
+                    snippet.source == Some(Source::ManualPageNumber(page_number))
+                })
+                && filter.retains(snippet)
+        })
+        .map(|(index, snippet)| ApiSnippet {
+            index,
+            snippet: snippet.clone(),
+        })
+        .collect();
+
+    Ok(Json(ApiPage::new(snippets, offset, limit)))
+}
+
+#[get("/api/snippets/<id>")]
+fn api_snippet(state: &State<RootContext>, id: &str) -> Option<Json<ApiSnippet>> {
+    let notebook = state.notebook();
+    let index = notebook.position_of(&id.parse().ok()?)?;
+    let snippet = notebook.snippets[index].clone();
+
+    Some(Json(ApiSnippet {
+        index,
+        snippet,
+    }))
+}
+
+/// `text` finds words by their candidate readings, the same way as `/reverse`
+#[get("/api/dictionary?<offset>&<limit>&<text>&<defined>&<hypothesis>")]
+fn api_dictionary(
+    state: &State<RootContext>,
+    offset: Option<usize>,
+    limit: Option<usize>,
+    text: Option<&str>,
+    defined: Option<bool>,
+    hypothesis: Option<&str>,
+) -> Result<Json<ApiPage<ApiDictionaryEntry>>, BadRequest<String>> {
+    let hypothesis = parse_hypothesis(hypothesis)?;
+    let dictionary = state.dictionary();
+
+    let words: Vec<DictionaryWord> = match text {
+        Some(text) => ReverseIndex::new(&dictionary).lookup(text),
+        None => {
+            let mut words: Vec<DictionaryWord> = dictionary.entries().keys().cloned().collect();
+            words.sort_by_key(|word| word.to_string());
+
+            words
+        }
     };
 
-    Template::render("definitions", context)
+    let entries: Vec<ApiDictionaryEntry> = words
+        .iter()
+        .filter_map(|word| {
+            let entry = dictionary.get(word)?;
+
+            Some(ApiDictionaryEntry::new(word, entry, &hypothesis))
+        })
+        .filter(|entry| {
+            defined.is_none_or(|defined| {
+                (entry.definition != Definition::Undefined) == defined
+            })
+        })
+        .collect();
+
+    Ok(Json(ApiPage::new(entries, offset, limit)))
 }
 
-fn to_dictionary_entries(dictionary: &Dictionary) -> Vec<DictionaryEntry> {
+#[get("/api/dictionary/<word>?<hypothesis>")]
+fn api_dictionary_word(
+    state: &State<RootContext>,
+    word: &str,
+    hypothesis: Option<&str>,
+) -> Result<Option<Json<ApiDictionaryEntry>>, BadRequest<String>> {
+    let word: DictionaryWord = word
+        .parse()
+        .map_err(|error: NotationError| BadRequest(Some(error.to_string())))?;
+    let hypothesis = parse_hypothesis(hypothesis)?;
+
+    Ok(state
+        .dictionary()
+        .get(&word)
+        .map(|entry| Json(ApiDictionaryEntry::new(&word, entry, &hypothesis))))
+}
+
+/// Snippets with words matching a glyph pattern, as taken by `search pattern`
+#[get("/api/search?<pattern>&<border>&<colored>&<offset>&<limit>")]
+fn api_search(
+    state: &State<RootContext>,
+    pattern: &str,
+    border: Option<bool>,
+    colored: Option<bool>,
+    offset: Option<usize>,
+    limit: Option<usize>,
+) -> Result<Json<ApiPage<ApiSearchResult>>, BadRequest<String>> {
+    let pattern: SearchPattern = pattern
+        .parse()
+        .map_err(|error: NotationError| BadRequest(Some(error.to_string())))?;
+    let pattern = pattern.with_border_as(border).with_colored_as(colored);
+
+    let results: Vec<ApiSearchResult> = state
+        .notebook()
+        .snippets
+        .iter()
+        .enumerate()
+        .filter_map(|(index, snippet)| {
+            let matches = pattern.find_in(&snippet.words);
+
+            (!matches.is_empty()).then(|| ApiSearchResult {
+                index,
+                matches,
+                snippet: snippet.clone(),
+            })
+        })
+        .collect();
+
+    Ok(Json(ApiPage::new(results, offset, limit)))
+}
+
+/// Snippets sent without an ID are given a new one, and the created and
+/// modified times are set to now
+#[post("/api/snippets", data = "<snippet>")]
+fn api_add_snippet(
+    state: &State<RootContext>,
+    _token: EditToken,
+    snippet: Json<Snippet>,
+) -> Result<Json<ApiSnippet>, Custom<String>> {
+    let snippet = snippet.into_inner();
+
+    let (index, snippet) = edit_notebook(state, |notebook| {
+        let now = current_timestamp();
+        let snippet = Snippet {
+            created: Some(now.clone()),
+            modified: Some(now),
+            ..snippet
+        };
+
+        let snippet = if snippet.id.is_empty() {
+            let id = notebook.new_snippet_id();
+
+            snippet.with_id(id)
+        } else if notebook.position_of(&snippet.id).is_some() {
+            return Err(Custom(
+                Status::Conflict,
+                format!("There is already a snippet {}", snippet.id),
+            ));
+        } else {
+            snippet
+        };
+
+        notebook.snippets.push(snippet.clone());
+
+        Ok((notebook.snippets.len() - 1, snippet))
+    })?;
+
+    Ok(Json(ApiSnippet { index, snippet }))
+}
+
+/// The snippet keeps its ID and created time whatever the replacement has
+#[put("/api/snippets/<id>", data = "<snippet>")]
+fn api_replace_snippet(
+    state: &State<RootContext>,
+    _token: EditToken,
+    id: &str,
+    snippet: Json<Snippet>,
+) -> Result<Json<ApiSnippet>, Custom<String>> {
+    edit_snippet(state, id, |existing| {
+        *existing = Snippet {
+            id: existing.id.clone(),
+            created: existing.created.clone(),
+            ..snippet.into_inner()
+        };
+
+        Ok(())
+    })
+}
+
+#[delete("/api/snippets/<id>")]
+fn api_delete_snippet(
+    state: &State<RootContext>,
+    _token: EditToken,
+    id: &str,
+) -> Result<Json<ApiSnippet>, Custom<String>> {
+    let (index, snippet) = edit_notebook(state, |notebook| {
+        let index = snippet_position(notebook, id)?;
+
+        Ok((index, notebook.snippets.remove(index)))
+    })?;
+
+    Ok(Json(ApiSnippet { index, snippet }))
+}
+
+#[put("/api/snippets/<id>/transcribed", data = "<transcribed>")]
+fn api_set_transcribed(
+    state: &State<RootContext>,
+    _token: EditToken,
+    id: &str,
+    transcribed: Json<bool>,
+) -> Result<Json<ApiSnippet>, Custom<String>> {
+    edit_snippet(state, id, |snippet| {
+        snippet.transcribed = transcribed.into_inner();
+
+        Ok(())
+    })
+}
+
+/// Without a `position` the word goes at the end
+#[post("/api/snippets/<id>/words?<position>", data = "<word>")]
+fn api_insert_word(
+    state: &State<RootContext>,
+    _token: EditToken,
+    id: &str,
+    position: Option<usize>,
+    word: Json<Word>,
+) -> Result<Json<ApiSnippet>, Custom<String>> {
+    edit_snippet(state, id, |snippet| {
+        let word_count = snippet.words.len();
+        let position = position.unwrap_or(word_count);
+
+        if position > word_count {
+            return Err(word_not_found(position, word_count));
+        }
+
+        snippet.words.insert(position, word.into_inner());
+
+        Ok(())
+    })
+}
+
+#[put("/api/snippets/<id>/words/<position>", data = "<word>")]
+fn api_replace_word(
+    state: &State<RootContext>,
+    _token: EditToken,
+    id: &str,
+    position: usize,
+    word: Json<Word>,
+) -> Result<Json<ApiSnippet>, Custom<String>> {
+    edit_snippet(state, id, |snippet| {
+        let word_count = snippet.words.len();
+        let existing = snippet
+            .words
+            .get_mut(position)
+            .ok_or_else(|| word_not_found(position, word_count))?;
+
+        *existing = word.into_inner();
+
+        Ok(())
+    })
+}
+
+#[delete("/api/snippets/<id>/words/<position>")]
+fn api_delete_word(
+    state: &State<RootContext>,
+    _token: EditToken,
+    id: &str,
+    position: usize,
+) -> Result<Json<ApiSnippet>, Custom<String>> {
+    edit_snippet(state, id, |snippet| {
+        let word_count = snippet.words.len();
+
+        if position >= word_count {
+            return Err(word_not_found(position, word_count));
+        }
+
+        snippet.words.remove(position);
+
+        Ok(())
+    })
+}
+
+/// Keeps the word's border and colored states
+#[put("/api/snippets/<id>/words/<position>/glyphs", data = "<glyphs>")]
+fn api_replace_glyphs(
+    state: &State<RootContext>,
+    _token: EditToken,
+    id: &str,
+    position: usize,
+    glyphs: Json<Vec<Glyph>>,
+) -> Result<Json<ApiSnippet>, Custom<String>> {
+    edit_snippet(state, id, |snippet| {
+        let word_count = snippet.words.len();
+        let word = snippet
+            .words
+            .get(position)
+            .ok_or_else(|| word_not_found(position, word_count))?;
+
+        let tunic_word = match &word.word_type {
+            WordType::Tunic(tunic_word) => tunic_word.clone(),
+            WordType::English(_) => {
+                return Err(Custom(
+                    Status::BadRequest,
+                    format!("Word {} is an English word and has no glyphs", position),
+                ))
+            }
+        };
+
+        snippet.words[position] = tunic_word.with_glyphs(glyphs.into_inner()).into();
+
+        Ok(())
+    })
+}
+
+/// Replaces every candidate reading of the word with `definition`
+#[put("/api/dictionary/<word>", data = "<definition>")]
+fn api_define_word(
+    state: &State<RootContext>,
+    _token: EditToken,
+    word: &str,
+    definition: Json<Definition>,
+) -> Result<Json<ApiDictionaryEntry>, Custom<String>> {
+    let word = parse_dictionary_word(word)?;
+
+    edit_dictionary(state, &word, |dictionary| {
+        Ok(dictionary.with_definition_for(&word, definition.into_inner()))
+    })
+}
+
+#[post("/api/dictionary/<word>/candidates", data = "<candidate>")]
+fn api_add_candidate(
+    state: &State<RootContext>,
+    _token: EditToken,
+    word: &str,
+    candidate: Json<Candidate>,
+) -> Result<Json<ApiDictionaryEntry>, Custom<String>> {
+    let word = parse_dictionary_word(word)?;
+    let candidate = candidate.into_inner();
+
+    edit_dictionary(state, &word, |dictionary| {
+        let exists = dictionary.get(&word).is_some_and(|entry| {
+            entry
+                .candidates()
+                .iter()
+                .any(|existing| existing.text() == candidate.text())
+        });
+
+        if exists {
+            return Err(Custom(
+                Status::Conflict,
+                format!("{} already has the candidate \"{}\"", word, candidate.text()),
+            ));
+        }
+
+        Ok(dictionary.with_candidate_for(&word, candidate))
+    })
+}
+
+/// Ranks start at 1, as listed by `/api/dictionary/<word>`
+#[delete("/api/dictionary/<word>/candidates/<rank>")]
+fn api_delete_candidate(
+    state: &State<RootContext>,
+    _token: EditToken,
+    word: &str,
+    rank: usize,
+) -> Result<Json<ApiDictionaryEntry>, Custom<String>> {
+    let word = parse_dictionary_word(word)?;
+
+    edit_dictionary(state, &word, |dictionary| {
+        let entry = dictionary
+            .get(&word)
+            .ok_or_else(|| Custom(Status::NotFound, format!("{} isn't in the dictionary", word)))?;
+
+        if rank == 0 || rank > entry.candidates().len() {
+            return Err(Custom(
+                Status::NotFound,
+                format!(
+                    "{} has {} candidate(s), so there is no rank {}",
+                    word,
+                    entry.candidates().len(),
+                    rank
+                ),
+            ));
+        }
+
+        let entry = entry.clone().without_candidate_at(rank - 1);
+
+        Ok(dictionary.with_new_complete_definition(&word, &entry))
+    })
+}
+
+/// Applies `edit` to the notebook as it is on disk and saves it before swapping
+/// it in, so changes made by other tools since the watcher last looked are
+/// kept. Failed edits and failed saves leave the notebook as it was.
+fn edit_notebook<T, F>(state: &RootContext, edit: F) -> Result<T, Custom<String>>
+where
+    F: FnOnce(&mut Notebook) -> Result<T, Custom<String>>,
+{
+    let mut notebook = state.notebook.write().unwrap_or_else(PoisonError::into_inner);
+    let (mut edited, _yaml) =
+        notebook_from_yaml_file(&state.config.notebook).map_err(server_error)?;
+
+    let result = edit(&mut edited)?;
+
+    notebook_to_yaml_file(&edited, &state.config.notebook).map_err(server_error)?;
+    *notebook = edited;
+
+    Ok(result)
+}
+
+fn edit_snippet<F>(
+    state: &RootContext,
+    id: &str,
+    edit: F,
+) -> Result<Json<ApiSnippet>, Custom<String>>
+where
+    F: FnOnce(&mut Snippet) -> Result<(), Custom<String>>,
+{
+    let (index, snippet) = edit_notebook(state, |notebook| {
+        let index = snippet_position(notebook, id)?;
+
+        let snippet = &mut notebook.snippets[index];
+        edit(snippet)?;
+        snippet.modified = Some(current_timestamp());
+
+        Ok((index, snippet.clone()))
+    })?;
+
+    Ok(Json(ApiSnippet { index, snippet }))
+}
+
+/// Like `edit_notebook`, returning the word's entry as saved
+fn edit_dictionary<F>(
+    state: &RootContext,
+    word: &DictionaryWord,
+    edit: F,
+) -> Result<Json<ApiDictionaryEntry>, Custom<String>>
+where
+    F: FnOnce(Dictionary) -> Result<Dictionary, Custom<String>>,
+{
+    let mut dictionary = state.dictionary.write().unwrap_or_else(PoisonError::into_inner);
+    let (on_disk, _yaml) =
+        dictionary_from_yaml_file(&state.config.dictionary).map_err(server_error)?;
+
+    let edited = edit(on_disk)?;
+
+    dictionary_to_yaml_file(&edited, &state.config.dictionary).map_err(server_error)?;
+    *dictionary = edited;
+
+    let entry = dictionary.get(word).cloned().unwrap_or_default();
+
+    Ok(Json(ApiDictionaryEntry::new(word, &entry, &Hypothesis::Preferred)))
+}
+
+fn snippet_position(notebook: &Notebook, id: &str) -> Result<usize, Custom<String>> {
+    let id: SnippetId = id
+        .parse()
+        .map_err(|error: FennecError| Custom(Status::BadRequest, error.to_string()))?;
+
+    notebook
+        .position_of(&id)
+        .ok_or_else(|| Custom(Status::NotFound, format!("There is no snippet {}", id)))
+}
+
+fn word_not_found(position: usize, word_count: usize) -> Custom<String> {
+    Custom(
+        Status::NotFound,
+        format!("There is no word {}, the snippet has {}", position, word_count),
+    )
+}
+
+fn parse_dictionary_word(word: &str) -> Result<DictionaryWord, Custom<String>> {
+    word.parse()
+        .map_err(|error: NotationError| Custom(Status::BadRequest, error.to_string()))
+}
+
+fn server_error(error: FennecError) -> Custom<String> {
+    Custom(Status::InternalServerError, error.to_string())
+}
+
+/// Reloads files changed on disk, whether by the editing routes or another
+/// tool, and tells open pages. Files that fail to load keep their last
+/// version until they change again.
+fn watch_files(
+    config: FennecConfig,
+    notebook: Arc<RwLock<Notebook>>,
+    dictionary: Arc<RwLock<Dictionary>>,
+    updates: Sender<String>,
+) {
+    let mut watcher = FileWatcher::new(&[&config.notebook, &config.dictionary]);
+
+    loop {
+        thread::sleep(FILE_WATCH_INTERVAL);
+
+        for file in watcher.changed_files() {
+            let reloaded = if file == config.notebook {
+                notebook_from_yaml_file(&file).map(|(loaded, _yaml)| {
+                    *notebook.write().unwrap_or_else(PoisonError::into_inner) = loaded;
+                    "notebook"
+                })
+            } else {
+                dictionary_from_yaml_file(&file).map(|(loaded, _yaml)| {
+                    *dictionary.write().unwrap_or_else(PoisonError::into_inner) = loaded;
+                    "dictionary"
+                })
+            };
+
+            match reloaded {
+                // Sending only fails without any open pages
+                Ok(subject) => {
+                    let _ = updates.send(subject.to_owned());
+                }
+                Err(error) => eprintln!("error: Failed to reload {}: {}", file, error),
+            }
+        }
+    }
+}
+
+/// 128 bits from the operating system's random source
+fn generate_token() -> String {
+    let mut bytes = [0u8; 16];
+    getrandom::getrandom(&mut bytes).unwrap_or_else(|error| {
+        eprintln!("error: Unable to make an editing token: {}", error);
+        process::exit(71);
+    });
+
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Takes as long wherever the first difference is, so response times don't
+/// give the token away
+fn is_same_token(expected: &str, given: &str) -> bool {
+    expected.len() == given.len()
+        && expected
+            .bytes()
+            .zip(given.bytes())
+            .fold(0, |difference, (a, b)| difference | (a ^ b))
+            == 0
+}
+
+/// Missing means the preferred candidate
+fn parse_hypothesis(hypothesis: Option<&str>) -> Result<Hypothesis, BadRequest<String>> {
+    match hypothesis {
+        Some(hypothesis) => hypothesis
+            .parse()
+            .map_err(|error: FennecError| BadRequest(Some(error.to_string()))),
+        None => Ok(Hypothesis::Preferred),
+    }
+}
+
+/// Missing means every snippet
+fn parse_since(since: Option<&str>) -> Result<Option<DateTime<Utc>>, BadRequest<String>> {
+    since
+        .map(parse_date_or_timestamp)
+        .transpose()
+        .map_err(|error| BadRequest(Some(error.to_string())))
+}
+
+fn to_dictionary_entries(
+    dictionary: &Dictionary,
+    hypothesis: &Hypothesis,
+) -> Vec<DictionaryEntry> {
      dictionary
         .entries()
         .iter()
         .map(|(word, entry)| {
             let glyphs = word.glyphs();
-            let definition =  match entry.definition() {
+            let definition =  match entry.definition_as(hypothesis) {
                 Definition::Undefined => "[Undefined]".to_owned(),
-                Definition::Tentative(text) => text.clone(),
-                Definition::Confirmed(text) => text.clone(),
+                Definition::Tentative(text) => text,
+                Definition::Confirmed(text) => text,
             };
+            let candidates: Vec<CandidateRow> = entry
+                .candidates()
+                .iter()
+                .map(|candidate| CandidateRow {
+                    text: candidate.text().to_owned(),
+                    confidence: candidate.confidence(),
+                    author: candidate.author().map(str::to_owned),
+                    date: candidate.date().map(str::to_owned),
+                    snippets: candidate.snippets().clone(),
+                    confirmed: candidate.is_confirmed(),
+                })
+                .collect();
             let notes: Vec<String> = entry
                 .notes()
                 .iter()
@@ -178,40 +1162,234 @@ fn to_dictionary_entries(dictionary: &Dictionary) -> Vec<DictionaryEntry> {
             DictionaryEntry {
                 glyphs,
                 definition,
+                candidates,
                 notes,
             }
         })
         .collect()
 }
 
+fn to_phrase_entries(dictionary: &Dictionary, hypothesis: &Hypothesis) -> Vec<PhraseEntry> {
+    dictionary
+        .phrases()
+        .iter()
+        .map(|(phrase, entry)| {
+            let definition = match entry.definition_as(hypothesis) {
+                Definition::Undefined => "[Undefined]".to_owned(),
+                Definition::Tentative(text) => text,
+                Definition::Confirmed(text) => text,
+            };
+
+            PhraseEntry {
+                phrase: phrase.to_string(),
+                definition,
+            }
+        })
+        .collect()
+}
+
 #[launch]
 fn rocket() -> _ {
-    let (notebook, _yaml) = notebook_from_yaml_file(DEFAULT_NOTEBOOK_FILE)
-        .unwrap_or_else(|error| {
-            println!("Unable to load notebook file: {}", DEFAULT_NOTEBOOK_FILE);
-            println!("{:?}", error);
-            panic!("Search aborted");
-        });
+    let cli = Cli::parse();
 
-    let (dictionary, _yaml) = dictionary_from_yaml_file(DEFAULT_DICTIONARY_FILE)
-        .unwrap_or_else(|error| {
-            println!("Unable to load dictionary file: {}", DEFAULT_NOTEBOOK_FILE);
-            println!("{:?}", error);
-            panic!("Search aborted");
-        });
+    let config = FennecConfig::from_args(&cli.paths).unwrap_or_else(|error| error.exit());
+
+    let (notebook, _yaml) =
+        notebook_from_yaml_file(&config.notebook).unwrap_or_else(|error| error.exit());
+
+    let (dictionary, _yaml) =
+        dictionary_from_yaml_file(&config.dictionary).unwrap_or_else(|error| error.exit());
+
+    let token = cli.token.unwrap_or_else(|| {
+        let token = generate_token();
+        println!("Editing token: {}", token);
+
+        token
+    });
+
+    let notebook = Arc::new(RwLock::new(notebook));
+    let dictionary = Arc::new(RwLock::new(dictionary));
+    let (updates, _receiver) = broadcast::channel::<String>(16);
+
+    {
+        let (config, notebook, dictionary, updates) =
+            (config.clone(), notebook.clone(), dictionary.clone(), updates.clone());
+
+        thread::spawn(move || watch_files(config, notebook, dictionary, updates));
+    }
 
     let root_context = RootContext {
         app: AppContext {
             title: "Fennec",
             appname: "Fennec",
         },
+        config: config.clone(),
+        token,
         notebook,
         dictionary,
     };
 
-    rocket::build()
-        .mount("/", routes![index, definitions, snippets])
-        .mount("/media", FileServer::from(relative!("sources")))
-        .manage(root_context)
+    with_api_routes(rocket::build(), root_context)
+        .mount("/", routes![index, events, definitions, snippets, snippet_svg, similar, reverse])
+        .mount("/media", FileServer::from(&config.sources))
+        .manage(updates)
         .attach(Template::fairing())
 }
+
+/// The JSON routes need no templates, so they can be tried on their own
+fn with_api_routes(rocket: Rocket<Build>, root_context: RootContext<'static>) -> Rocket<Build> {
+    rocket
+        .mount(
+            "/",
+            routes![api_snippets, api_snippet, api_dictionary, api_dictionary_word, api_search],
+        )
+        .mount(
+            "/",
+            routes![
+                api_add_snippet,
+                api_replace_snippet,
+                api_delete_snippet,
+                api_set_transcribed,
+                api_insert_word,
+                api_replace_word,
+                api_delete_word,
+                api_replace_glyphs,
+                api_define_word,
+                api_add_candidate,
+                api_delete_candidate,
+            ],
+        )
+        .manage(root_context)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rocket::http::Header;
+    use rocket::local::blocking::{Client, LocalRequest};
+    use tempfile::TempDir;
+
+    const TOKEN: &str = "test-token";
+
+    /// Serves the API for `notebook` and an empty dictionary saved in `directory`
+    fn client_with(directory: &TempDir, notebook: Notebook) -> Client {
+        let path = |file: &str| directory.path().join(file).to_string_lossy().into_owned();
+        let config = FennecConfig {
+            notebook: path("notebook.yaml"),
+            dictionary: path("dictionary.yaml"),
+            ..FennecConfig::default()
+        };
+        let dictionary = Dictionary::new();
+
+        dictionary_to_yaml_file(&dictionary, &config.dictionary).unwrap();
+
+        let root_context = RootContext {
+            app: AppContext {
+                title: "Fennec",
+                appname: "Fennec",
+            },
+            config,
+            token: TOKEN.to_owned(),
+            notebook: Arc::new(RwLock::new(notebook)),
+            dictionary: Arc::new(RwLock::new(dictionary)),
+        };
+
+        Client::tracked(with_api_routes(rocket::build(), root_context)).unwrap()
+    }
+
+    fn client_in(directory: &TempDir) -> Client {
+        client_with(directory, Notebook::default())
+    }
+
+    fn with_token(request: LocalRequest<'_>) -> LocalRequest<'_> {
+        request.header(Header::new("Authorization", format!("Bearer {}", TOKEN)))
+    }
+
+    fn candidates_on_disk(directory: &TempDir) -> Vec<String> {
+        let path = directory.path().join("dictionary.yaml");
+        let (dictionary, _yaml) = dictionary_from_yaml_file(&path.to_string_lossy()).unwrap();
+        let word: DictionaryWord = "4352".parse().unwrap();
+
+        dictionary.get(&word).map_or(vec![], |entry| {
+            entry.candidates().iter().map(|candidate| candidate.text().to_owned()).collect()
+        })
+    }
+
+    #[test]
+    fn dictionary_edits_are_saved() {
+        let directory = tempfile::tempdir().unwrap();
+        let client = client_in(&directory);
+
+        let response = with_token(client.put("/api/dictionary/4352"))
+            .header(ContentType::JSON)
+            .body(r#"{"Tentative": "sword"}"#)
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(candidates_on_disk(&directory), vec!["sword".to_owned()]);
+
+        let add_blade = || {
+            with_token(client.post("/api/dictionary/4352/candidates"))
+                .header(ContentType::JSON)
+                .body(r#"{"text": "blade", "confidence": 80}"#)
+                .dispatch()
+        };
+        assert_eq!(add_blade().status(), Status::Ok);
+        assert_eq!(add_blade().status(), Status::Conflict);
+        assert_eq!(candidates_on_disk(&directory), vec!["blade".to_owned(), "sword".to_owned()]);
+
+        let response = with_token(client.delete("/api/dictionary/4352/candidates/1")).dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(candidates_on_disk(&directory), vec!["sword".to_owned()]);
+
+        let response = with_token(client.delete("/api/dictionary/4352/candidates/2")).dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+    }
+
+    #[test]
+    fn snippets_are_filtered_by_the_query() {
+        let directory = tempfile::tempdir().unwrap();
+        let snippet = |tags: &str, location: &str| {
+            Snippet::default()
+                .with_tags_text(tags)
+                .with_location(Some(location.into()))
+                .with_modified_at("2023-03-14T09:30:00Z".into())
+        };
+        let notebook: Notebook = vec![
+            snippet("sign", "West Garden"),
+            snippet("sign, npc", "Old House"),
+            snippet("npc", "Garden"),
+        ]
+        .into();
+        let client = client_with(&directory, notebook);
+
+        let total = |query: &str| {
+            let response = client.get(format!("/api/snippets?{}", query)).dispatch();
+            let page: serde_json::Value = response.into_json().unwrap();
+
+            page["total"].as_u64().unwrap()
+        };
+
+        assert_eq!(total("limit=1&tag=sign"), 2);
+        assert_eq!(total("tag=sign&tag=npc"), 1);
+        assert_eq!(total("location=garden&since=2023-03-14"), 2);
+        assert_eq!(total("since=2023-03-15"), 0);
+
+        let response = client.get("/api/snippets?since=yesterday").dispatch();
+        assert_eq!(response.status(), Status::BadRequest);
+    }
+
+    #[test]
+    fn dictionary_edits_need_the_token() {
+        let directory = tempfile::tempdir().unwrap();
+        let client = client_in(&directory);
+
+        let response = client
+            .put("/api/dictionary/4352")
+            .header(ContentType::JSON)
+            .body(r#"{"Tentative": "sword"}"#)
+            .dispatch();
+
+        assert_eq!(response.status(), Status::Unauthorized);
+        assert!(candidates_on_disk(&directory).is_empty());
+    }
+}
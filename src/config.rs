@@ -0,0 +1,186 @@
+use clap::Args;
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::prelude::*;
+
+/// Window settings for the fennec GUI. The width and height are in tiles, and
+/// the tile size is how big each glyph is drawn. The glyph font itself is always
+/// read at its own size, so other tile sizes scale it.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WindowConfig {
+    pub width: i32,
+    pub height: i32,
+    pub tile_width: i32,
+    pub tile_height: i32,
+    pub resources: String,
+    pub glyph_font: String,
+    pub small_text_font: String,
+    pub large_text_font: String,
+}
+
+impl Default for WindowConfig {
+    fn default() -> Self {
+        Self {
+            width: DISPLAY_WIDTH,
+            height: DISPLAY_HEIGHT,
+            tile_width: GLYPH_FONT_WIDTH,
+            tile_height: GLYPH_FONT_HEIGHT,
+            resources: "resources/".into(),
+            glyph_font: "tunic_glyphs.png".into(),
+            small_text_font: "dbyte_1x.png".into(),
+            large_text_font: "dbyte_2x.png".into(),
+        }
+    }
+}
+
+/// Project settings read from `fennec.toml`. Relative paths in the file are
+/// relative to the directory the file is in.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FennecConfig {
+    pub notebook: String,
+    pub dictionary: String,
+    pub phonemes: String,
+    pub sources: String,
+    pub window: WindowConfig,
+}
+
+impl Default for FennecConfig {
+    fn default() -> Self {
+        Self {
+            notebook: DEFAULT_NOTEBOOK_FILE.into(),
+            dictionary: DEFAULT_DICTIONARY_FILE.into(),
+            phonemes: DEFAULT_PHONEME_TABLE_FILE.into(),
+            sources: DEFAULT_SOURCES_DIRECTORY.into(),
+            window: WindowConfig::default(),
+        }
+    }
+}
+
+impl FennecConfig {
+    pub fn from_toml_file(target_file: &Path) -> FennecResult<Self> {
+        let path = target_file.to_string_lossy();
+
+        let text = fs::read_to_string(target_file).map_err(|error| FennecError::io(&path, error))?;
+        let config: Self = toml::from_str(&text).map_err(|error| FennecError::toml(&path, error))?;
+
+        let base_directory = target_file.parent().unwrap_or_else(|| Path::new(""));
+
+        Ok(config.relative_to(base_directory))
+    }
+
+    /// Looks for `fennec.toml` in the working directory and then each of its
+    /// parents. Without one, the defaults are used.
+    pub fn discover() -> FennecResult<Self> {
+        let working_directory = env::current_dir().map_err(|error| FennecError::io(".", error))?;
+
+        match find_config_file(&working_directory) {
+            Some(target_file) => Self::from_toml_file(&target_file),
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// Loads the config named by `--config`, or discovers one, then applies the
+    /// path flags on top
+    pub fn from_args(args: &ConfigArgs) -> FennecResult<Self> {
+        let config = match &args.config {
+            Some(target_file) => Self::from_toml_file(Path::new(target_file))?,
+            None => Self::discover()?,
+        };
+
+        Ok(config.with_overrides(args))
+    }
+
+    pub fn with_overrides(self, args: &ConfigArgs) -> Self {
+        Self {
+            notebook: args.notebook.clone().unwrap_or(self.notebook),
+            dictionary: args.dictionary.clone().unwrap_or(self.dictionary),
+            sources: args.sources.clone().unwrap_or(self.sources),
+            ..self
+        }
+    }
+
+    fn relative_to(self, base_directory: &Path) -> Self {
+        let resolve = |path: String| -> String {
+            base_directory.join(path).to_string_lossy().into_owned()
+        };
+
+        Self {
+            notebook: resolve(self.notebook),
+            dictionary: resolve(self.dictionary),
+            phonemes: resolve(self.phonemes),
+            sources: resolve(self.sources),
+            window: WindowConfig {
+                resources: resolve(self.window.resources),
+                ..self.window
+            },
+        }
+    }
+}
+
+fn find_config_file(start_directory: &Path) -> Option<PathBuf> {
+    start_directory
+        .ancestors()
+        .map(|directory| directory.join(CONFIG_FILE))
+        .find(|target_file| target_file.is_file())
+}
+
+/// Path flags shared by every binary. They take precedence over `fennec.toml`.
+#[derive(Args, Clone, Debug, Default)]
+pub struct ConfigArgs {
+    /// Project config file [default: the nearest fennec.toml]
+    #[arg(long, global = true)]
+    pub config: Option<String>,
+
+    /// Notebook file [default: notebook.yaml]
+    #[arg(long, global = true)]
+    pub notebook: Option<String>,
+
+    /// Dictionary file [default: dictionary.yaml]
+    #[arg(long, global = true)]
+    pub dictionary: Option<String>,
+
+    /// Directory of manual pages and screenshots [default: sources/]
+    #[arg(long, global = true)]
+    pub sources: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_settings_use_the_defaults() {
+        let config: FennecConfig = toml::from_str("notebook = \"playthrough-2.yaml\"").unwrap();
+
+        assert_eq!(config.notebook, "playthrough-2.yaml");
+        assert_eq!(config.dictionary, DEFAULT_DICTIONARY_FILE);
+        assert_eq!(config.window, WindowConfig::default());
+    }
+
+    #[test]
+    fn paths_are_relative_to_the_config_file() {
+        let config = FennecConfig::default().relative_to(Path::new("/games/tunic"));
+
+        assert_eq!(config.notebook, "/games/tunic/notebook.yaml");
+        assert_eq!(config.sources, "/games/tunic/sources");
+        assert_eq!(config.window.resources, "/games/tunic/resources/");
+    }
+
+    #[test]
+    fn flags_take_precedence() {
+        let args = ConfigArgs {
+            notebook: Some("other.yaml".into()),
+            ..ConfigArgs::default()
+        };
+
+        let config = FennecConfig::default().with_overrides(&args);
+
+        assert_eq!(config.notebook, "other.yaml");
+        assert_eq!(config.dictionary, DEFAULT_DICTIONARY_FILE);
+    }
+}
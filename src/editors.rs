@@ -1,6 +1,9 @@
+pub mod dictionary_editors;
 pub mod events;
 pub mod file_editors;
 pub mod glyph_editors;
+pub mod history;
+pub mod line_editors;
 pub mod notebook_editors;
 pub mod snippet_editors;
 pub mod word_editors;
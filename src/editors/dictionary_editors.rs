@@ -0,0 +1,729 @@
+use std::cmp;
+use std::error::Error;
+
+use crate::prelude::*;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DictionaryField {
+    Definition,
+    NewCandidate,
+    NewNote,
+}
+
+const CONFIDENCE_STEP: u8 = 10;
+
+/// Defines and annotates the word selected in the notebook. The dictionary is
+/// saved separately from the notebook, with the same confirmation steps.
+///
+/// The reverse lookup searches definitions for English text. Entries opened from
+/// its results go back to the lookup when closed.
+///
+/// The notebook shows the candidate `hypothesis` picks for each word, which is
+/// kept here alongside the dictionary it applies to.
+///
+/// `displayed_dictionary` and `reverse_index` are built from the dictionary
+/// whenever it or the hypothesis changes, rather than on every frame.
+///
+/// Changes on disk are merged entry by entry with unsaved edits, using
+/// `saved_dictionary` as the common version. Conflicting ones wait in
+/// `changed_on_disk`, with the entries in `conflicts`, until the user picks a
+/// side.
+#[derive(Clone, Debug)]
+pub struct DictionaryEditor {
+    dictionary: Dictionary,
+    saved_dictionary: Dictionary,
+    changed_on_disk: Option<Dictionary>,
+    conflicts: Vec<String>,
+    target_file: String,
+    state: FileEditorState,
+    selected_word: Option<DictionaryWord>,
+    editing_field: DictionaryField,
+    line_editor: Option<LineEditor>,
+    candidate_cursor: usize,
+    reverse_lookup: Option<LineEditor>,
+    reverse_lookup_cursor: usize,
+    reverse_index: ReverseIndex,
+    hypothesis: Hypothesis,
+    displayed_dictionary: Dictionary,
+}
+
+impl DictionaryEditor {
+    pub fn new(dictionary: Dictionary, filename: &str) -> Self {
+        let hypothesis = Hypothesis::Preferred;
+
+        Self {
+            saved_dictionary: dictionary.clone(),
+            changed_on_disk: None,
+            conflicts: vec![],
+            reverse_index: ReverseIndex::new(&dictionary),
+            displayed_dictionary: dictionary.as_hypothesis(&hypothesis),
+            dictionary,
+            target_file: filename.into(),
+            state: FileEditorState::Idle,
+            selected_word: None,
+            editing_field: DictionaryField::Definition,
+            line_editor: None,
+            candidate_cursor: 0,
+            reverse_lookup: None,
+            reverse_lookup_cursor: 0,
+            hypothesis,
+        }
+    }
+
+    pub fn dictionary(&self) -> &Dictionary {
+        &self.dictionary
+    }
+
+    pub fn hypothesis(&self) -> &Hypothesis {
+        &self.hypothesis
+    }
+
+    /// The dictionary as the notebook shows it
+    pub fn displayed_dictionary(&self) -> &Dictionary {
+        &self.displayed_dictionary
+    }
+
+    pub fn state(&self) -> FileEditorState {
+        self.state.clone()
+    }
+
+    pub fn target_file(&self) -> String {
+        self.target_file.clone()
+    }
+
+    pub fn conflicts(&self) -> &[String] {
+        &self.conflicts
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.selected_word.is_some() || self.reverse_lookup.is_some()
+    }
+
+    pub fn is_entry_open(&self) -> bool {
+        self.selected_word.is_some()
+    }
+
+    pub fn is_editing_text(&self) -> bool {
+        self.line_editor.is_some()
+    }
+
+    /// Whether `event` should go to this editor rather than the notebook
+    pub fn handles(&self, event: &EditorEvent) -> bool {
+        self.is_open()
+            || matches!(
+                event,
+                EditorEvent::OpenDictionaryEntry(_)
+                    | EditorEvent::OpenReverseLookup
+                    | EditorEvent::CycleDisplayedHypothesis
+                    | EditorEvent::ReportDictionaryChangedOnDisk(_)
+                    | EditorEvent::AcceptDictionaryChangesOnDisk
+                    | EditorEvent::KeepDictionaryEdits
+            )
+    }
+
+    pub fn has_unsaved_changes(&self) -> bool {
+        self.dictionary != self.saved_dictionary
+    }
+
+    /// Words whose definitions match the reverse lookup text
+    pub fn reverse_lookup_results(&self) -> Vec<DictionaryWord> {
+        match &self.reverse_lookup {
+            Some(line_editor) => self.reverse_index.lookup(&line_editor.text()),
+            None => vec![],
+        }
+    }
+
+    pub fn selected_reverse_lookup_result(&self) -> Option<DictionaryWord> {
+        self.reverse_lookup_results()
+            .get(self.reverse_lookup_cursor)
+            .cloned()
+    }
+
+    pub fn on_input(&self, callback: Box<dyn Fn(&Self) -> EditorEvent>) -> EditorEvent {
+        callback(self)
+    }
+
+    fn with_state(self, state: FileEditorState) -> Self {
+        Self { state, ..self }
+    }
+
+    /// Every change to the dictionary goes through here to keep what is built
+    /// from it up to date
+    fn with_dictionary(self, dictionary: Dictionary) -> Self {
+        Self {
+            reverse_index: ReverseIndex::new(&dictionary),
+            displayed_dictionary: dictionary.as_hypothesis(&self.hypothesis),
+            dictionary,
+            ..self
+        }
+    }
+
+    fn with_hypothesis(self, hypothesis: Hypothesis) -> Self {
+        Self {
+            displayed_dictionary: self.dictionary.as_hypothesis(&hypothesis),
+            hypothesis,
+            ..self
+        }
+    }
+
+    fn with_change_on_disk(self, dictionary: Dictionary) -> Self {
+        if dictionary == self.saved_dictionary {
+            return self;
+        }
+
+        match self.dictionary.merged_with(&self.saved_dictionary, &dictionary) {
+            Ok(merged) => Self {
+                saved_dictionary: dictionary,
+                ..self
+            }
+            .with_dictionary(merged),
+            Err(conflicts) => Self {
+                changed_on_disk: Some(dictionary),
+                conflicts,
+                state: FileEditorState::ChangedOnDisk,
+                ..self
+            },
+        }
+    }
+
+    fn with_changes_on_disk_accepted(self) -> Self {
+        match self.changed_on_disk {
+            Some(dictionary) => Self {
+                saved_dictionary: dictionary.clone(),
+                changed_on_disk: None,
+                conflicts: vec![],
+                line_editor: None,
+                candidate_cursor: 0,
+                state: FileEditorState::Idle,
+                ..self
+            }
+            .with_dictionary(dictionary),
+            None => self.with_state(FileEditorState::Idle),
+        }
+    }
+
+    /// The unsaved edits stay unsaved, and saving them replaces the version on disk
+    fn with_edits_kept(self) -> Self {
+        Self {
+            saved_dictionary: self.changed_on_disk.unwrap_or(self.saved_dictionary),
+            changed_on_disk: None,
+            conflicts: vec![],
+            state: FileEditorState::Idle,
+            ..self
+        }
+    }
+
+    fn selected_entry(&self) -> Option<Entry> {
+        self.selected_word
+            .as_ref()
+            .map(|word| self.dictionary.get(word).cloned().unwrap_or_default())
+    }
+
+    fn with_entry_opened(self, word: DictionaryWord) -> Self {
+        Self {
+            selected_word: Some(word),
+            line_editor: None,
+            candidate_cursor: 0,
+            ..self
+        }
+    }
+
+    fn with_entry_closed(self) -> Self {
+        Self {
+            selected_word: None,
+            line_editor: None,
+            ..self
+        }
+    }
+
+    fn with_reverse_lookup_opened(self) -> Self {
+        Self {
+            reverse_lookup: Some(LineEditor::default()),
+            reverse_lookup_cursor: 0,
+            ..self
+        }
+    }
+
+    fn with_reverse_lookup_closed(self) -> Self {
+        Self {
+            reverse_lookup: None,
+            reverse_lookup_cursor: 0,
+            ..self
+        }
+    }
+
+    fn with_reverse_lookup_cursor_moved_forward(self) -> Self {
+        let last_result = self.reverse_lookup_results().len().saturating_sub(1);
+
+        Self {
+            reverse_lookup_cursor: cmp::min(self.reverse_lookup_cursor + 1, last_result),
+            ..self
+        }
+    }
+
+    fn with_reverse_lookup_cursor_moved_backward(self) -> Self {
+        Self {
+            reverse_lookup_cursor: self.reverse_lookup_cursor.saturating_sub(1),
+            ..self
+        }
+    }
+
+    /// Text goes to the field being edited, or else to the reverse lookup.
+    /// Changing the lookup text starts the results over from the top.
+    fn with_text_event_applied(self, event: EditorEvent) -> Self {
+        match (self.line_editor, self.reverse_lookup) {
+            (Some(line_editor), reverse_lookup) => Self {
+                line_editor: Some(line_editor.apply(event)),
+                reverse_lookup,
+                ..self
+            },
+            (None, Some(reverse_lookup)) if self.selected_word.is_none() => Self {
+                line_editor: None,
+                reverse_lookup: Some(reverse_lookup.apply(event)),
+                reverse_lookup_cursor: 0,
+                ..self
+            },
+            (line_editor, reverse_lookup) => Self {
+                line_editor,
+                reverse_lookup,
+                ..self
+            },
+        }
+    }
+
+    fn selected_candidate(&self) -> Option<Candidate> {
+        self.selected_entry()?
+            .candidates()
+            .get(self.candidate_cursor)
+            .cloned()
+    }
+
+    /// Replaces the open entry, keeping the cursor on `candidate` wherever the
+    /// ranking moved it
+    fn with_selected_entry(self, entry: Entry, candidate: Option<&Candidate>) -> Self {
+        let word = match &self.selected_word {
+            Some(word) => word.clone(),
+            None => return self,
+        };
+
+        let last_candidate = entry.candidates().len().saturating_sub(1);
+        let candidate_cursor = candidate
+            .and_then(|candidate| entry.candidates().iter().position(|c| c == candidate))
+            .unwrap_or(cmp::min(self.candidate_cursor, last_candidate));
+
+        let dictionary = self.dictionary.clone().with_new_complete_definition(&word, &entry);
+
+        Self {
+            candidate_cursor,
+            ..self
+        }
+        .with_dictionary(dictionary)
+    }
+
+    fn with_candidate_cursor_moved_forward(self) -> Self {
+        let last_candidate = self
+            .selected_entry()
+            .map_or(0, |entry| entry.candidates().len().saturating_sub(1));
+
+        Self {
+            candidate_cursor: cmp::min(self.candidate_cursor + 1, last_candidate),
+            ..self
+        }
+    }
+
+    fn with_candidate_cursor_moved_backward(self) -> Self {
+        Self {
+            candidate_cursor: self.candidate_cursor.saturating_sub(1),
+            ..self
+        }
+    }
+
+    fn with_selected_candidate_confirmation_toggled(self) -> Self {
+        let (entry, candidate) = match (self.selected_entry(), self.selected_candidate()) {
+            (Some(entry), Some(candidate)) => (entry, candidate),
+            _ => return self,
+        };
+
+        // Found by the whole candidate, since two can share the same text
+        let confirmed = !candidate.is_confirmed();
+        let candidate = candidate.with_confirmed_as(confirmed);
+        let entry = entry.with_confirmation_toggled_at(self.candidate_cursor);
+
+        self.with_selected_entry(entry, Some(&candidate))
+    }
+
+    fn with_selected_candidate_confidence_changed(self, raised: bool) -> Self {
+        let (entry, candidate) = match (self.selected_entry(), self.selected_candidate()) {
+            (Some(entry), Some(candidate)) => (entry, candidate),
+            _ => return self,
+        };
+
+        let confidence = if raised {
+            candidate.confidence().saturating_add(CONFIDENCE_STEP)
+        } else {
+            candidate.confidence().saturating_sub(CONFIDENCE_STEP)
+        };
+        let candidate = candidate.with_confidence(confidence);
+
+        let entry = entry.with_candidate_at(self.candidate_cursor, candidate.clone());
+
+        self.with_selected_entry(entry, Some(&candidate))
+    }
+
+    fn with_selected_candidate_deleted(self) -> Self {
+        match self.selected_entry() {
+            Some(entry) => {
+                let entry = entry.without_candidate_at(self.candidate_cursor);
+
+                self.with_selected_entry(entry, None)
+            }
+            None => self,
+        }
+    }
+
+    fn with_field_opened(self, field: DictionaryField) -> Self {
+        let (field, text) = match (field, self.selected_candidate()) {
+            (DictionaryField::Definition, Some(candidate)) => (field, candidate.text().to_owned()),
+            // With nothing to edit, a new candidate is added instead
+            (DictionaryField::Definition, None) => (DictionaryField::NewCandidate, String::new()),
+            _ => (field, String::new()),
+        };
+
+        Self {
+            editing_field: field,
+            line_editor: Some(LineEditor::new(&text)),
+            ..self
+        }
+    }
+
+    fn with_field_edit_confirmed(self) -> Self {
+        let (word, entry, text) = match (&self.selected_word, self.selected_entry(), &self.line_editor) {
+            (Some(word), Some(entry), Some(line_editor)) => (word.clone(), entry, line_editor.text()),
+            _ => return self,
+        };
+
+        let editor = Self {
+            line_editor: None,
+            ..self
+        };
+
+        match editor.editing_field {
+            // Clearing the text of a candidate removes it
+            DictionaryField::Definition if text.trim().is_empty() => {
+                editor.with_selected_candidate_deleted()
+            }
+            DictionaryField::Definition => match editor.selected_candidate() {
+                Some(candidate) => {
+                    let candidate = candidate.with_text(&text);
+                    let entry = entry.with_candidate_at(editor.candidate_cursor, candidate.clone());
+
+                    editor.with_selected_entry(entry, Some(&candidate))
+                }
+                None => editor,
+            },
+            DictionaryField::NewCandidate if text.trim().is_empty() => editor,
+            DictionaryField::NewCandidate => {
+                let candidate = Candidate::new(&text);
+                let entry = entry.with_candidate(candidate.clone());
+
+                editor.with_selected_entry(entry, Some(&candidate))
+            }
+            DictionaryField::NewNote if text.trim().is_empty() => editor,
+            DictionaryField::NewNote => {
+                let note = text.as_str().into();
+                let dictionary = editor.dictionary.clone().with_note_for(&word, note);
+
+                editor.with_dictionary(dictionary)
+            }
+        }
+    }
+
+    fn with_field_edit_cancelled(self) -> Self {
+        Self {
+            line_editor: None,
+            ..self
+        }
+    }
+
+    pub fn render_with<R>(&self, mut renderer: R) -> Result<(), Box<dyn Error>>
+    where
+        R: FnMut(DictionaryEditorView) -> Result<(), Box<dyn Error>>,
+    {
+        renderer(self.to_view())
+    }
+
+    pub fn to_view(&self) -> DictionaryEditorView {
+        let reverse_lookup_view = self.reverse_lookup.as_ref().map(|line_editor| {
+            let results = self
+                .reverse_lookup_results()
+                .into_iter()
+                .map(|word| {
+                    let entry = self.dictionary.get(&word).cloned().unwrap_or_default();
+
+                    (word, entry)
+                })
+                .collect();
+
+            ReverseLookupView {
+                line_editor_view: line_editor.to_view(),
+                results,
+                selected: self.reverse_lookup_cursor,
+            }
+        });
+
+        DictionaryEditorView {
+            word: self.selected_word.clone(),
+            entry: self.selected_entry(),
+            selected_candidate: self.candidate_cursor,
+            editing_field: self.editing_field,
+            line_editor_view: self.line_editor.as_ref().map(|editor| editor.to_view()),
+            state: self.state.clone(),
+            target_file: self.target_file.clone(),
+            conflicts: self.conflicts.clone(),
+            reverse_lookup_view,
+        }
+    }
+}
+
+impl AppliesEditorEvents for DictionaryEditor {
+    fn apply(self, event: EditorEvent) -> Self {
+        match event {
+            EditorEvent::OpenDictionaryEntry(word) => self.with_entry_opened(word),
+            EditorEvent::CloseDictionaryEntry => self.with_entry_closed(),
+            EditorEvent::EditDictionaryDefinition => {
+                self.with_field_opened(DictionaryField::Definition)
+            }
+            EditorEvent::AddCandidateToDictionaryEntry => {
+                self.with_field_opened(DictionaryField::NewCandidate)
+            }
+            EditorEvent::AddNoteToDictionaryEntry => self.with_field_opened(DictionaryField::NewNote),
+            EditorEvent::MoveDictionaryCandidateCursorForward => {
+                self.with_candidate_cursor_moved_forward()
+            }
+            EditorEvent::MoveDictionaryCandidateCursorBackward => {
+                self.with_candidate_cursor_moved_backward()
+            }
+            EditorEvent::ToggleSelectedCandidateConfirmed => {
+                self.with_selected_candidate_confirmation_toggled()
+            }
+            EditorEvent::RaiseSelectedCandidateConfidence => {
+                self.with_selected_candidate_confidence_changed(true)
+            }
+            EditorEvent::LowerSelectedCandidateConfidence => {
+                self.with_selected_candidate_confidence_changed(false)
+            }
+            EditorEvent::DeleteSelectedCandidate => self.with_selected_candidate_deleted(),
+            EditorEvent::CycleDisplayedHypothesis => {
+                let hypothesis = self.hypothesis.cycled();
+
+                self.with_hypothesis(hypothesis)
+            }
+            EditorEvent::ConfirmDictionaryEntryEdit => self.with_field_edit_confirmed(),
+            EditorEvent::CancelDictionaryEntryEdit => self.with_field_edit_cancelled(),
+            EditorEvent::RequestSaveDictionaryToFile => {
+                self.with_state(FileEditorState::ConfirmingSaveRequest)
+            }
+            EditorEvent::ConfirmSaveDictionaryToFileRequest => {
+                self.with_state(FileEditorState::SaveRequestConfirmed)
+            }
+            EditorEvent::ReportSavedDictionaryToFile => Self {
+                saved_dictionary: self.dictionary.clone(),
+                ..self
+            }
+            .with_state(FileEditorState::SaveRequestSucceeded),
+            EditorEvent::ReportFailedToSaveDictionaryToFile(error) => {
+                self.with_state(FileEditorState::SaveRequestFailed(error))
+            }
+            EditorEvent::ResetDictionaryEditorToIdle => self.with_state(FileEditorState::Idle),
+            EditorEvent::ReportDictionaryChangedOnDisk(dictionary) => {
+                self.with_change_on_disk(dictionary)
+            }
+            EditorEvent::AcceptDictionaryChangesOnDisk => self.with_changes_on_disk_accepted(),
+            EditorEvent::KeepDictionaryEdits => self.with_edits_kept(),
+            EditorEvent::OpenReverseLookup => self.with_reverse_lookup_opened(),
+            EditorEvent::CloseReverseLookup => self.with_reverse_lookup_closed(),
+            EditorEvent::MoveReverseLookupCursorForward => {
+                self.with_reverse_lookup_cursor_moved_forward()
+            }
+            EditorEvent::MoveReverseLookupCursorBackward => {
+                self.with_reverse_lookup_cursor_moved_backward()
+            }
+            _ => self.with_text_event_applied(event),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word(text: &str) -> DictionaryWord {
+        text.parse().unwrap()
+    }
+
+    fn editor_with(dictionary: Dictionary) -> DictionaryEditor {
+        DictionaryEditor::new(dictionary, "dictionary.yaml")
+            .apply(EditorEvent::OpenDictionaryEntry(word("1,3")))
+    }
+
+    fn typed(editor: DictionaryEditor, text: &str) -> DictionaryEditor {
+        editor
+            .apply(EditorEvent::InsertTextAtTextCursor(text.into()))
+            .apply(EditorEvent::ConfirmDictionaryEntryEdit)
+    }
+
+    fn with_candidate(editor: DictionaryEditor, text: &str) -> DictionaryEditor {
+        typed(editor.apply(EditorEvent::AddCandidateToDictionaryEntry), text)
+    }
+
+    fn candidate_texts(editor: &DictionaryEditor) -> Vec<String> {
+        editor
+            .selected_entry()
+            .unwrap()
+            .candidates()
+            .iter()
+            .map(|candidate| candidate.text().to_owned())
+            .collect()
+    }
+
+    #[test]
+    fn candidates_are_added_edited_and_removed() {
+        let editor = with_candidate(editor_with(Dictionary::new()), "fox");
+
+        assert_eq!(candidate_texts(&editor), vec!["fox"]);
+        assert!(editor.has_unsaved_changes());
+        assert!(!editor.is_editing_text());
+
+        let editor = typed(editor.apply(EditorEvent::EditDictionaryDefinition), "es");
+        assert_eq!(candidate_texts(&editor), vec!["foxes"]);
+
+        let editor = editor
+            .apply(EditorEvent::EditDictionaryDefinition)
+            .apply(EditorEvent::MoveTextCursorToStart);
+        let editor = (0..5).fold(editor, |editor, _| {
+            editor.apply(EditorEvent::DeleteTextAtTextCursor)
+        });
+        let editor = editor.apply(EditorEvent::ConfirmDictionaryEntryEdit);
+
+        assert!(candidate_texts(&editor).is_empty());
+    }
+
+    #[test]
+    fn the_cursor_follows_a_candidate_through_the_ranking() {
+        let editor = with_candidate(with_candidate(editor_with(Dictionary::new()), "fox"), "dog")
+            .apply(EditorEvent::MoveDictionaryCandidateCursorForward);
+
+        assert_eq!(editor.selected_candidate().unwrap().text(), "dog");
+
+        let editor = editor.apply(EditorEvent::RaiseSelectedCandidateConfidence);
+
+        assert_eq!(candidate_texts(&editor), vec!["dog", "fox"]);
+        assert_eq!(editor.selected_candidate().unwrap().confidence(), 60);
+
+        let editor = editor
+            .apply(EditorEvent::MoveDictionaryCandidateCursorForward)
+            .apply(EditorEvent::ToggleSelectedCandidateConfirmed);
+
+        assert_eq!(candidate_texts(&editor), vec!["fox", "dog"]);
+        assert!(editor.selected_candidate().unwrap().is_confirmed());
+    }
+
+    #[test]
+    fn the_cursor_follows_a_candidate_sharing_its_text_with_another() {
+        let editor = with_candidate(with_candidate(editor_with(Dictionary::new()), "fox"), "fox")
+            .apply(EditorEvent::RaiseSelectedCandidateConfidence)
+            .apply(EditorEvent::MoveDictionaryCandidateCursorForward)
+            .apply(EditorEvent::ToggleSelectedCandidateConfirmed);
+
+        assert_eq!(editor.candidate_cursor, 0);
+        assert_eq!(editor.selected_candidate().unwrap().confidence(), DEFAULT_CONFIDENCE);
+
+        let editor = editor.apply(EditorEvent::ToggleSelectedCandidateConfirmed);
+
+        assert_eq!(editor.candidate_cursor, 1);
+        assert_eq!(editor.selected_candidate().unwrap().confidence(), DEFAULT_CONFIDENCE);
+        assert!(!editor.selected_candidate().unwrap().is_confirmed());
+    }
+
+    #[test]
+    fn notes_are_added_to_the_open_entry() {
+        let editor = typed(
+            editor_with(Dictionary::new()).apply(EditorEvent::AddNoteToDictionaryEntry),
+            "seen on the shop sign",
+        );
+
+        let notes = editor.selected_entry().unwrap().notes().clone();
+        assert_eq!(notes, vec![Note::from("seen on the shop sign")]);
+    }
+
+    #[test]
+    fn saving_clears_unsaved_changes() {
+        let editor = with_candidate(editor_with(Dictionary::new()), "fox")
+            .apply(EditorEvent::RequestSaveDictionaryToFile);
+
+        assert_eq!(editor.state(), FileEditorState::ConfirmingSaveRequest);
+
+        let editor = editor
+            .apply(EditorEvent::ConfirmSaveDictionaryToFileRequest)
+            .apply(EditorEvent::ReportSavedDictionaryToFile);
+
+        assert_eq!(editor.state(), FileEditorState::SaveRequestSucceeded);
+        assert!(!editor.has_unsaved_changes());
+    }
+
+    #[test]
+    fn changes_on_disk_merge_unless_they_conflict() {
+        let base = Dictionary::new()
+            .with_definition_for(&word("1,3"), Definition::Tentative("fox".into()));
+        let theirs =
+            base.clone().with_definition_for(&word("2,"), Definition::Tentative("dog".into()));
+
+        let editor = with_candidate(editor_with(base.clone()), "vulpine")
+            .apply(EditorEvent::ReportDictionaryChangedOnDisk(theirs.clone()));
+
+        assert_eq!(editor.state(), FileEditorState::Idle);
+        assert_eq!(editor.dictionary().entries().len(), 2);
+        assert_eq!(candidate_texts(&editor), vec!["fox", "vulpine"]);
+
+        let theirs = theirs.with_definition_for(&word("1,3"), Definition::Tentative("kit".into()));
+        let editor = editor.apply(EditorEvent::ReportDictionaryChangedOnDisk(theirs.clone()));
+
+        assert_eq!(editor.state(), FileEditorState::ChangedOnDisk);
+        assert_eq!(editor.conflicts(), ["1,3".to_owned()]);
+
+        let editor = editor.apply(EditorEvent::AcceptDictionaryChangesOnDisk);
+
+        assert_eq!(editor.dictionary(), &theirs);
+        assert!(!editor.has_unsaved_changes());
+    }
+
+    #[test]
+    fn reverse_lookup_opens_the_selected_result() {
+        let dictionary = Dictionary::new()
+            .with_definition_for(&word("1,3"), Definition::Tentative("fox den".into()))
+            .with_definition_for(&word("2,"), Definition::Tentative("fox".into()));
+
+        let editor = DictionaryEditor::new(dictionary, "dictionary.yaml")
+            .apply(EditorEvent::OpenReverseLookup)
+            .apply(EditorEvent::InsertTextAtTextCursor("fox".into()));
+
+        assert_eq!(editor.reverse_lookup_results().len(), 2);
+
+        let editor = editor.apply(EditorEvent::InsertTextAtTextCursor(" d".into()));
+
+        assert_eq!(editor.reverse_lookup_results(), vec![word("1,3")]);
+        assert_eq!(editor.selected_reverse_lookup_result(), Some(word("1,3")));
+        assert!(editor.is_open());
+        assert!(!editor.is_entry_open());
+    }
+
+    #[test]
+    fn reverse_lookup_finds_new_candidates() {
+        let editor = with_candidate(editor_with(Dictionary::new()), "fox")
+            .apply(EditorEvent::CloseDictionaryEntry)
+            .apply(EditorEvent::OpenReverseLookup)
+            .apply(EditorEvent::InsertTextAtTextCursor("fox".into()));
+
+        assert_eq!(editor.reverse_lookup_results(), vec![word("1,3")]);
+    }
+}
@@ -28,11 +28,93 @@ pub enum EditorEvent {
     ReportFailedToLoadFromFile(FileEditorError),
     ReportFailedToSaveToFile(FileEditorError),
     ResetFileEditorToIdle,
+    ReportNotebookChangedOnDisk(Notebook),
+    AcceptNotebookChangesOnDisk,
+    KeepNotebookEdits,
     EnableSnippetNavigationMode,
     EnableSnippetEditingMode,
     ToggleSnippetTranscriptionState,
     ToggleHasBeenTranscribedFilter,
+    CycleTagFilter,
+    CycleLocationFilter,
     ToggleWordHasABorderState,
     ToggleWordIsColoredState,
     PrintWord(Word),
+    EnableSnippetDetailsEditingMode,
+    MoveSnippetFieldCursorForward,
+    MoveSnippetFieldCursorBackward,
+    EditSelectedSnippetField,
+    ConfirmSnippetFieldEdit,
+    CancelSnippetFieldEdit,
+    CycleSnippetSourceType,
+    AddNewNoteToSnippet,
+    DeleteSelectedSnippetNote,
+    InsertTextAtTextCursor(String),
+    DeleteTextBeforeTextCursor,
+    DeleteTextAtTextCursor,
+    MoveTextCursorForward,
+    MoveTextCursorBackward,
+    MoveTextCursorToStart,
+    MoveTextCursorToEnd,
+    TypeNewEnglishWordAtCursor,
+    EditEnglishWordAtCursor,
+    ConfirmEnglishWordEdit,
+    CancelEnglishWordEdit,
+    OpenDictionaryEntry(DictionaryWord),
+    CloseDictionaryEntry,
+    EditDictionaryDefinition,
+    AddCandidateToDictionaryEntry,
+    MoveDictionaryCandidateCursorForward,
+    MoveDictionaryCandidateCursorBackward,
+    ToggleSelectedCandidateConfirmed,
+    RaiseSelectedCandidateConfidence,
+    LowerSelectedCandidateConfidence,
+    DeleteSelectedCandidate,
+    AddNoteToDictionaryEntry,
+    ConfirmDictionaryEntryEdit,
+    CancelDictionaryEntryEdit,
+    RequestSaveDictionaryToFile,
+    ConfirmSaveDictionaryToFileRequest,
+    ReportSavedDictionaryToFile,
+    ReportFailedToSaveDictionaryToFile(FileEditorError),
+    ResetDictionaryEditorToIdle,
+    ReportDictionaryChangedOnDisk(Dictionary),
+    AcceptDictionaryChangesOnDisk,
+    KeepDictionaryEdits,
+    OpenReverseLookup,
+    CloseReverseLookup,
+    MoveReverseLookupCursorForward,
+    MoveReverseLookupCursorBackward,
+    CycleDisplayedHypothesis,
+    Undo,
+    Redo,
+}
+
+impl EditorEvent {
+    /// Whether this event is part of the same logical action as `previous`, and so
+    /// shares its undo step. Typing a new English word is a single step.
+    pub fn continues_undo_step(&self, previous: &EditorEvent) -> bool {
+        matches!(
+            (previous, self),
+            (
+                EditorEvent::TypeNewEnglishWordAtCursor,
+                EditorEvent::ConfirmEnglishWordEdit | EditorEvent::CancelEnglishWordEdit
+            )
+        )
+    }
+
+    /// Events that only change text still being typed, which isn't part of the
+    /// notebook until it is confirmed
+    pub fn is_text_entry(&self) -> bool {
+        matches!(
+            self,
+            EditorEvent::InsertTextAtTextCursor(_)
+                | EditorEvent::DeleteTextBeforeTextCursor
+                | EditorEvent::DeleteTextAtTextCursor
+                | EditorEvent::MoveTextCursorForward
+                | EditorEvent::MoveTextCursorBackward
+                | EditorEvent::MoveTextCursorToStart
+                | EditorEvent::MoveTextCursorToEnd
+        )
+    }
 }
@@ -14,6 +14,8 @@ pub enum FileEditorState {
     LoadRequestSucceeded,
     SaveRequestFailed(FileEditorError),
     LoadRequestFailed(FileEditorError),
+    /// The file was changed by something else while there were unsaved edits
+    ChangedOnDisk,
 }
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -38,7 +40,7 @@ impl FileEditorError {
         description: String,
         error_type: FileEditorErrorType,
         filename: String,
-        inner_error: Option<&Box<dyn Error>>,
+        inner_error: Option<&FennecError>,
     ) -> Self {
         let inner_error_description = if let Some(inner_error) = inner_error {
             Some(inner_error.to_string())
@@ -75,31 +77,68 @@ impl Error for FileEditorError {
     }
 }
 
+/// Edits are unsaved while the notebook differs from `saved_notebook`, the one
+/// last loaded or saved. A version changed on disk under unsaved edits waits in
+/// `changed_on_disk` until the user picks one.
 #[derive(Clone, Debug)]
 pub struct FileEditor {
     notebook_editor: NotebookEditor,
     state: FileEditorState,
     target_file: String,
+    history: EditorHistory<NotebookEditor>,
+    saved_notebook: Notebook,
+    changed_on_disk: Option<Notebook>,
 }
 
 impl FileEditor {
     pub fn new(notebook: Notebook, filename: &str) -> Self {
+        let notebook_editor =
+            NotebookEditor::new(notebook.clone()).with_relative_snippet_selected(0);
+
         Self {
-            notebook_editor: NotebookEditor::new(notebook).with_relative_snippet_selected(0),
+            notebook_editor,
             target_file: filename.into(),
             state: FileEditorState::Idle,
+            history: EditorHistory::new(MAX_UNDO_STEPS),
+            saved_notebook: notebook,
+            changed_on_disk: None,
         }
     }
 
     pub fn with_notebook(self, notebook: Notebook) -> Self {
-        let notebook_editor = NotebookEditor::new(notebook).with_relative_snippet_selected(0);
+        let notebook_editor =
+            NotebookEditor::new(notebook.clone()).with_relative_snippet_selected(0);
 
         Self {
             notebook_editor,
+            history: EditorHistory::new(MAX_UNDO_STEPS),
+            saved_notebook: notebook,
+            changed_on_disk: None,
             ..self
         }
     }
 
+    /// Unlike loading, a notebook changed on disk is one more step to undo, and
+    /// the selected snippet stays selected
+    fn with_notebook_reloaded(self, notebook: Notebook) -> Self {
+        let previous_editor = self.notebook_editor.clone();
+
+        Self {
+            notebook_editor: self.notebook_editor.with_notebook_reloaded(notebook.clone()),
+            history: self
+                .history
+                .with_step_recorded(previous_editor, EditorEvent::AcceptNotebookChangesOnDisk)
+                .with_step_closed(),
+            saved_notebook: notebook,
+            changed_on_disk: None,
+            ..self
+        }
+    }
+
+    pub fn has_unsaved_changes(&self) -> bool {
+        self.to_source() != self.saved_notebook
+    }
+
     pub fn state(&self) -> FileEditorState {
         self.state.clone()
     }
@@ -141,6 +180,92 @@ impl FileEditor {
     pub fn to_source(&self) -> Notebook {
         self.notebook_editor.to_source()
     }
+
+    /// Reloads straight away without unsaved edits to lose. Our own saves read
+    /// back the same as `saved_notebook` and are ignored.
+    fn with_change_on_disk(self, notebook: Notebook) -> Self {
+        if notebook == self.saved_notebook {
+            self
+        } else if !self.has_unsaved_changes() {
+            self.with_notebook_reloaded(notebook)
+        } else {
+            Self {
+                changed_on_disk: Some(notebook),
+                state: FileEditorState::ChangedOnDisk,
+                ..self
+            }
+        }
+    }
+
+    fn with_changes_on_disk_accepted(self) -> Self {
+        match self.changed_on_disk.clone() {
+            Some(notebook) => self.with_notebook_reloaded(notebook),
+            None => self,
+        }
+        .with_state(FileEditorState::Idle)
+    }
+
+    /// The unsaved edits stay unsaved, and saving them replaces the version on disk
+    fn with_edits_kept(self) -> Self {
+        Self {
+            saved_notebook: self.changed_on_disk.unwrap_or(self.saved_notebook),
+            changed_on_disk: None,
+            state: FileEditorState::Idle,
+            ..self
+        }
+    }
+
+    fn with_last_change_undone(self) -> Self {
+        let (history, previous_editor) = self.history.undone(self.notebook_editor.clone());
+
+        Self {
+            notebook_editor: previous_editor.unwrap_or(self.notebook_editor),
+            history,
+            ..self
+        }
+    }
+
+    fn with_last_change_redone(self) -> Self {
+        let (history, next_editor) = self.history.redone(self.notebook_editor.clone());
+
+        Self {
+            notebook_editor: next_editor.unwrap_or(self.notebook_editor),
+            history,
+            ..self
+        }
+    }
+
+    fn with_event_applied_to_notebook_editor(self, event: EditorEvent) -> Self {
+        let previous_editor = self.notebook_editor.clone();
+        let notebook_editor = self.notebook_editor.apply(event.clone());
+
+        // Only changes to the notebook itself are worth undoing, not navigation.
+        // Text being typed can't change it, so it isn't compared.
+        let history = if event.is_text_entry() {
+            self.history
+        } else if !notebook_editor.has_same_source_as(&previous_editor) {
+            let history = self.history.with_step_recorded(previous_editor, event);
+
+            // Like a new word that was typed and then cancelled
+            let changed_nothing = history
+                .last_step()
+                .is_some_and(|step| step.has_same_source_as(&notebook_editor));
+
+            if changed_nothing {
+                history.with_last_step_dropped()
+            } else {
+                history
+            }
+        } else {
+            self.history.with_step_closed()
+        };
+
+        Self {
+            notebook_editor,
+            history,
+            ..self
+        }
+    }
 }
 
 impl AppliesEditorEvents for FileEditor {
@@ -161,9 +286,11 @@ impl AppliesEditorEvents for FileEditor {
             EditorEvent::ReportLoadedFromFile(notebook) => self
                 .with_notebook(notebook)
                 .with_state(FileEditorState::LoadRequestSucceeded),
-            EditorEvent::ReportSavedToFile => {
-                self.with_state(FileEditorState::SaveRequestSucceeded)
+            EditorEvent::ReportSavedToFile => Self {
+                saved_notebook: self.to_source(),
+                ..self
             }
+            .with_state(FileEditorState::SaveRequestSucceeded),
             EditorEvent::ReportFailedToLoadFromFile(error) => {
                 self.with_state(FileEditorState::LoadRequestFailed(error))
             }
@@ -175,14 +302,85 @@ impl AppliesEditorEvents for FileEditor {
                 self
             }
             EditorEvent::ResetFileEditorToIdle => self.with_state(FileEditorState::Idle),
-            _ => {
-                let notebook_editor = self.notebook_editor.apply(event);
-
-                Self {
-                    notebook_editor,
-                    ..self
-                }
+            EditorEvent::ReportNotebookChangedOnDisk(notebook) => {
+                self.with_change_on_disk(notebook)
             }
+            EditorEvent::AcceptNotebookChangesOnDisk => self.with_changes_on_disk_accepted(),
+            EditorEvent::KeepNotebookEdits => self.with_edits_kept(),
+            EditorEvent::Undo => self.with_last_change_undone(),
+            EditorEvent::Redo => self.with_last_change_redone(),
+            _ => self.with_event_applied_to_notebook_editor(event),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn editor() -> FileEditor {
+        FileEditor::new(vec![Snippet::starting_snippet()].into(), "notebook.yaml")
+    }
+
+    fn typed_word(editor: FileEditor, text: &str, end: EditorEvent) -> FileEditor {
+        editor
+            .apply(EditorEvent::TypeNewEnglishWordAtCursor)
+            .apply(EditorEvent::InsertTextAtTextCursor(text.into()))
+            .apply(end)
+    }
+
+    #[test]
+    fn a_typed_english_word_is_one_undo_step() {
+        let editor = editor();
+        let typed = typed_word(editor.clone(), "fox", EditorEvent::ConfirmEnglishWordEdit);
+
+        let words = &typed.to_source().snippets[0].words;
+        assert_eq!(words.len(), 2);
+        assert_eq!(words[1], Word::from("fox".to_owned()));
+        assert_eq!(typed.history.undo_count(), 1);
+
+        assert_eq!(typed.apply(EditorEvent::Undo).to_source(), editor.to_source());
+    }
+
+    #[test]
+    fn a_cancelled_english_word_leaves_nothing_to_undo() {
+        let editor = editor();
+        let cancelled = typed_word(editor.clone(), "fox", EditorEvent::CancelEnglishWordEdit);
+
+        assert_eq!(cancelled.to_source(), editor.to_source());
+        assert_eq!(cancelled.history.undo_count(), 0);
+    }
+
+    #[test]
+    fn changes_on_disk_keep_the_selection_and_can_be_undone() {
+        let first = Snippet {
+            id: SnippetId::for_position(0),
+            ..Snippet::starting_snippet()
+        };
+        let second = Snippet {
+            id: SnippetId::for_position(1),
+            description: "Second".into(),
+            ..Snippet::starting_snippet()
+        };
+        let notebook: Notebook = vec![first.clone(), second.clone()].into();
+
+        let editor = FileEditor::new(notebook.clone(), "notebook.yaml")
+            .apply(EditorEvent::MoveSnippetCursorForward);
+        let editor = typed_word(editor, "fox", EditorEvent::ConfirmEnglishWordEdit)
+            .apply(EditorEvent::ReportSavedToFile)
+            .apply(EditorEvent::ResetFileEditorToIdle);
+
+        let added = Snippet {
+            id: SnippetId::for_position(2),
+            ..Snippet::starting_snippet()
+        };
+        let on_disk: Notebook = vec![added, first, editor.to_source().snippets[1].clone()].into();
+        let reloaded = editor.clone().apply(EditorEvent::ReportNotebookChangedOnDisk(on_disk));
+
+        assert_eq!(reloaded.state(), FileEditorState::Idle);
+        assert_eq!(reloaded.notebook_editor.selected_snippet_id(), Some(&second.id));
+        assert_eq!(reloaded.history.undo_count(), 2);
+
+        assert_eq!(reloaded.apply(EditorEvent::Undo).to_source(), editor.to_source());
+    }
+}
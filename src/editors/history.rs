@@ -0,0 +1,195 @@
+use std::collections::VecDeque;
+
+use crate::prelude::*;
+
+#[derive(Clone, Debug)]
+pub struct EditorHistory<T: Clone> {
+    undo_steps: VecDeque<T>,
+    redo_steps: Vec<T>,
+    max_steps: usize,
+    last_event: Option<EditorEvent>,
+}
+
+impl<T: Clone> EditorHistory<T> {
+    pub fn new(max_steps: usize) -> Self {
+        Self {
+            undo_steps: VecDeque::new(),
+            redo_steps: vec![],
+            max_steps,
+            last_event: None,
+        }
+    }
+
+    /// Records the state from before `event` was applied. Events that continue the
+    /// previous logical action, like typing another letter, share its undo step.
+    pub fn with_step_recorded(self, previous_state: T, event: EditorEvent) -> Self {
+        let continues_last_step = self
+            .last_event
+            .as_ref()
+            .is_some_and(|last_event| event.continues_undo_step(last_event));
+
+        if continues_last_step {
+            return Self {
+                last_event: Some(event),
+                ..self
+            };
+        }
+
+        let mut undo_steps = self.undo_steps;
+        undo_steps.push_back(previous_state);
+
+        while undo_steps.len() > self.max_steps {
+            undo_steps.pop_front();
+        }
+
+        Self {
+            undo_steps,
+            redo_steps: vec![],
+            last_event: Some(event),
+            ..self
+        }
+    }
+
+    /// Ends the current logical action so the next change starts a new undo step
+    pub fn with_step_closed(self) -> Self {
+        Self {
+            last_event: None,
+            ..self
+        }
+    }
+
+    /// The state the latest undo step goes back to
+    pub fn last_step(&self) -> Option<&T> {
+        self.undo_steps.back()
+    }
+
+    /// Drops the latest undo step, for an action that turned out to change nothing
+    pub fn with_last_step_dropped(self) -> Self {
+        let mut undo_steps = self.undo_steps;
+        undo_steps.pop_back();
+
+        Self {
+            undo_steps,
+            last_event: None,
+            ..self
+        }
+    }
+
+    pub fn undone(self, current_state: T) -> (Self, Option<T>) {
+        let mut undo_steps = self.undo_steps;
+        let mut redo_steps = self.redo_steps;
+
+        let previous_state = undo_steps.pop_back();
+
+        if previous_state.is_some() {
+            redo_steps.push(current_state);
+        }
+
+        let history = Self {
+            undo_steps,
+            redo_steps,
+            last_event: None,
+            ..self
+        };
+
+        (history, previous_state)
+    }
+
+    pub fn redone(self, current_state: T) -> (Self, Option<T>) {
+        let mut undo_steps = self.undo_steps;
+        let mut redo_steps = self.redo_steps;
+
+        let next_state = redo_steps.pop();
+
+        if next_state.is_some() {
+            undo_steps.push_back(current_state);
+        }
+
+        let history = Self {
+            undo_steps,
+            redo_steps,
+            last_event: None,
+            ..self
+        };
+
+        (history, next_state)
+    }
+
+    pub fn undo_count(&self) -> usize {
+        self.undo_steps.len()
+    }
+
+    pub fn redo_count(&self) -> usize {
+        self.redo_steps.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn toggle() -> EditorEvent {
+        EditorEvent::ToggleSegmentsOnSelectedGlyph(vec![1, 3])
+    }
+
+    #[test]
+    fn undo_then_redo_restores_each_state() {
+        let history = EditorHistory::new(10)
+            .with_step_recorded(1, toggle())
+            .with_step_recorded(2, toggle());
+
+        let (history, state) = history.undone(3);
+        assert_eq!(state, Some(2));
+
+        let (history, state) = history.undone(2);
+        assert_eq!(state, Some(1));
+
+        let (history, state) = history.undone(1);
+        assert_eq!(state, None);
+
+        let (history, state) = history.redone(1);
+        assert_eq!(state, Some(2));
+
+        let (_history, state) = history.redone(2);
+        assert_eq!(state, Some(3));
+    }
+
+    #[test]
+    fn recording_a_new_step_discards_redo_steps() {
+        let history = EditorHistory::new(10).with_step_recorded(1, toggle());
+
+        let (history, _) = history.undone(2);
+        assert_eq!(history.redo_count(), 1);
+
+        let history = history.with_step_recorded(1, toggle());
+        assert_eq!(history.redo_count(), 0);
+    }
+
+    #[test]
+    fn typing_a_word_is_one_step() {
+        let history = EditorHistory::new(10)
+            .with_step_recorded(1, EditorEvent::TypeNewEnglishWordAtCursor)
+            .with_step_recorded(2, EditorEvent::ConfirmEnglishWordEdit)
+            .with_step_recorded(3, toggle())
+            .with_step_recorded(4, toggle());
+
+        assert_eq!(history.undo_count(), 3);
+
+        let (history, _) = history.undone(5);
+        let (history, _) = history.undone(4);
+        let (_history, state) = history.undone(3);
+        assert_eq!(state, Some(1));
+    }
+
+    #[test]
+    fn history_is_bounded() {
+        let history = (0..20).fold(EditorHistory::new(5), |history, state| {
+            history.with_step_recorded(state, toggle())
+        });
+
+        assert_eq!(history.undo_count(), 5);
+
+        let (_history, state) = history.undone(20);
+        assert_eq!(state, Some(19));
+    }
+}
@@ -0,0 +1,177 @@
+use serde::{Deserialize, Serialize};
+use std::cmp;
+
+use crate::prelude::*;
+
+/// Edits a single line of text. The cursor counts characters rather than bytes
+/// so phonemes like `ɑ` can be typed and deleted like any other letter.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct LineEditor {
+    text: String,
+    cursor: usize,
+}
+
+impl LineEditor {
+    pub fn new(text: &str) -> Self {
+        Self {
+            text: text.to_owned(),
+            cursor: text.chars().count(),
+        }
+    }
+
+    pub fn text(&self) -> String {
+        self.text.clone()
+    }
+
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    fn character_count(&self) -> usize {
+        self.text.chars().count()
+    }
+
+    fn byte_index_of(&self, character_index: usize) -> usize {
+        self.text
+            .char_indices()
+            .nth(character_index)
+            .map_or(self.text.len(), |(index, _)| index)
+    }
+
+    fn with_text_inserted(self, text: &str) -> Self {
+        let mut new_text = self.text.clone();
+        new_text.insert_str(self.byte_index_of(self.cursor), text);
+
+        Self {
+            text: new_text,
+            cursor: self.cursor + text.chars().count(),
+        }
+    }
+
+    fn with_character_before_cursor_deleted(self) -> Self {
+        if self.cursor == 0 {
+            return self;
+        }
+
+        Self {
+            cursor: self.cursor - 1,
+            ..self
+        }
+        .with_character_at_cursor_deleted()
+    }
+
+    fn with_character_at_cursor_deleted(self) -> Self {
+        if self.cursor >= self.character_count() {
+            return self;
+        }
+
+        let mut text = self.text.clone();
+        text.remove(self.byte_index_of(self.cursor));
+
+        Self { text, ..self }
+    }
+
+    fn with_cursor_moved_forward(self, amount: usize) -> Self {
+        Self {
+            cursor: cmp::min(self.character_count(), self.cursor + amount),
+            ..self
+        }
+    }
+
+    fn with_cursor_moved_backward(self, amount: usize) -> Self {
+        Self {
+            cursor: self.cursor.saturating_sub(amount),
+            ..self
+        }
+    }
+
+    pub fn to_view(&self) -> LineEditorView {
+        LineEditorView {
+            text: self.text.clone(),
+            cursor: self.cursor,
+        }
+    }
+}
+
+impl AppliesEditorEvents for LineEditor {
+    fn apply(self, event: EditorEvent) -> Self {
+        match event {
+            EditorEvent::InsertTextAtTextCursor(text) => self.with_text_inserted(&text),
+            EditorEvent::DeleteTextBeforeTextCursor => self.with_character_before_cursor_deleted(),
+            EditorEvent::DeleteTextAtTextCursor => self.with_character_at_cursor_deleted(),
+            EditorEvent::MoveTextCursorForward => self.with_cursor_moved_forward(1),
+            EditorEvent::MoveTextCursorBackward => self.with_cursor_moved_backward(1),
+            EditorEvent::MoveTextCursorToStart => Self { cursor: 0, ..self },
+            EditorEvent::MoveTextCursorToEnd => {
+                let cursor = self.character_count();
+
+                Self { cursor, ..self }
+            }
+            _ => self,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn typed(editor: LineEditor, text: &str) -> LineEditor {
+        editor.apply(EditorEvent::InsertTextAtTextCursor(text.into()))
+    }
+
+    #[test]
+    fn starts_with_the_cursor_at_the_end() {
+        let editor = LineEditor::new("fox");
+
+        assert_eq!(editor.cursor(), 3);
+        assert_eq!(typed(editor, "es").text(), "foxes");
+    }
+
+    #[test]
+    fn inserts_and_deletes_around_the_cursor() {
+        let editor = LineEditor::new("fx")
+            .apply(EditorEvent::MoveTextCursorBackward)
+            .apply(EditorEvent::InsertTextAtTextCursor("o".into()));
+
+        assert_eq!(editor.text(), "fox");
+        assert_eq!(editor.cursor(), 2);
+
+        let editor = editor.apply(EditorEvent::DeleteTextAtTextCursor);
+        assert_eq!(editor.text(), "fo");
+
+        let editor = editor
+            .apply(EditorEvent::MoveTextCursorToStart)
+            .apply(EditorEvent::DeleteTextBeforeTextCursor);
+        assert_eq!(editor.text(), "fo");
+
+        let editor = editor
+            .apply(EditorEvent::MoveTextCursorToEnd)
+            .apply(EditorEvent::DeleteTextBeforeTextCursor);
+        assert_eq!(editor.text(), "f");
+    }
+
+    #[test]
+    fn handles_multibyte_characters() {
+        let editor = LineEditor::new("fɑks")
+            .apply(EditorEvent::MoveTextCursorBackward)
+            .apply(EditorEvent::MoveTextCursorBackward)
+            .apply(EditorEvent::DeleteTextBeforeTextCursor);
+
+        assert_eq!(editor.text(), "fks");
+
+        let editor = typed(editor, "ɔ");
+        assert_eq!(editor.text(), "fɔks");
+        assert_eq!(editor.cursor(), 2);
+    }
+
+    #[test]
+    fn cursor_stays_within_the_text() {
+        let editor = LineEditor::new("ab")
+            .apply(EditorEvent::MoveTextCursorForward)
+            .apply(EditorEvent::DeleteTextAtTextCursor);
+
+        assert_eq!(editor.cursor(), 2);
+        assert_eq!(editor.text(), "ab");
+    }
+}
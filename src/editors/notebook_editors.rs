@@ -6,6 +6,8 @@ use crate::prelude::*;
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct NotebookEditorFilters {
     has_been_transcribed: Option<bool>,
+    tag: Option<String>,
+    location: Option<String>,
 }
 
 impl NotebookEditorFilters {
@@ -17,13 +19,47 @@ impl NotebookEditorFilters {
             .map_or(true, |expected| snippet.transcribed == expected);
         outcomes.push(has_been_transcribed);
 
+        let has_tag = self.tag.as_ref().is_none_or(|tag| snippet.has_tag(tag));
+        outcomes.push(has_tag);
+
+        let is_at_location = self
+            .location
+            .as_ref()
+            .is_none_or(|location| snippet.is_at_location(location));
+        outcomes.push(is_at_location);
+
         outcomes.iter().all(|o| *o)
     }
+
+    pub fn has_been_transcribed(&self) -> Option<bool> {
+        self.has_been_transcribed
+    }
+
+    pub fn tag(&self) -> Option<&str> {
+        self.tag.as_deref()
+    }
+
+    pub fn location(&self) -> Option<&str> {
+        self.location.as_deref()
+    }
+}
+
+/// Steps from no filter through each of `values` in order and back to none
+fn next_filter_value(current: &Option<String>, values: Vec<String>) -> Option<String> {
+    match current {
+        None => values.first().cloned(),
+        Some(current) => values
+            .iter()
+            .position(|value| value == current)
+            .and_then(|index| values.get(index + 1))
+            .cloned(),
+    }
 }
 
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub enum NotebookEditorState {
     EditingSnippet,
+    EditingSnippetDetails,
     SelectingSnippet,
 }
 
@@ -48,6 +84,8 @@ impl NotebookEditor {
     pub fn new(notebook: Notebook) -> Self {
         let filters = NotebookEditorFilters {
             has_been_transcribed: Some(false),
+            tag: None,
+            location: None,
         };
 
         Self {
@@ -63,6 +101,12 @@ impl NotebookEditor {
         self.state.clone()
     }
 
+    pub fn is_editing_text(&self) -> bool {
+        self.snippet_editor
+            .as_ref()
+            .is_some_and(|editor| editor.is_editing_text())
+    }
+
     pub fn with_state(self, state: NotebookEditorState) -> Self {
         Self {
             state,
@@ -100,6 +144,30 @@ impl NotebookEditor {
         }
     }
 
+    pub fn selected_snippet_id(&self) -> Option<&SnippetId> {
+        self.selected_snippet_index
+            .and_then(|index| self.selected_notebook.snippets.get(index))
+            .map(|snippet| &snippet.id)
+    }
+
+    /// Swaps in a notebook loaded again from disk, keeping the filters and the
+    /// selected snippet wherever it moved to
+    pub fn with_notebook_reloaded(self, notebook: Notebook) -> Self {
+        let selected_id = self.selected_snippet_id().cloned();
+
+        let editor = Self {
+            selected_notebook: notebook,
+            snippet_editor: None,
+            selected_snippet_index: None,
+            ..self
+        };
+
+        match selected_id.and_then(|id| editor.selected_notebook.position_of(&id)) {
+            Some(index) => editor.with_absolute_snippet_selected(index),
+            None => editor.with_relative_snippet_selected(0),
+        }
+    }
+
     fn retained_snippet_outcomes(&self) -> Vec<SnippetFiltrationOutcome> {
         let snippets = self.selected_notebook.snippets.clone();
         let mut relative_index = 0;
@@ -190,7 +258,8 @@ impl NotebookEditor {
 
     pub fn with_new_snippet_at_cursor(self) -> Self {
         let mut snippets = self.selected_notebook.snippets.clone();
-        let new_snippet: Snippet = Snippet::starting_snippet();
+        let new_snippet: Snippet =
+            Snippet::starting_snippet().with_id(self.selected_notebook.new_snippet_id());
 
         let new_index = if let Some(selected_snippet_index) = self.selected_snippet_index {
             if selected_snippet_index + 1 == snippets.len() {
@@ -234,6 +303,44 @@ impl NotebookEditor {
         }
     }
 
+    fn with_tag_filter_cycled(self) -> Self {
+        let mut tags: Vec<String> = self
+            .selected_notebook
+            .snippets
+            .iter()
+            .flat_map(|snippet| snippet.tags.iter().cloned())
+            .collect();
+        tags.sort();
+        tags.dedup();
+
+        Self {
+            filters: NotebookEditorFilters {
+                tag: next_filter_value(&self.filters.tag, tags),
+                ..self.filters
+            },
+            ..self
+        }
+    }
+
+    fn with_location_filter_cycled(self) -> Self {
+        let mut locations: Vec<String> = self
+            .selected_notebook
+            .snippets
+            .iter()
+            .filter_map(|snippet| snippet.location.clone())
+            .collect();
+        locations.sort();
+        locations.dedup();
+
+        Self {
+            filters: NotebookEditorFilters {
+                location: next_filter_value(&self.filters.location, locations),
+                ..self.filters
+            },
+            ..self
+        }
+    }
+
     pub fn on_input(&self, callback: Box<dyn Fn(&Self) -> EditorEvent>) -> EditorEvent {
         callback(self)
     }
@@ -289,12 +396,18 @@ impl NotebookEditor {
         NotebookView {
             state: self.state.clone(),
             snippet_views,
+            filters: self.filters.clone(),
         }
     }
 
     pub fn to_source(&self) -> Notebook {
         self.selected_notebook.clone()
     }
+
+    /// Compares the notebooks without cloning them, ignoring selection and filters
+    pub fn has_same_source_as(&self, other: &NotebookEditor) -> bool {
+        self.selected_notebook == other.selected_notebook
+    }
 }
 
 impl AppliesEditorEvents for NotebookEditor {
@@ -303,6 +416,9 @@ impl AppliesEditorEvents for NotebookEditor {
             EditorEvent::EnableSnippetEditingMode => {
                 self.with_state(NotebookEditorState::EditingSnippet)
             }
+            EditorEvent::EnableSnippetDetailsEditingMode => {
+                self.with_state(NotebookEditorState::EditingSnippetDetails)
+            }
             EditorEvent::EnableSnippetNavigationMode => {
                 self.with_state(NotebookEditorState::SelectingSnippet)
             }
@@ -312,6 +428,12 @@ impl AppliesEditorEvents for NotebookEditor {
             EditorEvent::ToggleHasBeenTranscribedFilter => self
                 .with_has_been_transcribed_filter_toggled()
                 .with_relative_snippet_selected(0),
+            EditorEvent::CycleTagFilter => {
+                self.with_tag_filter_cycled().with_relative_snippet_selected(0)
+            }
+            EditorEvent::CycleLocationFilter => {
+                self.with_location_filter_cycled().with_relative_snippet_selected(0)
+            }
             _ => {
                 if let Some(editor) = self.snippet_editor {
                     let snippet_editor = editor.apply(event);
@@ -320,7 +442,11 @@ impl AppliesEditorEvents for NotebookEditor {
 
                     if let Some(index) = self.selected_snippet_index {
                         if let Some(snippet) = notebook.snippets.get_mut(index) {
-                            *snippet = snippet_editor.selected_snippet();
+                            let edited_snippet = snippet_editor.selected_snippet();
+
+                            if edited_snippet != *snippet {
+                                *snippet = edited_snippet.with_modified_at(current_timestamp());
+                            }
                         }
                     }
 
@@ -1,12 +1,28 @@
 use serde::{Deserialize, Serialize};
+use std::cmp;
 
 use crate::prelude::*;
 
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum SnippetField {
+    Description,
+    Source,
+    Region,
+    Tags,
+    Location,
+    Note(usize),
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SnippetEditor {
     selected_snippet: Snippet,
     word_editor: Option<WordEditor>,
     cursor: VisibleCursor,
+    selected_field: SnippetField,
+    line_editor: Option<LineEditor>,
+    /// The last filename or other source text, kept while the source type is
+    /// cycled through page numbers and no source so it isn't lost
+    source_text: String,
 }
 
 impl SnippetEditor {
@@ -23,6 +39,9 @@ impl SnippetEditor {
             selected_snippet: snippet,
             word_editor: None,
             cursor,
+            selected_field: SnippetField::Description,
+            line_editor: None,
+            source_text: String::new(),
         }
         .with_visible_word_selected()
     }
@@ -31,6 +50,16 @@ impl SnippetEditor {
         self.selected_snippet.clone()
     }
 
+    pub fn is_editing_text(&self) -> bool {
+        self.line_editor.is_some() || self.is_editing_word_text()
+    }
+
+    pub fn is_editing_word_text(&self) -> bool {
+        self.word_editor
+            .as_ref()
+            .is_some_and(|editor| editor.is_editing_text())
+    }
+
     pub fn on_input(&self, callback: Box<dyn Fn(&SnippetEditor) -> EditorEvent>) -> EditorEvent {
         callback(self)
     }
@@ -117,6 +146,28 @@ impl SnippetEditor {
         }.with_word_selected(cursor_index)
     }
 
+    fn with_new_english_word_typed_at_cursor(self) -> Self {
+        self.with_new_english_word_at_cursor(String::new())
+            .with_event_applied_to_word_editor(EditorEvent::EditEnglishWordAtCursor)
+    }
+
+    /// English words left without any text are removed once typing ends
+    fn with_blank_english_word_at_cursor_deleted(self) -> Self {
+        let blank_english_word = self
+            .selected_snippet
+            .words
+            .get(self.cursor.index())
+            .is_some_and(|word| {
+                matches!(word.word_type, WordType::English(_)) && word.is_blank()
+            });
+
+        if blank_english_word {
+            self.with_word_at_cursor_deleted()
+        } else {
+            self
+        }
+    }
+
     fn with_word_at_cursor_deleted(self) -> Self {
         let index = self.cursor.index();
 
@@ -166,6 +217,193 @@ impl SnippetEditor {
         Self { cursor, ..self }
     }
 
+    fn fields(&self) -> Vec<SnippetField> {
+        let notes = (0..self.selected_snippet.notes.len()).map(SnippetField::Note);
+
+        [
+            SnippetField::Description,
+            SnippetField::Source,
+            SnippetField::Region,
+            SnippetField::Tags,
+            SnippetField::Location,
+        ]
+        .into_iter()
+        .chain(notes)
+        .collect()
+    }
+
+    fn field_text(&self, field: SnippetField) -> String {
+        match field {
+            SnippetField::Description => self.selected_snippet.description.clone(),
+            SnippetField::Source => self
+                .selected_snippet
+                .source
+                .as_ref()
+                .map_or(String::new(), |source| source.text()),
+            SnippetField::Region => self
+                .selected_snippet
+                .region
+                .map_or(String::new(), |region| region.to_string()),
+            SnippetField::Tags => self.selected_snippet.tags_text(),
+            SnippetField::Location => self.selected_snippet.location.clone().unwrap_or_default(),
+            SnippetField::Note(index) => self
+                .selected_snippet
+                .notes
+                .get(index)
+                .map_or(String::new(), |note| note.as_text()),
+        }
+    }
+
+    fn with_field_selected(self, index: usize) -> Self {
+        let fields = self.fields();
+        let index = cmp::min(index, fields.len() - 1);
+
+        Self {
+            selected_field: fields[index],
+            ..self
+        }
+    }
+
+    fn selected_field_index(&self) -> usize {
+        self.fields()
+            .iter()
+            .position(|field| *field == self.selected_field)
+            .unwrap_or(0)
+    }
+
+    fn with_field_selection_moved_forward(self, amount: usize) -> Self {
+        let index = self.selected_field_index() + amount;
+
+        self.with_field_selected(index)
+    }
+
+    fn with_field_selection_moved_backward(self, amount: usize) -> Self {
+        let index = self.selected_field_index().saturating_sub(amount);
+
+        self.with_field_selected(index)
+    }
+
+    fn with_selected_field_kept_in_range(self) -> Self {
+        if self.fields().contains(&self.selected_field) {
+            self
+        } else {
+            let index = self.fields().len() - 1;
+
+            self.with_field_selected(index)
+        }
+    }
+
+    fn with_selected_field_opened(self) -> Self {
+        let line_editor = LineEditor::new(&self.field_text(self.selected_field));
+
+        Self {
+            line_editor: Some(line_editor),
+            ..self
+        }
+    }
+
+    fn with_new_note_opened(self) -> Self {
+        Self {
+            selected_field: SnippetField::Note(self.selected_snippet.notes.len()),
+            line_editor: Some(LineEditor::default()),
+            ..self
+        }
+    }
+
+    fn with_field_edit_confirmed(self) -> Self {
+        let text = match &self.line_editor {
+            Some(line_editor) => line_editor.text(),
+            None => return self,
+        };
+
+        let snippet = self.selected_snippet.clone();
+
+        let selected_snippet = match self.selected_field {
+            SnippetField::Description => Some(snippet.with_description(text)),
+            SnippetField::Source => snippet
+                .source
+                .clone()
+                .unwrap_or(Source::Other(String::new()))
+                .with_text(&text)
+                .map(|source| snippet.with_source(Some(source))),
+            SnippetField::Region => text
+                .parse::<SourceRegion>()
+                .ok()
+                .map(|region| snippet.with_region(Some(region))),
+            SnippetField::Tags => Some(snippet.with_tags_text(&text)),
+            SnippetField::Location => Some(snippet.with_location(Some(text))),
+            // Notes left empty are removed rather than kept as blanks
+            SnippetField::Note(index) if text.trim().is_empty() => {
+                Some(snippet.without_note_at(index))
+            }
+            SnippetField::Note(index) => Some(snippet.with_note_at(index, text.as_str().into())),
+        };
+
+        // An invalid value, like a page number that isn't a number or a malformed
+        // region, keeps the field open
+        match selected_snippet {
+            Some(selected_snippet) => Self {
+                selected_snippet,
+                line_editor: None,
+                ..self
+            }
+            .with_selected_field_kept_in_range(),
+            None => self,
+        }
+    }
+
+    fn with_field_edit_cancelled(self) -> Self {
+        Self {
+            line_editor: None,
+            ..self
+        }
+        .with_selected_field_kept_in_range()
+    }
+
+    fn with_source_type_cycled(self) -> Self {
+        let source_text = match &self.selected_snippet.source {
+            Some(Source::Other(text)) | Some(Source::ScreenshotFilename(text)) => text.clone(),
+            _ => self.source_text.clone(),
+        };
+
+        let source = match self.selected_snippet.source.clone() {
+            Some(Source::Other(text)) => Some(Source::ScreenshotFilename(text)),
+            Some(Source::ScreenshotFilename(filename)) => {
+                Some(Source::ManualPageNumber(filename.trim().parse().unwrap_or(0)))
+            }
+            Some(Source::ManualPageNumber(_)) => None,
+            None => Some(Source::Other(source_text.clone())),
+        };
+
+        Self {
+            selected_snippet: self.selected_snippet.with_source(source),
+            source_text,
+            ..self
+        }
+    }
+
+    fn with_selected_note_deleted(self) -> Self {
+        match self.selected_field {
+            SnippetField::Note(index) => Self {
+                selected_snippet: self.selected_snippet.without_note_at(index),
+                ..self
+            }
+            .with_selected_field_kept_in_range(),
+            _ => self,
+        }
+    }
+
+    fn with_event_applied_to_line_editor(self, event: EditorEvent) -> Self {
+        if self.line_editor.is_none() {
+            return self.with_event_applied_to_word_editor(event);
+        }
+
+        Self {
+            line_editor: self.line_editor.map(|editor| editor.apply(event)),
+            ..self
+        }
+    }
+
     pub fn to_view(&self, selected_snippet: bool, retained: bool, dictionary: &Dictionary) -> SnippetView {
         let word_views: Vec<WordView> = self
             .selected_snippet
@@ -177,9 +415,7 @@ impl SnippetEditor {
                     WordType::English(text) => Definition::Confirmed(text.into()),
                     WordType::Tunic(tunic_word) => dictionary
                         .get(&tunic_word.into())
-                        .map(|entry| entry.definition())
-                        .unwrap_or(&Definition::Undefined)
-                        .clone(),
+                        .map_or(Definition::Undefined, Entry::definition),
                 };
 
                 let params = WordViewParams {
@@ -218,6 +454,8 @@ impl SnippetEditor {
             word_views,
             transcribed,
             retained,
+            selected_field: self.selected_field,
+            line_editor_view: self.line_editor.as_ref().map(|editor| editor.to_view()),
         }
     }
 
@@ -249,8 +487,29 @@ impl AppliesEditorEvents for SnippetEditor {
             EditorEvent::MoveWordCursorForward => self.with_word_selection_moved_forward(1),
             EditorEvent::AddNewTunicWordAtCursor => self.with_new_tunic_word_at_cursor(),
             EditorEvent::AddNewEnglishWordAtCursor(text) => self.with_new_english_word_at_cursor(text),
+            EditorEvent::TypeNewEnglishWordAtCursor => self.with_new_english_word_typed_at_cursor(),
+            EditorEvent::ConfirmEnglishWordEdit | EditorEvent::CancelEnglishWordEdit => self
+                .with_event_applied_to_word_editor(event)
+                .with_blank_english_word_at_cursor_deleted(),
             EditorEvent::DeleteWordAtCursor => self.with_word_at_cursor_deleted(),
             EditorEvent::ToggleSnippetTranscriptionState => self.with_transcription_state_toggled(),
+            EditorEvent::MoveSnippetFieldCursorForward => self.with_field_selection_moved_forward(1),
+            EditorEvent::MoveSnippetFieldCursorBackward => {
+                self.with_field_selection_moved_backward(1)
+            }
+            EditorEvent::EditSelectedSnippetField => self.with_selected_field_opened(),
+            EditorEvent::ConfirmSnippetFieldEdit => self.with_field_edit_confirmed(),
+            EditorEvent::CancelSnippetFieldEdit => self.with_field_edit_cancelled(),
+            EditorEvent::CycleSnippetSourceType => self.with_source_type_cycled(),
+            EditorEvent::AddNewNoteToSnippet => self.with_new_note_opened(),
+            EditorEvent::DeleteSelectedSnippetNote => self.with_selected_note_deleted(),
+            EditorEvent::InsertTextAtTextCursor(_)
+            | EditorEvent::DeleteTextBeforeTextCursor
+            | EditorEvent::DeleteTextAtTextCursor
+            | EditorEvent::MoveTextCursorForward
+            | EditorEvent::MoveTextCursorBackward
+            | EditorEvent::MoveTextCursorToStart
+            | EditorEvent::MoveTextCursorToEnd => self.with_event_applied_to_line_editor(event),
             EditorEvent::MoveWordsViewSliceForward(amount) => {
                 self.with_word_view_slice_moved_forward(amount)
             }
@@ -275,3 +534,54 @@ impl AppliesEditorEvents for SnippetEditor {
         editor.with_visible_word_selected()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snippet_with_source(source: Source) -> SnippetEditor {
+        SnippetEditor::new(Snippet::starting_snippet().with_source(Some(source)))
+    }
+
+    fn source_edited(editor: SnippetEditor, text: &str) -> SnippetEditor {
+        editor
+            .apply(EditorEvent::MoveSnippetFieldCursorForward)
+            .apply(EditorEvent::EditSelectedSnippetField)
+            .apply(EditorEvent::MoveTextCursorToStart)
+            .apply(EditorEvent::DeleteTextAtTextCursor)
+            .apply(EditorEvent::InsertTextAtTextCursor(text.into()))
+            .apply(EditorEvent::ConfirmSnippetFieldEdit)
+    }
+
+    #[test]
+    fn cycling_the_source_type_keeps_the_typed_text() {
+        let editor = snippet_with_source(Source::Other("shop.png".into()));
+
+        let editor = editor.apply(EditorEvent::CycleSnippetSourceType);
+        assert_eq!(
+            editor.selected_snippet().source,
+            Some(Source::ScreenshotFilename("shop.png".into()))
+        );
+
+        let editor = editor.apply(EditorEvent::CycleSnippetSourceType);
+        assert_eq!(editor.selected_snippet().source, Some(Source::ManualPageNumber(0)));
+
+        let editor = editor
+            .apply(EditorEvent::CycleSnippetSourceType)
+            .apply(EditorEvent::CycleSnippetSourceType);
+        assert_eq!(editor.selected_snippet().source, Some(Source::Other("shop.png".into())));
+    }
+
+    #[test]
+    fn page_numbers_that_are_not_numbers_keep_the_source_field_open() {
+        let editor = source_edited(snippet_with_source(Source::ManualPageNumber(3)), "x");
+
+        assert!(editor.is_editing_text());
+        assert_eq!(editor.selected_snippet().source, Some(Source::ManualPageNumber(3)));
+
+        let editor = source_edited(snippet_with_source(Source::ManualPageNumber(3)), " 12 ");
+
+        assert!(!editor.is_editing_text());
+        assert_eq!(editor.selected_snippet().source, Some(Source::ManualPageNumber(12)));
+    }
+}
@@ -41,6 +41,13 @@ impl WordEditor {
         }
     }
 
+    pub fn is_editing_text(&self) -> bool {
+        match &self.sub_editor {
+            SubEditorType::English(editor) => editor.is_editing_text(),
+            SubEditorType::Tunic(_) => false,
+        }
+    }
+
     pub fn on_input(&self, callbacks: WordEditorCallbacks) -> EditorEvent {
         match &self.sub_editor {
             SubEditorType::Tunic(editor) => editor.on_input(callbacks),
@@ -60,10 +67,16 @@ impl WordEditor {
 
                 Self { sub_editor, ..self }
             }
-            WordType::English(english_word) => Self {
-                sub_editor: SubEditorType::English(english_word.into()),
-                ..self
-            },
+            WordType::English(english_word) => {
+                let sub_editor = match &self.sub_editor {
+                    SubEditorType::English(editor) => {
+                        SubEditorType::English(editor.clone().with_word(english_word.clone()))
+                    }
+                    _ => SubEditorType::English(english_word.into()),
+                };
+
+                Self { sub_editor }
+            }
         }
     }
 
@@ -5,6 +5,7 @@ use crate::prelude::*;
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct EnglishWordEditor {
     word: EnglishWord,
+    line_editor: Option<LineEditor>,
 }
 
 impl EnglishWordEditor {
@@ -16,13 +17,17 @@ impl EnglishWordEditor {
             index: params.index,
             within_visible_range: params.within_visible_range,
             definition: params.definition,
+            line_editor_view: self.line_editor.as_ref().map(|editor| editor.to_view()),
         }
     }
 }
 
 impl EnglishWordEditor {
     pub fn new(word: EnglishWord) -> Self {
-        Self { word }
+        Self {
+            word,
+            line_editor: None,
+        }
     }
 
     pub fn on_input(&self, callbacks: WordEditorCallbacks) -> EditorEvent {
@@ -32,22 +37,64 @@ impl EnglishWordEditor {
     pub fn word(&self) -> EnglishWord {
         self.word.clone()
     }
+
+    pub fn with_word(self, word: EnglishWord) -> Self {
+        Self { word, ..self }
+    }
+
+    pub fn is_editing_text(&self) -> bool {
+        self.line_editor.is_some()
+    }
+
+    fn with_text_editing_started(self) -> Self {
+        let line_editor = LineEditor::new(&self.word.text());
+
+        Self {
+            line_editor: Some(line_editor),
+            ..self
+        }
+    }
+
+    fn with_text_editing_confirmed(self) -> Self {
+        match &self.line_editor {
+            Some(line_editor) => Self {
+                word: line_editor.text().into(),
+                line_editor: None,
+            },
+            None => self,
+        }
+    }
+
+    fn with_text_editing_cancelled(self) -> Self {
+        Self {
+            line_editor: None,
+            ..self
+        }
+    }
 }
 
 impl AppliesEditorEvents for EnglishWordEditor {
-    fn apply(self, _event: EditorEvent) -> Self {
-        self
+    fn apply(self, event: EditorEvent) -> Self {
+        match event {
+            EditorEvent::EditEnglishWordAtCursor => self.with_text_editing_started(),
+            EditorEvent::ConfirmEnglishWordEdit => self.with_text_editing_confirmed(),
+            EditorEvent::CancelEnglishWordEdit => self.with_text_editing_cancelled(),
+            _ => Self {
+                line_editor: self.line_editor.map(|editor| editor.apply(event)),
+                ..self
+            },
+        }
     }
 }
 
 impl From<&EnglishWord> for EnglishWordEditor {
     fn from(word: &EnglishWord) -> Self {
-        EnglishWordEditor { word: word.clone() }
+        EnglishWordEditor::new(word.clone())
     }
 }
 
 impl From<EnglishWord> for EnglishWordEditor {
     fn from(word: EnglishWord) -> Self {
-        EnglishWordEditor { word }
+        EnglishWordEditor::new(word)
     }
 }
@@ -195,6 +195,7 @@ impl TunicWordEditor {
             index: params.index,
             within_visible_range: params.within_visible_range,
             definition: params.definition,
+            line_editor_view: None,
         }
     }
 }
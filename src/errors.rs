@@ -0,0 +1,147 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::process;
+
+use crate::prelude::*;
+
+pub type FennecResult<T> = Result<T, FennecError>;
+
+/// Every failure the library reports. Binaries turn these into an exit code
+/// with `FennecError::exit`.
+#[derive(Debug)]
+pub enum FennecError {
+    Io {
+        path: String,
+        error: io::Error,
+    },
+    Parse {
+        path: String,
+        line: Option<usize>,
+        column: Option<usize>,
+        description: String,
+    },
+    Validation(String),
+    Glyph(NotationError),
+    Migration(MigrationError),
+    Render(String),
+}
+
+impl FennecError {
+    pub fn io(path: &str, error: io::Error) -> Self {
+        Self::Io {
+            path: path.into(),
+            error,
+        }
+    }
+
+    pub fn yaml(path: &str, error: serde_yaml::Error) -> Self {
+        let location = error.location();
+
+        Self::Parse {
+            path: path.into(),
+            line: location.as_ref().map(|location| location.line()),
+            column: location.as_ref().map(|location| location.column()),
+            description: error.to_string(),
+        }
+    }
+
+    pub fn toml(path: &str, error: toml::de::Error) -> Self {
+        // toml counts lines and columns from 0
+        let location = error.line_col();
+
+        Self::Parse {
+            path: path.into(),
+            line: location.map(|(line, _)| line + 1),
+            column: location.map(|(_, column)| column + 1),
+            description: error.to_string(),
+        }
+    }
+
+    pub fn render(error: impl fmt::Display) -> Self {
+        Self::Render(error.to_string())
+    }
+
+    /// Follows the BSD `sysexits.h` convention so scripts can tell failures apart
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Self::Io { .. } => 74,
+            Self::Parse { .. } => 65,
+            Self::Validation(_) => 65,
+            Self::Glyph(_) => 65,
+            Self::Migration(_) => 65,
+            Self::Render(_) => 70,
+        }
+    }
+
+    /// Reports the error on stderr and ends the process
+    pub fn exit(&self) -> ! {
+        eprintln!("error: {}", self);
+
+        process::exit(self.exit_code())
+    }
+}
+
+impl fmt::Display for FennecError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Io { path, error } => write!(f, "{}: {}", path, error),
+            Self::Parse {
+                path,
+                line: Some(line),
+                column: Some(column),
+                description,
+            } => write!(f, "{}:{}:{}: {}", path, line, column, description),
+            Self::Parse {
+                path, description, ..
+            } => write!(f, "{}: {}", path, description),
+            Self::Validation(description) => write!(f, "{}", description),
+            Self::Glyph(error) => write!(f, "{}", error),
+            Self::Migration(error) => write!(f, "{}", error),
+            Self::Render(description) => write!(f, "Unable to render: {}", description),
+        }
+    }
+}
+
+impl Error for FennecError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Io { error, .. } => Some(error),
+            Self::Glyph(error) => Some(error),
+            Self::Migration(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<NotationError> for FennecError {
+    fn from(error: NotationError) -> Self {
+        Self::Glyph(error)
+    }
+}
+
+impl From<GlyphError> for FennecError {
+    fn from(error: GlyphError) -> Self {
+        Self::Glyph(error.into())
+    }
+}
+
+impl From<MigrationError> for FennecError {
+    fn from(error: MigrationError) -> Self {
+        Self::Migration(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_errors_point_at_the_line() {
+        let error = serde_yaml::from_str::<Notebook>("version: 3\nsnippets: 4\n").unwrap_err();
+        let error = FennecError::yaml("notebook.yaml", error);
+
+        assert!(error.to_string().starts_with("notebook.yaml:2:"));
+        assert_eq!(error.exit_code(), 65);
+    }
+}
@@ -0,0 +1,354 @@
+use std::ops::Range;
+
+use crate::prelude::*;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ExportFormat {
+    Markdown,
+    Text,
+    /// LaTeX for the `gb4e` package
+    Gb4e,
+    /// LaTeX for the `expex` package
+    Expex,
+}
+
+/// One aligned column of an interlinear gloss. Phrases take a single column
+/// for all of their words, which are the positions in `words`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GlossColumn {
+    pub words: Range<usize>,
+    pub glyphs: String,
+    pub reading: String,
+    pub definition: Definition,
+}
+
+/// A snippet as three aligned lines: glyphs, phonetic reading and definitions
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InterlinearGloss {
+    pub id: SnippetId,
+    pub description: String,
+    pub source: Option<Source>,
+    pub columns: Vec<GlossColumn>,
+}
+
+impl InterlinearGloss {
+    pub fn new(snippet: &Snippet, dictionary: &Dictionary, phoneme_table: &PhonemeTable) -> Self {
+        let columns = glosses_for(&snippet.words, dictionary)
+            .into_iter()
+            .map(|gloss| {
+                let words = &snippet.words[gloss.words.clone()];
+
+                // English words are already read, so they are their own definition
+                match &words[0].word_type {
+                    WordType::English(english_word) => GlossColumn {
+                        words: gloss.words,
+                        glyphs: english_word.text(),
+                        reading: String::new(),
+                        definition: Definition::Confirmed(english_word.text()),
+                    },
+                    WordType::Tunic(_) => GlossColumn {
+                        glyphs: join_words(words, |word| word.to_string()),
+                        reading: join_words(words, |word| {
+                            phoneme_table.read_glyphs(&word.glyphs())
+                        }),
+                        definition: gloss.definition(),
+                        words: gloss.words,
+                    },
+                }
+            })
+            .collect();
+
+        Self {
+            id: snippet.id.clone(),
+            description: snippet.description.clone(),
+            source: snippet.source.clone(),
+            columns,
+        }
+    }
+
+    pub fn render(&self, format: ExportFormat) -> String {
+        match format {
+            ExportFormat::Markdown => self.to_markdown(),
+            ExportFormat::Text => self.to_text(),
+            ExportFormat::Gb4e => self.to_gb4e(),
+            ExportFormat::Expex => self.to_expex(),
+        }
+    }
+
+    pub fn title(&self) -> String {
+        format!("{}: {}", self.id.to_reference(), self.description)
+    }
+
+    pub fn source_text(&self) -> Option<String> {
+        self.source.as_ref().map(|source| match source {
+            Source::ManualPageNumber(page_number) => format!("Manual page {}", page_number),
+            Source::ScreenshotFilename(filename) => format!("Screenshot {}", filename),
+            Source::Other(text) => text.clone(),
+        })
+    }
+
+    fn to_markdown(&self) -> String {
+        let mut lines = vec![format!("### {}", escape_markdown(&self.title())), String::new()];
+
+        if let Some(source) = self.source_text() {
+            lines.push(format!("Source: {}", escape_markdown(&source)));
+            lines.push(String::new());
+        }
+
+        let row = |cells: Vec<String>| format!("| {} |", cells.join(" | "));
+
+        lines.push(row(self.cells(|column| escape_markdown(&column.glyphs))));
+        lines.push(row(self.cells(|_| "---".to_owned())));
+        lines.push(row(self.cells(|column| escape_markdown(&column.reading))));
+        lines.push(row(self.cells(|column| {
+            style_definition(&column.definition, escape_markdown, "*", "*", "\\_\\_\\_")
+        })));
+
+        lines.join("\n") + "\n"
+    }
+
+    /// Tentative definitions are in parentheses
+    fn to_text(&self) -> String {
+        let definitions = self.cells(|column| {
+            style_definition(&column.definition, str::to_owned, "(", ")", "___")
+        });
+        let widths: Vec<usize> = self
+            .columns
+            .iter()
+            .zip(definitions.iter())
+            .map(|(column, definition)| {
+                [&column.glyphs, &column.reading, definition]
+                    .iter()
+                    .map(|text| text.chars().count())
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+
+        let aligned = |cells: Vec<String>| {
+            cells
+                .iter()
+                .zip(widths.iter())
+                .map(|(cell, width)| format!("{:width$}", cell, width = width))
+                .collect::<Vec<String>>()
+                .join("  ")
+                .trim_end()
+                .to_owned()
+        };
+
+        let mut lines = vec![self.title()];
+
+        if let Some(source) = self.source_text() {
+            lines.push(source);
+        }
+
+        lines.push(aligned(self.cells(|column| column.glyphs.clone())));
+        lines.push(aligned(self.cells(|column| column.reading.clone())));
+        lines.push(aligned(definitions));
+
+        lines.join("\n") + "\n"
+    }
+
+    fn to_gb4e(&self) -> String {
+        let words = |cells: Vec<String>| cells.join(" ");
+
+        let mut lines = vec![
+            format!("% {}", self.title()),
+            "\\begin{exe}".to_owned(),
+            format!("\\ex\\label{{snippet:{}}}", self.id),
+        ];
+
+        lines.push(format!(
+            "\\glll {} \\\\",
+            words(self.cells(|column| latex_group(&column.glyphs)))
+        ));
+        lines.push(format!(
+            "{} \\\\",
+            words(self.cells(|column| latex_group(&column.reading)))
+        ));
+        lines.push(format!("{} \\\\", words(self.latex_definitions())));
+        lines.push(format!("\\glt {}", escape_latex(&self.description)));
+        lines.push("\\end{exe}".to_owned());
+
+        lines.join("\n") + "\n"
+    }
+
+    fn to_expex(&self) -> String {
+        let words = |cells: Vec<String>| cells.join(" ");
+
+        let lines = vec![
+            format!("% {}", self.title()),
+            format!("\\ex\\label{{snippet:{}}}", self.id),
+            "\\begingl".to_owned(),
+            format!("\\gla {} //", words(self.cells(|column| latex_group(&column.glyphs)))),
+            format!("\\glb {} //", words(self.cells(|column| latex_group(&column.reading)))),
+            format!("\\glc {} //", words(self.latex_definitions())),
+            format!("\\glft {} //", escape_latex(&self.description)),
+            "\\endgl".to_owned(),
+            "\\xe".to_owned(),
+        ];
+
+        lines.join("\n") + "\n"
+    }
+
+    fn cells<F>(&self, cell: F) -> Vec<String>
+    where
+        F: Fn(&GlossColumn) -> String,
+    {
+        self.columns.iter().map(cell).collect()
+    }
+
+    fn latex_definitions(&self) -> Vec<String> {
+        self.cells(|column| {
+            let text = style_definition(
+                &column.definition,
+                escape_latex,
+                "\\textit{",
+                "}",
+                "\\_\\_\\_",
+            );
+
+            format!("{{{}}}", text)
+        })
+    }
+}
+
+impl GlossColumn {
+    pub fn is_phrase(&self) -> bool {
+        self.words.len() > 1
+    }
+}
+
+/// Glosses and renders snippets one after another, with their IDs in the
+/// titles and labels
+pub fn export_snippets<'a, I>(
+    snippets: I,
+    dictionary: &Dictionary,
+    phoneme_table: &PhonemeTable,
+    format: ExportFormat,
+) -> String
+where
+    I: IntoIterator<Item = &'a Snippet>,
+{
+    snippets
+        .into_iter()
+        .map(|snippet| InterlinearGloss::new(snippet, dictionary, phoneme_table).render(format))
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+fn join_words<F>(words: &[Word], format_word: F) -> String
+where
+    F: Fn(&TunicWord) -> String,
+{
+    words
+        .iter()
+        .filter_map(|word| match &word.word_type {
+            WordType::Tunic(tunic_word) => Some(format_word(tunic_word)),
+            WordType::English(_) => None,
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+/// Confirmed definitions are left plain and tentative ones are wrapped in
+/// `open` and `close`
+fn style_definition<E>(
+    definition: &Definition,
+    escape: E,
+    open: &str,
+    close: &str,
+    undefined: &str,
+) -> String
+where
+    E: Fn(&str) -> String,
+{
+    match definition {
+        Definition::Undefined => undefined.to_owned(),
+        Definition::Tentative(text) => format!("{}{}{}", open, escape(text), close),
+        Definition::Confirmed(text) => escape(text),
+    }
+}
+
+fn escape_markdown(text: &str) -> String {
+    text.chars()
+        .flat_map(|character| match character {
+            '\\' | '*' | '_' | '|' | '`' | '[' | ']' => vec!['\\', character],
+            _ => vec![character],
+        })
+        .collect()
+}
+
+fn escape_latex(text: &str) -> String {
+    text.chars()
+        .map(|character| match character {
+            '\\' => "\\textbackslash{}".to_owned(),
+            '~' => "\\textasciitilde{}".to_owned(),
+            '^' => "\\textasciicircum{}".to_owned(),
+            '&' | '%' | '$' | '#' | '_' | '{' | '}' => format!("\\{}", character),
+            _ => character.to_string(),
+        })
+        .collect()
+}
+
+/// Words with spaces inside are braced so the gloss keeps them in one column
+fn latex_group(text: &str) -> String {
+    format!("{{{}}}", escape_latex(text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gloss() -> InterlinearGloss {
+        let word: TunicWord = "fɑ k".parse().unwrap();
+        let snippet = Snippet {
+            words: vec![
+                word.clone().into(),
+                "!".into(),
+                "ɑ".parse::<TunicWord>().unwrap().into(),
+            ],
+            id: SnippetId::for_position(0),
+            description: "Fox_1".into(),
+            ..Snippet::default()
+        };
+        let dictionary = Dictionary::new().with_new_definition(&word, "fox".into());
+
+        InterlinearGloss::new(&snippet, &dictionary, &PhonemeTable::default())
+    }
+
+    #[test]
+    fn columns_line_up_glyphs_readings_and_definitions() {
+        let gloss = gloss();
+
+        assert_eq!(gloss.columns.len(), 3);
+        assert_eq!(gloss.columns[0].reading, "fɑk");
+        assert_eq!(gloss.columns[0].definition, Definition::Tentative("fox".into()));
+        assert_eq!(gloss.columns[1].words, 1..2);
+        assert_eq!(gloss.columns[1].reading, "");
+        assert_eq!(gloss.columns[2].definition, Definition::Undefined);
+    }
+
+    #[test]
+    fn text_columns_are_padded_to_the_widest_line() {
+        let text = gloss().render(ExportFormat::Text);
+        let lines: Vec<&str> = text.lines().collect();
+
+        let bang_column = |line: &str| line.chars().position(|character| character == '!');
+
+        assert_eq!(lines[0], "#00000001: Fox_1");
+        assert!(bang_column(lines[1]).is_some());
+        assert_eq!(bang_column(lines[1]), bang_column(lines[3]));
+        assert!(lines[2].starts_with("fɑk "));
+        assert!(lines[3].starts_with("(fox) ") && lines[3].ends_with("!  ___"));
+    }
+
+    #[test]
+    fn markdown_and_latex_are_escaped() {
+        assert!(gloss().render(ExportFormat::Markdown).contains("### #00000001: Fox\\_1"));
+        assert!(gloss()
+            .render(ExportFormat::Gb4e)
+            .contains("{\\textit{fox}} {!} {\\_\\_\\_} \\\\"));
+        assert!(gloss().render(ExportFormat::Expex).contains("\\glft Fox\\_1 //"));
+    }
+}
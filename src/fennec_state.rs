@@ -1,23 +1,108 @@
 use std::error::Error;
+use std::time::Instant;
 
 use crate::prelude::*;
 
 pub struct FennecState {
     file_editor: FileEditor,
-    dictionary: Dictionary,
+    dictionary_editor: DictionaryEditor,
+    phoneme_table: PhonemeTable,
+    file_watcher: FileWatcher,
+    last_watched: Instant,
+    hovered_segment: Option<Segment>,
 }
 
 impl FennecState {
-    pub fn new(snippet: Snippet, dictionary: Dictionary) -> Self {
+    pub fn new(snippet: Snippet, dictionary: Dictionary, config: &FennecConfig) -> Self {
         let notebook: Notebook = vec![snippet].into();
-        let file_editor = FileEditor::new(notebook.clone(), DEFAULT_NOTEBOOK_FILE);
+        let file_editor = FileEditor::new(notebook.clone(), &config.notebook);
         let file_editor = file_editor.apply(EditorEvent::ConfirmLoadFromFileRequest);
 
-        Self { file_editor, dictionary }
+        Self {
+            file_editor,
+            dictionary_editor: DictionaryEditor::new(dictionary, &config.dictionary),
+            phoneme_table: PhonemeTable::default(),
+            file_watcher: FileWatcher::new(&[&config.notebook, &config.dictionary]),
+            last_watched: Instant::now(),
+            hovered_segment: None,
+        }
+    }
+
+    pub fn with_phoneme_table(self, phoneme_table: PhonemeTable) -> Self {
+        Self {
+            phoneme_table,
+            ..self
+        }
+    }
+
+    /// A notebook changed on disk is asked about first, even over the dictionary
+    fn is_notebook_changed_on_disk(&self) -> bool {
+        self.file_editor.state() == FileEditorState::ChangedOnDisk
+    }
+
+    /// The dictionary editor takes input while open, or while asking about a
+    /// change on disk
+    fn is_dictionary_editor_active(&self) -> bool {
+        !self.is_notebook_changed_on_disk()
+            && (self.dictionary_editor.is_open()
+                || self.dictionary_editor.state() == FileEditorState::ChangedOnDisk)
+    }
+
+    /// Files that fail to load are left for the next change, since another tool
+    /// may still be writing them
+    fn reload_changed_files(&mut self) {
+        if self.last_watched.elapsed() < FILE_WATCH_INTERVAL {
+            return;
+        }
+
+        self.last_watched = Instant::now();
+
+        for file in self.file_watcher.changed_files() {
+            if file == self.file_editor.target_file() {
+                match notebook_from_yaml_file(&file) {
+                    Ok((notebook, _yaml)) => {
+                        let event = EditorEvent::ReportNotebookChangedOnDisk(notebook);
+                        self.file_editor = self.file_editor.clone().apply(event);
+                    }
+                    Err(error) => eprintln!("error: Failed to reload notebook: {}", error),
+                }
+            } else if file == self.dictionary_editor.target_file() {
+                match dictionary_from_yaml_file(&file) {
+                    Ok((dictionary, _yaml)) => {
+                        let event = EditorEvent::ReportDictionaryChangedOnDisk(dictionary);
+                        self.dictionary_editor = self.dictionary_editor.clone().apply(event);
+                    }
+                    Err(error) => eprintln!("error: Failed to reload dictionary: {}", error),
+                }
+            }
+        }
     }
 
     fn render(&self, map: &mut GlyphMap, ctx: &mut BTerm) -> Result<(), Box<dyn Error>> {
-        self.file_editor.render_with(&self.dictionary, |file_editor_view| {
+        if self.dictionary_editor.is_open() {
+            ctx.set_active_console(SNIPPET_CONSOLE);
+            ctx.cls();
+
+            self.dictionary_editor.render_with(|dictionary_editor_view| {
+                render_dictionary_editor_on(&dictionary_editor_view, ctx, 1, 1)
+            })?;
+
+            if self.is_notebook_changed_on_disk() {
+                let target_file = self.file_editor.target_file();
+                let state = self.file_editor.state();
+
+                render_file_state_onto(&state, "notebook", &target_file, ctx)?;
+            }
+
+            self.render_status_notes_on(ctx);
+
+            // Nothing is added to the map, so drawing it clears the glyphs behind the entry
+            return Ok(());
+        }
+
+        let dictionary = self.dictionary_editor.displayed_dictionary();
+
+        self.file_editor.render_with(dictionary, |file_editor_view| {
             ctx.set_active_console(FILE_CONSOLE);
             ctx.cls();
             ctx.set_active_console(NOTEBOOK_CONSOLE);
@@ -28,64 +113,122 @@ impl FennecState {
             match &file_editor_view.state {
                 FileEditorState::Idle => {
                     let notebook_view = &file_editor_view.notebook_view;
-                    render_notebook_on(notebook_view, &self.dictionary, map, ctx, 1, 1)?;
+                    render_notebook_on(
+                        notebook_view,
+                        dictionary,
+                        &self.phoneme_table,
+                        map,
+                        ctx,
+                        1,
+                        1,
+                    )?;
                 }
                 _ => render_file_editor_view_onto(&file_editor_view, ctx)?,
             };
 
+            if self.dictionary_editor.state() == FileEditorState::ChangedOnDisk
+                && !self.is_notebook_changed_on_disk()
+            {
+                let target_file = self.dictionary_editor.target_file();
+                let state = self.dictionary_editor.state();
+                let conflicts = self.dictionary_editor.conflicts();
+
+                render_dictionary_state_onto(&state, &target_file, conflicts, ctx)?;
+            }
+
+            self.render_status_notes_on(ctx);
+
             Ok(())
         })?;
 
-        map.draw_on(ctx, 1, 1)?;
-
         Ok(())
     }
 
-    fn emergency_backup_and_abort(&self, error: Box<dyn Error>) {
-        println!("Rendering error");
-        dbg!(error);
+    /// Notes along the top of the screen about what isn't visible otherwise
+    fn render_status_notes_on(&self, ctx: &mut BTerm) {
+        let hypothesis = self.dictionary_editor.hypothesis();
+        let mut notes: Vec<String> = vec![];
+
+        if *hypothesis != Hypothesis::Preferred {
+            notes.push(format!("Showing hypothesis: {}", hypothesis));
+        }
+
+        if self.dictionary_editor.has_unsaved_changes() {
+            notes.push("Unsaved dictionary changes (F2 in the dictionary to save)".into());
+        }
+
+        ctx.set_active_console(FILE_CONSOLE);
+        ctx.print_color(1, 0, YELLOW, BLACK, notes.join("  "));
+    }
+
+    /// Saves what it can of the notebook next to the target file, then exits
+    fn emergency_backup_and_abort(&self, error: FennecError) -> ! {
+        eprintln!("error: {}", error);
 
         let file = self.file_editor.target_file();
         let file = format!("{}~", file);
 
-        println!("Trying to save notebook backup to: {}", &file);
+        eprintln!("Trying to save notebook backup to: {}", &file);
 
         let notebook = self.file_editor.to_source();
 
-        notebook_to_yaml_file(&notebook, &file).expect("Failed to save notebook backup");
-
-        println!("Saved notebook backup");
+        match notebook_to_yaml_file(&notebook, &file) {
+            Ok(_yaml) => eprintln!("Saved notebook backup"),
+            Err(backup_error) => {
+                eprintln!("error: Failed to save notebook backup: {}", backup_error)
+            }
+        }
 
-        panic!("Aborting!");
+        error.exit()
     }
 }
 
 impl GameState for FennecState {
     fn tick(&mut self, ctx: &mut BTerm) {
-        let mut map = GlyphMap::new(100, 100).expect("Invalid map dimensions");
+        let mut map = GlyphMap::new(100, 100)
+            .unwrap_or_else(|error| self.emergency_backup_and_abort(FennecError::render(error)));
 
-        let ctx_clone = ctx.clone();
+        self.reload_changed_files();
 
-        self.file_editor = {
-            let file_editor = self.file_editor.clone();
+        let input = KeyInput::from(&*ctx);
 
-            let event = file_editor.on_input(Box::new(move |editor| {
-                on_file_editor_input(editor, &ctx_clone)
+        let event = if self.is_dictionary_editor_active() {
+            self.dictionary_editor.on_input(Box::new(move |editor| {
+                on_dictionary_editor_input(editor, &input)
+            }))
+        } else {
+            let event = self.file_editor.on_input(Box::new(move |editor| {
+                on_file_editor_input(editor, &input)
             }));
 
-            if event != EditorEvent::NoOp {
-                file_editor.apply(event)
+            if event == EditorEvent::NoOp {
+                on_glyph_mouse_input(self.hovered_segment, ctx.left_click)
             } else {
-                file_editor
+                event
             }
         };
 
-        self.render(&mut map, ctx)
-            .map_err(|error| self.emergency_backup_and_abort(error))
-            .unwrap();
+        if event != EditorEvent::NoOp {
+            if !self.is_notebook_changed_on_disk() && self.dictionary_editor.handles(&event) {
+                self.dictionary_editor = self.dictionary_editor.clone().apply(event);
+            } else {
+                self.file_editor = self.file_editor.clone().apply(event);
+            }
+        }
+
+        if let Err(error) = self.render(&mut map, ctx) {
+            self.emergency_backup_and_abort(FennecError::render(error));
+        }
+
+        // Hovering is worked out on the drawn map, so a click acts on the next tick
+        self.hovered_segment = map.hover_mouse_on(ctx, 1, 1);
+
+        if let Err(error) = map.draw_on(ctx, 1, 1) {
+            self.emergency_backup_and_abort(FennecError::render(error));
+        }
 
-        render_draw_buffer(ctx)
-            .map_err(|error| self.emergency_backup_and_abort(error))
-            .unwrap();
+        if let Err(error) = render_draw_buffer(ctx) {
+            self.emergency_backup_and_abort(FennecError::render(error));
+        }
     }
 }
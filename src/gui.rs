@@ -1,7 +1,29 @@
-use std::error::Error;
 use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+use tempfile::NamedTempFile;
 
 use crate::prelude::*;
+
+/// The key pressed this frame and the modifiers held with it. The terminal
+/// and egui editors both translate their input into this.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct KeyInput {
+    pub key: Option<VirtualKeyCode>,
+    pub shift: bool,
+    pub control: bool,
+}
+
+impl From<&BTerm> for KeyInput {
+    fn from(ctx: &BTerm) -> Self {
+        Self {
+            key: ctx.key,
+            shift: ctx.shift,
+            control: ctx.control,
+        }
+    }
+}
+
 pub fn map_keys_to_glyph_segments(key: VirtualKeyCode, shift_key: bool) -> Vec<Segment> {
     match key {
         VirtualKeyCode::W if shift_key => vec![1, 3],
@@ -42,9 +64,72 @@ pub fn map_keys_to_glyph_segments(key: VirtualKeyCode, shift_key: bool) -> Vec<S
     }
 }
 
-pub fn on_modify_selected_glyph(_editor: &GlyphEditor, ctx: BTerm) -> EditorEvent {
-    if let Some(key) = ctx.key {
-        let segments = map_keys_to_glyph_segments(key, ctx.shift);
+pub fn map_key_to_character(key: VirtualKeyCode, shift_key: bool) -> Option<char> {
+    let (character, shifted_character) = match key {
+        VirtualKeyCode::A => ('a', 'A'),
+        VirtualKeyCode::B => ('b', 'B'),
+        VirtualKeyCode::C => ('c', 'C'),
+        VirtualKeyCode::D => ('d', 'D'),
+        VirtualKeyCode::E => ('e', 'E'),
+        VirtualKeyCode::F => ('f', 'F'),
+        VirtualKeyCode::G => ('g', 'G'),
+        VirtualKeyCode::H => ('h', 'H'),
+        VirtualKeyCode::I => ('i', 'I'),
+        VirtualKeyCode::J => ('j', 'J'),
+        VirtualKeyCode::K => ('k', 'K'),
+        VirtualKeyCode::L => ('l', 'L'),
+        VirtualKeyCode::M => ('m', 'M'),
+        VirtualKeyCode::N => ('n', 'N'),
+        VirtualKeyCode::O => ('o', 'O'),
+        VirtualKeyCode::P => ('p', 'P'),
+        VirtualKeyCode::Q => ('q', 'Q'),
+        VirtualKeyCode::R => ('r', 'R'),
+        VirtualKeyCode::S => ('s', 'S'),
+        VirtualKeyCode::T => ('t', 'T'),
+        VirtualKeyCode::U => ('u', 'U'),
+        VirtualKeyCode::V => ('v', 'V'),
+        VirtualKeyCode::W => ('w', 'W'),
+        VirtualKeyCode::X => ('x', 'X'),
+        VirtualKeyCode::Y => ('y', 'Y'),
+        VirtualKeyCode::Z => ('z', 'Z'),
+
+        VirtualKeyCode::Key1 => ('1', '!'),
+        VirtualKeyCode::Key2 => ('2', '@'),
+        VirtualKeyCode::Key3 => ('3', '#'),
+        VirtualKeyCode::Key4 => ('4', '$'),
+        VirtualKeyCode::Key5 => ('5', '%'),
+        VirtualKeyCode::Key6 => ('6', '^'),
+        VirtualKeyCode::Key7 => ('7', '&'),
+        VirtualKeyCode::Key8 => ('8', '*'),
+        VirtualKeyCode::Key9 => ('9', '('),
+        VirtualKeyCode::Key0 => ('0', ')'),
+
+        VirtualKeyCode::Space => (' ', ' '),
+        VirtualKeyCode::Apostrophe => ('\'', '"'),
+        VirtualKeyCode::Comma => (',', '<'),
+        VirtualKeyCode::Period => ('.', '>'),
+        VirtualKeyCode::Slash => ('/', '?'),
+        VirtualKeyCode::Semicolon => (';', ':'),
+        VirtualKeyCode::Minus => ('-', '_'),
+        VirtualKeyCode::Equals => ('=', '+'),
+        VirtualKeyCode::LBracket => ('[', '{'),
+        VirtualKeyCode::RBracket => (']', '}'),
+        VirtualKeyCode::Backslash => ('\\', '|'),
+        VirtualKeyCode::Grave => ('`', '~'),
+
+        _ => return None,
+    };
+
+    if shift_key {
+        Some(shifted_character)
+    } else {
+        Some(character)
+    }
+}
+
+pub fn on_modify_selected_glyph(_editor: &GlyphEditor, input: KeyInput) -> EditorEvent {
+    if let Some(key) = input.key {
+        let segments = map_keys_to_glyph_segments(key, input.shift);
 
         if segments.is_empty() {
             match key {
@@ -65,10 +150,19 @@ pub fn on_modify_selected_glyph(_editor: &GlyphEditor, ctx: BTerm) -> EditorEven
     }
 }
 
-pub fn on_modify_tunic_word(editor: &TunicWordEditor, ctx: BTerm) -> EditorEvent {
-    if let Some(key) = ctx.key {
+/// Clicking a segment of the selected glyph toggles it
+pub fn on_glyph_mouse_input(hovered_segment: Option<Segment>, clicked: bool) -> EditorEvent {
+    match hovered_segment {
+        Some(segment) if clicked => EditorEvent::ToggleSegmentOnSelectedGlyph(segment),
+        _ => EditorEvent::NoOp,
+    }
+}
+
+pub fn on_modify_tunic_word(editor: &TunicWordEditor, input: KeyInput) -> EditorEvent {
+    if let Some(key) = input.key {
         match key {
             VirtualKeyCode::F4 => EditorEvent::PrintWord(editor.word().into()),
+            VirtualKeyCode::F5 => EditorEvent::OpenDictionaryEntry(editor.word().into()),
             _ => EditorEvent::NoOp,
         }
     } else {
@@ -76,10 +170,11 @@ pub fn on_modify_tunic_word(editor: &TunicWordEditor, ctx: BTerm) -> EditorEvent
     }
 }
 
-pub fn on_modify_english_word(_editor: &EnglishWordEditor, ctx: BTerm) -> EditorEvent {
-    if let Some(key) = ctx.key {
+pub fn on_modify_english_word(_editor: &EnglishWordEditor, input: KeyInput) -> EditorEvent {
+    if let Some(key) = input.key {
         match key {
             VirtualKeyCode::Back => EditorEvent::DeleteWordAtCursor,
+            VirtualKeyCode::E => EditorEvent::EditEnglishWordAtCursor,
             _ => EditorEvent::NoOp,
         }
     } else {
@@ -87,36 +182,42 @@ pub fn on_modify_english_word(_editor: &EnglishWordEditor, ctx: BTerm) -> Editor
     }
 }
 
-pub fn on_snippet_editor_input(editor: &SnippetEditor, ctx: BTerm) -> EditorEvent {
-    if let Some(key) = ctx.key {
+pub fn on_snippet_editor_input(editor: &SnippetEditor, input: KeyInput) -> EditorEvent {
+    if editor.is_editing_word_text() {
+        return match input.key {
+            Some(VirtualKeyCode::Return) => EditorEvent::ConfirmEnglishWordEdit,
+            Some(VirtualKeyCode::Escape) => EditorEvent::CancelEnglishWordEdit,
+            _ => on_line_editor_input(&input),
+        };
+    }
+
+    if let Some(key) = input.key {
         match key {
-            VirtualKeyCode::Up if ctx.shift => EditorEvent::MoveWordsViewSliceBackward(1),
-            VirtualKeyCode::Down if ctx.shift => EditorEvent::MoveWordsViewSliceForward(1),
+            VirtualKeyCode::Up if input.shift => EditorEvent::MoveWordsViewSliceBackward(1),
+            VirtualKeyCode::Down if input.shift => EditorEvent::MoveWordsViewSliceForward(1),
             VirtualKeyCode::Up => EditorEvent::MoveWordCursorBackward,
             VirtualKeyCode::Down => EditorEvent::MoveWordCursorForward,
             VirtualKeyCode::Key0 => EditorEvent::ToggleSnippetTranscriptionState,
-            VirtualKeyCode::Return if ctx.shift && ctx.control => EditorEvent::AddNewEnglishWordAtCursor("...".to_owned()),
-            VirtualKeyCode::Return if ctx.shift => EditorEvent::AddNewEnglishWordAtCursor(".".to_owned()),
+            VirtualKeyCode::Return if input.shift && input.control => EditorEvent::AddNewEnglishWordAtCursor("...".to_owned()),
+            VirtualKeyCode::Return if input.shift => EditorEvent::AddNewEnglishWordAtCursor(".".to_owned()),
             VirtualKeyCode::Period => EditorEvent::AddNewEnglishWordAtCursor(".".to_owned()),
             VirtualKeyCode::Minus => EditorEvent::AddNewEnglishWordAtCursor("---".to_owned()),
             VirtualKeyCode::Comma => EditorEvent::AddNewEnglishWordAtCursor(",".to_owned()),
             VirtualKeyCode::Slash => EditorEvent::AddNewEnglishWordAtCursor("?".to_owned()),
             VirtualKeyCode::Key1 => EditorEvent::AddNewEnglishWordAtCursor("!".to_owned()),
-            VirtualKeyCode::Z => EditorEvent::AddNewEnglishWordAtCursor("PLACEHOLDER".to_owned()),
+            VirtualKeyCode::Z => EditorEvent::AddNewEnglishWordAtCursor(PLACEHOLDER_ENGLISH_WORD.to_owned()),
+            VirtualKeyCode::Apostrophe => EditorEvent::TypeNewEnglishWordAtCursor,
             VirtualKeyCode::Return => EditorEvent::AddNewTunicWordAtCursor,
             _ => {
-                let glyph_ctx = ctx.clone();
-                let tunic_word_ctx = ctx.clone();
-                let english_word_ctx = ctx.clone();
                 let callbacks = WordEditorCallbacks {
                     on_modify_selected_glyph: Box::new(move |glyph_editor| {
-                        on_modify_selected_glyph(glyph_editor, glyph_ctx.clone())
+                        on_modify_selected_glyph(glyph_editor, input)
                     }),
                     on_modify_tunic_word: Box::new(move |tunic_word_editor| {
-                        on_modify_tunic_word(tunic_word_editor, tunic_word_ctx.clone())
+                        on_modify_tunic_word(tunic_word_editor, input)
                     }),
                     on_modify_english_word: Box::new(move |english_word_editor| {
-                        on_modify_english_word(english_word_editor, english_word_ctx.clone())
+                        on_modify_english_word(english_word_editor, input)
                     }),
                 };
 
@@ -128,17 +229,61 @@ pub fn on_snippet_editor_input(editor: &SnippetEditor, ctx: BTerm) -> EditorEven
     }
 }
 
-pub fn on_notebook_editor_input(editor: &NotebookEditor, ctx: &BTerm) -> EditorEvent {
-    let ctx = ctx.clone();
-    let callback_ctx = ctx.clone();
+pub fn on_line_editor_input(input: &KeyInput) -> EditorEvent {
+    if let Some(key) = input.key {
+        match key {
+            VirtualKeyCode::Back => EditorEvent::DeleteTextBeforeTextCursor,
+            VirtualKeyCode::Delete => EditorEvent::DeleteTextAtTextCursor,
+            VirtualKeyCode::Left => EditorEvent::MoveTextCursorBackward,
+            VirtualKeyCode::Right => EditorEvent::MoveTextCursorForward,
+            VirtualKeyCode::Home => EditorEvent::MoveTextCursorToStart,
+            VirtualKeyCode::End => EditorEvent::MoveTextCursorToEnd,
+            _ => match map_key_to_character(key, input.shift) {
+                Some(character) => EditorEvent::InsertTextAtTextCursor(character.to_string()),
+                None => EditorEvent::NoOp,
+            },
+        }
+    } else {
+        EditorEvent::NoOp
+    }
+}
+
+pub fn on_snippet_details_input(editor: &SnippetEditor, input: KeyInput) -> EditorEvent {
+    if editor.is_editing_text() {
+        return match input.key {
+            Some(VirtualKeyCode::Return) => EditorEvent::ConfirmSnippetFieldEdit,
+            Some(VirtualKeyCode::Escape) => EditorEvent::CancelSnippetFieldEdit,
+            _ => on_line_editor_input(&input),
+        };
+    }
+
+    if let Some(key) = input.key {
+        match key {
+            VirtualKeyCode::Escape => EditorEvent::EnableSnippetEditingMode,
+            VirtualKeyCode::Up => EditorEvent::MoveSnippetFieldCursorBackward,
+            VirtualKeyCode::Down => EditorEvent::MoveSnippetFieldCursorForward,
+            VirtualKeyCode::Return => EditorEvent::EditSelectedSnippetField,
+            VirtualKeyCode::Tab => EditorEvent::CycleSnippetSourceType,
+            VirtualKeyCode::Plus => EditorEvent::AddNewNoteToSnippet,
+            VirtualKeyCode::N => EditorEvent::AddNewNoteToSnippet,
+            VirtualKeyCode::Delete => EditorEvent::DeleteSelectedSnippetNote,
+            _ => EditorEvent::NoOp,
+        }
+    } else {
+        EditorEvent::NoOp
+    }
+}
+
+pub fn on_notebook_editor_input(editor: &NotebookEditor, input: &KeyInput) -> EditorEvent {
+    let input = *input;
 
     let callback: Box<dyn Fn(&SnippetEditor) -> EditorEvent> = Box::new(move |snippet_editor| {
-        on_snippet_editor_input(snippet_editor, callback_ctx.clone())
+        on_snippet_editor_input(snippet_editor, input)
     });
 
     match editor.state() {
         NotebookEditorState::SelectingSnippet => {
-            if let Some(key) = ctx.key {
+            if let Some(key) = input.key {
                 match key {
                     VirtualKeyCode::Escape => EditorEvent::EnableSnippetEditingMode,
                     VirtualKeyCode::Return => EditorEvent::EnableSnippetEditingMode,
@@ -148,16 +293,28 @@ pub fn on_notebook_editor_input(editor: &NotebookEditor, ctx: &BTerm) -> EditorE
                     VirtualKeyCode::J => EditorEvent::MoveSnippetCursorForward,
                     VirtualKeyCode::Plus => EditorEvent::AddNewSnippetAtCursor,
                     VirtualKeyCode::T => EditorEvent::ToggleHasBeenTranscribedFilter,
+                    VirtualKeyCode::G => EditorEvent::CycleTagFilter,
+                    VirtualKeyCode::L => EditorEvent::CycleLocationFilter,
                     _ => EditorEvent::NoOp,
                 }
             } else {
                 EditorEvent::NoOp
             }
         }
+        NotebookEditorState::EditingSnippetDetails => {
+            let details_callback: Box<dyn Fn(&SnippetEditor) -> EditorEvent> =
+                Box::new(move |snippet_editor| on_snippet_details_input(snippet_editor, input));
+
+            editor.on_snippet_editor_input(details_callback)
+        }
+        NotebookEditorState::EditingSnippet if editor.is_editing_text() => {
+            editor.on_snippet_editor_input(callback)
+        }
         NotebookEditorState::EditingSnippet => {
-            if let Some(key) = ctx.key {
+            if let Some(key) = input.key {
                 match key {
                     VirtualKeyCode::Escape => EditorEvent::EnableSnippetNavigationMode,
+                    VirtualKeyCode::Tab => EditorEvent::EnableSnippetDetailsEditingMode,
                     _ => editor.on_snippet_editor_input(callback),
                 }
             } else {
@@ -167,14 +324,14 @@ pub fn on_notebook_editor_input(editor: &NotebookEditor, ctx: &BTerm) -> EditorE
     }
 }
 
-pub fn on_file_editor_input(editor: &FileEditor, ctx: &BTerm) -> EditorEvent {
-    let ctx = ctx.clone();
+pub fn on_file_editor_input(editor: &FileEditor, input: &KeyInput) -> EditorEvent {
+    let input = *input;
 
     match editor.state() {
-        FileEditorState::LoadRequestConfirmed => on_attempt_to_load_file(editor, &ctx),
-        FileEditorState::SaveRequestConfirmed => on_attempt_to_save_file(editor, &ctx),
+        FileEditorState::LoadRequestConfirmed => on_attempt_to_load_file(editor, &input),
+        FileEditorState::SaveRequestConfirmed => on_attempt_to_save_file(editor, &input),
         _ => {
-            if let Some(key) = ctx.key {
+            if let Some(key) = input.key {
                 match editor.state() {
                     FileEditorState::LoadRequestSucceeded => EditorEvent::ResetFileEditorToIdle,
                     FileEditorState::LoadRequestFailed(_) => EditorEvent::ResetFileEditorToIdle,
@@ -190,13 +347,23 @@ pub fn on_file_editor_input(editor: &FileEditor, ctx: &BTerm) -> EditorEvent {
                         VirtualKeyCode::Escape => EditorEvent::ResetFileEditorToIdle,
                         _ => EditorEvent::NoOp,
                     },
+                    FileEditorState::ChangedOnDisk => match key {
+                        VirtualKeyCode::Return => EditorEvent::AcceptNotebookChangesOnDisk,
+                        VirtualKeyCode::Escape => EditorEvent::KeepNotebookEdits,
+                        _ => EditorEvent::NoOp,
+                    },
                     FileEditorState::Idle => match key {
                         VirtualKeyCode::F2 => EditorEvent::RequestSaveToFile,
                         VirtualKeyCode::F3 => EditorEvent::RequestLoadFromFile,
+                        VirtualKeyCode::F6 => EditorEvent::OpenReverseLookup,
+                        VirtualKeyCode::F7 => EditorEvent::CycleDisplayedHypothesis,
+                        VirtualKeyCode::Z if input.control && input.shift => EditorEvent::Redo,
+                        VirtualKeyCode::Z if input.control => EditorEvent::Undo,
+                        VirtualKeyCode::Y if input.control => EditorEvent::Redo,
                         _ => {
                             let callback: Box<dyn Fn(&NotebookEditor) -> EditorEvent> =
                                 Box::new(move |notebook_editor| {
-                                    on_notebook_editor_input(notebook_editor, &ctx)
+                                    on_notebook_editor_input(notebook_editor, &input)
                                 });
 
                             editor.on_notebook_editor_input(callback)
@@ -211,45 +378,154 @@ pub fn on_file_editor_input(editor: &FileEditor, ctx: &BTerm) -> EditorEvent {
     }
 }
 
-pub fn notebook_from_yaml_file(target_file: &str) -> Result<(Notebook, String), Box<dyn Error>> {
-    let yaml = fs::read_to_string(target_file)?;
-    let notebook: Notebook = serde_yaml::from_str(&yaml)?;
+pub fn on_dictionary_editor_input(editor: &DictionaryEditor, input: &KeyInput) -> EditorEvent {
+    match editor.state() {
+        FileEditorState::SaveRequestConfirmed => on_attempt_to_save_dictionary(editor, input),
+        _ => {
+            if let Some(key) = input.key {
+                match editor.state() {
+                    FileEditorState::SaveRequestSucceeded => EditorEvent::ResetDictionaryEditorToIdle,
+                    FileEditorState::SaveRequestFailed(_) => EditorEvent::ResetDictionaryEditorToIdle,
+                    FileEditorState::ConfirmingSaveRequest => match key {
+                        VirtualKeyCode::Return => EditorEvent::ConfirmSaveDictionaryToFileRequest,
+                        VirtualKeyCode::Escape => EditorEvent::ResetDictionaryEditorToIdle,
+                        _ => EditorEvent::NoOp,
+                    },
+                    FileEditorState::ChangedOnDisk => match key {
+                        VirtualKeyCode::Return => EditorEvent::AcceptDictionaryChangesOnDisk,
+                        VirtualKeyCode::Escape => EditorEvent::KeepDictionaryEdits,
+                        _ => EditorEvent::NoOp,
+                    },
+                    FileEditorState::Idle if editor.is_editing_text() => match key {
+                        VirtualKeyCode::Return => EditorEvent::ConfirmDictionaryEntryEdit,
+                        VirtualKeyCode::Escape => EditorEvent::CancelDictionaryEntryEdit,
+                        _ => on_line_editor_input(input),
+                    },
+                    FileEditorState::Idle if !editor.is_entry_open() => match key {
+                        VirtualKeyCode::Return => editor
+                            .selected_reverse_lookup_result()
+                            .map_or(EditorEvent::NoOp, EditorEvent::OpenDictionaryEntry),
+                        VirtualKeyCode::Escape => EditorEvent::CloseReverseLookup,
+                        VirtualKeyCode::Up => EditorEvent::MoveReverseLookupCursorBackward,
+                        VirtualKeyCode::Down => EditorEvent::MoveReverseLookupCursorForward,
+                        VirtualKeyCode::F2 => EditorEvent::RequestSaveDictionaryToFile,
+                        _ => on_line_editor_input(input),
+                    },
+                    FileEditorState::Idle => match key {
+                        VirtualKeyCode::F2 => EditorEvent::RequestSaveDictionaryToFile,
+                        VirtualKeyCode::Escape => EditorEvent::CloseDictionaryEntry,
+                        VirtualKeyCode::Return => EditorEvent::EditDictionaryDefinition,
+                        VirtualKeyCode::A => EditorEvent::AddCandidateToDictionaryEntry,
+                        VirtualKeyCode::Up => EditorEvent::MoveDictionaryCandidateCursorBackward,
+                        VirtualKeyCode::Down => EditorEvent::MoveDictionaryCandidateCursorForward,
+                        VirtualKeyCode::Tab => EditorEvent::ToggleSelectedCandidateConfirmed,
+                        VirtualKeyCode::RBracket => EditorEvent::RaiseSelectedCandidateConfidence,
+                        VirtualKeyCode::LBracket => EditorEvent::LowerSelectedCandidateConfidence,
+                        VirtualKeyCode::Delete => EditorEvent::DeleteSelectedCandidate,
+                        VirtualKeyCode::N => EditorEvent::AddNoteToDictionaryEntry,
+                        VirtualKeyCode::Plus => EditorEvent::AddNoteToDictionaryEntry,
+                        _ => EditorEvent::NoOp,
+                    },
+                    _ => EditorEvent::NoOp,
+                }
+            } else {
+                EditorEvent::NoOp
+            }
+        }
+    }
+}
+
+pub fn notebook_from_yaml_file(target_file: &str) -> FennecResult<(Notebook, String)> {
+    let yaml = read_file(target_file)?;
+    let notebook: Notebook = from_migrated_yaml(target_file, &yaml, &Migrations::for_notebooks())?;
 
-    Ok((notebook, yaml))
+    Ok((notebook.with_missing_snippet_ids(), yaml))
 }
 
-pub fn notebook_to_yaml_file(
-    notebook: &Notebook,
-    target_file: &str,
-) -> Result<String, Box<dyn Error>> {
-    let yaml = serde_yaml::to_string(notebook)?;
+pub fn notebook_to_yaml_file(notebook: &Notebook, target_file: &str) -> FennecResult<String> {
+    let yaml =
+        serde_yaml::to_string(notebook).map_err(|error| FennecError::yaml(target_file, error))?;
 
-    fs::write(target_file, &yaml)?;
+    write_file(target_file, &yaml)?;
 
     Ok(yaml)
 }
 
-pub fn dictionary_from_yaml_file(
-    target_file: &str,
-) -> Result<(Dictionary, String), Box<dyn Error>> {
-    let yaml = fs::read_to_string(target_file)?;
-    let dictionary: Dictionary = serde_yaml::from_str(&yaml)?;
+pub fn dictionary_from_yaml_file(target_file: &str) -> FennecResult<(Dictionary, String)> {
+    let yaml = read_file(target_file)?;
+    let dictionary: Dictionary =
+        from_migrated_yaml(target_file, &yaml, &Migrations::for_dictionaries())?;
 
     Ok((dictionary, yaml))
 }
 
-pub fn dictionary_to_yaml_file(
-    dictionary: &Dictionary,
-    target_file: &str,
-) -> Result<String, Box<dyn Error>> {
-    let yaml = serde_yaml::to_string(dictionary)?;
+pub fn dictionary_to_yaml_file(dictionary: &Dictionary, target_file: &str) -> FennecResult<String> {
+    let yaml =
+        serde_yaml::to_string(dictionary).map_err(|error| FennecError::yaml(target_file, error))?;
 
-    fs::write(target_file, &yaml)?;
+    write_file(target_file, &yaml)?;
 
     Ok(yaml)
 }
 
-pub fn on_attempt_to_load_file(editor: &FileEditor, _ctx: &BTerm) -> EditorEvent {
+pub fn phoneme_table_from_yaml_file(target_file: &str) -> FennecResult<(PhonemeTable, String)> {
+    let yaml = read_file(target_file)?;
+    let table: PhonemeTable =
+        serde_yaml::from_str(&yaml).map_err(|error| FennecError::yaml(target_file, error))?;
+
+    Ok((table, yaml))
+}
+
+/// The phoneme table is optional, so a missing file gives the default table.
+/// A file that's there but broken is still an error.
+pub fn phoneme_table_or_default(target_file: &str) -> FennecResult<PhonemeTable> {
+    match phoneme_table_from_yaml_file(target_file) {
+        Ok((table, _yaml)) => Ok(table),
+        Err(FennecError::Io { error, .. }) if error.kind() == io::ErrorKind::NotFound => {
+            Ok(PhonemeTable::default())
+        }
+        Err(error) => Err(error),
+    }
+}
+
+fn read_file(target_file: &str) -> FennecResult<String> {
+    fs::read_to_string(target_file).map_err(|error| FennecError::io(target_file, error))
+}
+
+/// Writes to a new temporary file next to the target and renames it over the
+/// target, so a crash or a concurrent reader never sees a half-written file
+/// and two writers never share a temporary file
+pub fn write_file(target_file: &str, text: &str) -> FennecResult<()> {
+    let directory = match Path::new(target_file).parent() {
+        Some(directory) if !directory.as_os_str().is_empty() => directory,
+        _ => Path::new("."),
+    };
+
+    let mut temporary_file =
+        NamedTempFile::new_in(directory).map_err(|error| FennecError::io(target_file, error))?;
+
+    // Temporary files are only readable by their owner, unlike the file they replace
+    if let Ok(metadata) = fs::metadata(target_file) {
+        temporary_file
+            .as_file()
+            .set_permissions(metadata.permissions())
+            .map_err(|error| FennecError::io(target_file, error))?;
+    }
+
+    temporary_file
+        .write_all(text.as_bytes())
+        .and_then(|_| temporary_file.as_file().sync_all())
+        .map_err(|error| FennecError::io(target_file, error))?;
+
+    // The temporary file is removed when the rename fails
+    temporary_file
+        .persist(target_file)
+        .map_err(|error| FennecError::io(target_file, error.error))?;
+
+    Ok(())
+}
+
+pub fn on_attempt_to_load_file(editor: &FileEditor, _input: &KeyInput) -> EditorEvent {
     let file = editor.target_file();
 
     // TODO: Replace these println calls with proper logging
@@ -264,7 +540,7 @@ pub fn on_attempt_to_load_file(editor: &FileEditor, _ctx: &BTerm) -> EditorEvent
         Err(error) => {
             println!("Unable to load notebook from file");
 
-            let error: FileEditorError = if error.is::<serde_yaml::Error>() {
+            let error: FileEditorError = if let FennecError::Parse { .. } = error {
                 FileEditorError::new(
                     "Unable to parse YAML for the notebook".into(),
                     FileEditorErrorType::ParsingError,
@@ -287,7 +563,7 @@ pub fn on_attempt_to_load_file(editor: &FileEditor, _ctx: &BTerm) -> EditorEvent
     }
 }
 
-pub fn on_attempt_to_save_file(editor: &FileEditor, _ctx: &BTerm) -> EditorEvent {
+pub fn on_attempt_to_save_file(editor: &FileEditor, _input: &KeyInput) -> EditorEvent {
     let file = editor.target_file();
 
     // TODO: Replace these println calls with proper logging
@@ -317,3 +593,17 @@ pub fn on_attempt_to_save_file(editor: &FileEditor, _ctx: &BTerm) -> EditorEvent
         }
     }
 }
+
+pub fn on_attempt_to_save_dictionary(editor: &DictionaryEditor, _input: &KeyInput) -> EditorEvent {
+    let file = editor.target_file();
+
+    match dictionary_to_yaml_file(editor.dictionary(), &file) {
+        Ok(_yaml) => EditorEvent::ReportSavedDictionaryToFile,
+        Err(error) => EditorEvent::ReportFailedToSaveDictionaryToFile(FileEditorError::new(
+            "Unable to save dictionary to file".into(),
+            FileEditorErrorType::FileWriteError,
+            file.clone(),
+            Some(&error),
+        )),
+    }
+}
@@ -0,0 +1,158 @@
+use crate::prelude::*;
+
+/// Zoom is relative to the image fitting the frame
+pub const MIN_IMAGE_ZOOM: f32 = 0.5;
+pub const MAX_IMAGE_ZOOM: f32 = 20.0;
+
+/// Left, top, width and height in the frame's pixels
+pub type FrameRect = (f32, f32, f32, f32);
+
+/// Pan and zoom for showing a source image in a frame. At a zoom of 1 and no
+/// offset the whole image fits the frame, centered. The offset moves the image
+/// from there, in frame pixels.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ImageViewport {
+    pub zoom: f32,
+    pub offset: (f32, f32),
+}
+
+impl Default for ImageViewport {
+    fn default() -> Self {
+        Self {
+            zoom: 1.0,
+            offset: (0.0, 0.0),
+        }
+    }
+}
+
+impl ImageViewport {
+    /// Where the image is drawn within a frame of this size
+    pub fn image_rect(&self, image_size: (f32, f32), frame_size: (f32, f32)) -> FrameRect {
+        let scale = fit_scale(image_size, frame_size) * self.zoom;
+        let (width, height) = (image_size.0 * scale, image_size.1 * scale);
+
+        (
+            (frame_size.0 - width) / 2.0 + self.offset.0,
+            (frame_size.1 - height) / 2.0 + self.offset.1,
+            width,
+            height,
+        )
+    }
+
+    /// Where part of the image is drawn, given as fractions of the image
+    pub fn area_rect(
+        &self,
+        area: ImageArea,
+        image_size: (f32, f32),
+        frame_size: (f32, f32),
+    ) -> FrameRect {
+        let (x, y, width, height) = self.image_rect(image_size, frame_size);
+        let (left, top, area_width, area_height) = area;
+
+        (
+            x + left * width,
+            y + top * height,
+            area_width * width,
+            area_height * height,
+        )
+    }
+
+    pub fn panned_by(self, delta: (f32, f32)) -> Self {
+        Self {
+            offset: (self.offset.0 + delta.0, self.offset.1 + delta.1),
+            ..self
+        }
+    }
+
+    /// Zooms by `factor` while keeping the part of the image under `point`, in
+    /// frame pixels, where it is
+    pub fn zoomed_at(
+        self,
+        factor: f32,
+        point: (f32, f32),
+        image_size: (f32, f32),
+        frame_size: (f32, f32),
+    ) -> Self {
+        let zoom = (self.zoom * factor).clamp(MIN_IMAGE_ZOOM, MAX_IMAGE_ZOOM);
+        let (x, y, width, height) = self.image_rect(image_size, frame_size);
+
+        if width <= 0.0 || height <= 0.0 {
+            return Self { zoom, ..self };
+        }
+
+        let anchor = ((point.0 - x) / width, (point.1 - y) / height);
+        let (new_width, new_height) = (width * zoom / self.zoom, height * zoom / self.zoom);
+
+        Self {
+            zoom,
+            offset: (
+                point.0 - anchor.0 * new_width - (frame_size.0 - new_width) / 2.0,
+                point.1 - anchor.1 * new_height - (frame_size.1 - new_height) / 2.0,
+            ),
+        }
+    }
+
+    /// Zoomed so the area fills the frame, with the area in the middle
+    pub fn focused_on(area: ImageArea, image_size: (f32, f32), frame_size: (f32, f32)) -> Self {
+        let (left, top, area_width, area_height) = area;
+        let scale = fit_scale(image_size, frame_size);
+
+        if scale <= 0.0 || area_width <= 0.0 || area_height <= 0.0 {
+            return Self::default();
+        }
+
+        let zoom = (frame_size.0 / (area_width * image_size.0 * scale))
+            .min(frame_size.1 / (area_height * image_size.1 * scale))
+            .clamp(MIN_IMAGE_ZOOM, MAX_IMAGE_ZOOM);
+
+        let (width, height) = (image_size.0 * scale * zoom, image_size.1 * scale * zoom);
+
+        Self {
+            zoom,
+            offset: (
+                width * (0.5 - left - area_width / 2.0),
+                height * (0.5 - top - area_height / 2.0),
+            ),
+        }
+    }
+}
+
+fn fit_scale(image_size: (f32, f32), frame_size: (f32, f32)) -> f32 {
+    if image_size.0 <= 0.0 || image_size.1 <= 0.0 {
+        return 0.0;
+    }
+
+    (frame_size.0 / image_size.0).min(frame_size.1 / image_size.1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const IMAGE: (f32, f32) = (800.0, 400.0);
+    const FRAME: (f32, f32) = (400.0, 400.0);
+
+    #[test]
+    fn zooming_keeps_the_point_under_the_cursor() {
+        let viewport = ImageViewport::default();
+
+        assert_eq!(viewport.image_rect(IMAGE, FRAME), (0.0, 100.0, 400.0, 200.0));
+
+        let zoomed = viewport
+            .panned_by((20.0, 0.0))
+            .zoomed_at(2.0, (120.0, 150.0), IMAGE, FRAME);
+
+        assert_eq!(zoomed.zoom, 2.0);
+        assert_eq!(zoomed.image_rect(IMAGE, FRAME), (-80.0, 50.0, 800.0, 400.0));
+        assert_eq!(viewport.zoomed_at(1000.0, (0.0, 0.0), IMAGE, FRAME).zoom, MAX_IMAGE_ZOOM);
+    }
+
+    #[test]
+    fn focusing_fills_the_frame_with_the_area() {
+        let area = (0.5, 0.25, 0.25, 0.25);
+        let viewport = ImageViewport::focused_on(area, IMAGE, FRAME);
+
+        assert_eq!(viewport.zoom, 4.0);
+        assert_eq!(viewport.area_rect(area, IMAGE, FRAME), (0.0, 100.0, 400.0, 200.0));
+    }
+}
@@ -1,9 +1,19 @@
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
+use crate::prelude::*;
+
 pub mod dictionary;
+pub mod glosses;
+pub mod glyph_shapes;
 pub mod glyphs;
+pub mod notation;
 pub mod notebooks;
+pub mod patterns;
+pub mod phonology;
+pub mod regions;
+pub mod reverse_lookup;
+pub mod similarity;
 pub mod snippets;
 pub mod words;
 
@@ -16,6 +26,38 @@ pub enum Source {
     Other(String),
 }
 
+impl Source {
+    /// The editable part of the source, without its kind
+    pub fn text(&self) -> String {
+        match self {
+            Source::ManualPageNumber(page_number) => page_number.to_string(),
+            Source::ScreenshotFilename(filename) => filename.clone(),
+            Source::Other(text) => text.clone(),
+        }
+    }
+
+    /// Replaces the value while keeping the kind of source. Page numbers that
+    /// don't parse give `None`.
+    pub fn with_text(&self, text: &str) -> Option<Self> {
+        match self {
+            Source::ManualPageNumber(_) => text.trim().parse().ok().map(Source::ManualPageNumber),
+            Source::ScreenshotFilename(_) => Some(Source::ScreenshotFilename(text.into())),
+            Source::Other(_) => Some(Source::Other(text.into())),
+        }
+    }
+
+    /// Where the source's image is, relative to the sources directory
+    pub fn image_path(&self) -> Option<String> {
+        match self {
+            Source::ManualPageNumber(page_number) => {
+                Some(format!("manual_pages/page{:0>2}.jpg", page_number))
+            }
+            Source::ScreenshotFilename(filename) => Some(format!("screenshots/{}", filename)),
+            Source::Other(_) => None,
+        }
+    }
+}
+
 impl fmt::Display for Source {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -33,11 +75,20 @@ impl Note {
     pub fn as_text(&self) -> String {
         self.0.clone()
     }
+
+    /// Snippet IDs written as `#` followed by the ID, e.g. `see #0000002a`
+    pub fn snippet_references(&self) -> Vec<SnippetId> {
+        self.0
+            .split(|character: char| !character.is_ascii_alphanumeric() && character != '#')
+            .filter(|token| token.starts_with(SNIPPET_REFERENCE_PREFIX))
+            .filter_map(|token| token.parse().ok())
+            .collect()
+    }
 }
 
 impl fmt::Display for Note {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "Note({})", self)
+        write!(f, "Note({})", self.0)
     }
 }
 
@@ -1,9 +1,16 @@
-use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::cmp;
+use std::collections::{HashMap, HashSet};
 use std::fmt;
+use std::hash::Hash;
+use std::str::FromStr;
 
 use crate::prelude::*;
 
+pub const DICTIONARY_VERSION: usize = 5;
+pub const DEFAULT_CONFIDENCE: u8 = 50;
+const MAX_CYCLED_RANK: usize = 3;
+
 #[derive(Clone, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
 pub struct DictionaryWord {
     glyphs: Vec<Glyph>,
@@ -13,6 +20,10 @@ impl DictionaryWord {
     pub fn glyphs(&self) -> Vec<Glyph> {
         self.glyphs.clone()
     }
+
+    pub fn segment_distance(&self, other: &DictionaryWord) -> u32 {
+        glyphs_segment_distance(&self.glyphs, &other.glyphs)
+    }
 }
 
 impl From<TunicWord> for DictionaryWord {
@@ -31,18 +42,41 @@ impl From<&TunicWord> for DictionaryWord {
     }
 }
 
-impl fmt::Display for DictionaryWord {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let word = self
-            .glyphs
+/// Several Tunic words defined together, like an idiom or a compound name
+#[derive(Clone, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
+pub struct DictionaryPhrase {
+    words: Vec<DictionaryWord>,
+}
+
+impl DictionaryPhrase {
+    /// Phrases need at least two words. One word is defined on its own.
+    pub fn new(words: Vec<DictionaryWord>) -> Option<Self> {
+        if words.len() < 2 {
+            None
+        } else {
+            Some(Self { words })
+        }
+    }
+
+    /// The phrase these notebook words would spell, if they are all Tunic words
+    pub fn from_words(words: &[Word]) -> Option<Self> {
+        let words = words
             .iter()
-            .map(|glyph| glyph.0.to_string())
-            .reduce(|word, glyph_value| word + " " + &glyph_value)
-            .map_or("(Empty Tunic Word)".into(), |word| {
-                format!("TunicWord: {}", word)
-            });
+            .map(|word| match &word.word_type {
+                WordType::Tunic(tunic_word) => Some(tunic_word.into()),
+                WordType::English(_) => None,
+            })
+            .collect::<Option<Vec<DictionaryWord>>>()?;
+
+        Self::new(words)
+    }
 
-        write!(f, "{}", word)
+    pub fn words(&self) -> &Vec<DictionaryWord> {
+        &self.words
+    }
+
+    pub fn word_count(&self) -> usize {
+        self.words.len()
     }
 }
 
@@ -65,38 +99,328 @@ impl fmt::Display for Definition {
     }
 }
 
+/// One reading of a word, with how sure we are of it and where it came from.
+/// Confidence is a percentage and `snippets` are the IDs of notebook snippets
+/// that support it.
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
-pub struct Entry {
-    definition: Definition,
-    notes: Vec<Note>,
+pub struct Candidate {
+    text: String,
+    #[serde(default = "default_confidence", deserialize_with = "clamped_confidence")]
+    confidence: u8,
+    #[serde(default)]
+    author: Option<String>,
+    #[serde(default)]
+    date: Option<String>,
+    #[serde(default)]
+    snippets: Vec<SnippetId>,
+    #[serde(default)]
+    confirmed: bool,
+}
+
+fn default_confidence() -> u8 {
+    DEFAULT_CONFIDENCE
 }
 
-impl Default for Entry {
-    fn default() -> Self {
+/// Hand-edited files can say anything up to 255
+fn clamped_confidence<'de, D>(deserializer: D) -> Result<u8, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    u8::deserialize(deserializer).map(|confidence| cmp::min(confidence, 100))
+}
+
+impl Candidate {
+    pub fn new(text: &str) -> Self {
         Self {
-            definition: Definition::Undefined,
-            notes: vec![],
+            text: text.to_owned(),
+            confidence: DEFAULT_CONFIDENCE,
+            author: None,
+            date: None,
+            snippets: vec![],
+            confirmed: false,
         }
     }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn confidence(&self) -> u8 {
+        self.confidence
+    }
+
+    pub fn author(&self) -> Option<&str> {
+        self.author.as_deref()
+    }
+
+    pub fn date(&self) -> Option<&str> {
+        self.date.as_deref()
+    }
+
+    pub fn snippets(&self) -> &Vec<SnippetId> {
+        &self.snippets
+    }
+
+    pub fn is_confirmed(&self) -> bool {
+        self.confirmed
+    }
+
+    pub fn with_text(self, text: &str) -> Self {
+        Self {
+            text: text.to_owned(),
+            ..self
+        }
+    }
+
+    /// Clamped to 100
+    pub fn with_confidence(self, confidence: u8) -> Self {
+        Self {
+            confidence: cmp::min(confidence, 100),
+            ..self
+        }
+    }
+
+    pub fn with_author(self, author: Option<String>) -> Self {
+        Self { author, ..self }
+    }
+
+    pub fn with_date(self, date: Option<String>) -> Self {
+        Self { date, ..self }
+    }
+
+    pub fn with_snippets(self, snippets: Vec<SnippetId>) -> Self {
+        Self { snippets, ..self }
+    }
+
+    /// Entries keep at most one confirmed candidate whatever this says
+    pub fn with_confirmed_as(self, confirmed: bool) -> Self {
+        Self { confirmed, ..self }
+    }
+
+    pub fn to_definition(&self) -> Definition {
+        if self.confirmed {
+            Definition::Confirmed(self.text.clone())
+        } else {
+            Definition::Tentative(self.text.clone())
+        }
+    }
+}
+
+/// Which candidate to show for each word
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub enum Hypothesis {
+    /// The confirmed candidate, or else the most confident one
+    #[default]
+    Preferred,
+    /// Only confirmed candidates
+    Confirmed,
+    /// The candidate at this rank, counting from 1
+    Rank(usize),
+    /// The best candidate from this author
+    Author(String),
+}
+
+impl Hypothesis {
+    /// The order the GUI steps through. Authors have to be asked for by name.
+    pub fn cycled(&self) -> Self {
+        match self {
+            Self::Preferred => Self::Confirmed,
+            Self::Confirmed => Self::Rank(2),
+            Self::Rank(rank) if *rank < MAX_CYCLED_RANK => Self::Rank(rank + 1),
+            _ => Self::Preferred,
+        }
+    }
+}
+
+impl fmt::Display for Hypothesis {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Preferred => write!(f, "preferred"),
+            Self::Confirmed => write!(f, "confirmed"),
+            Self::Rank(rank) => write!(f, "{}", rank),
+            Self::Author(author) => write!(f, "author:{}", author),
+        }
+    }
+}
+
+impl FromStr for Hypothesis {
+    type Err = FennecError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        match text {
+            "preferred" => Ok(Self::Preferred),
+            "confirmed" => Ok(Self::Confirmed),
+            _ => {
+                if let Some(author) = text.strip_prefix("author:") {
+                    Ok(Self::Author(author.to_owned()))
+                } else {
+                    match text.parse::<usize>() {
+                        Ok(rank) if rank > 0 => Ok(Self::Rank(rank)),
+                        _ => Err(FennecError::Validation(format!(
+                            "Unknown hypothesis \"{}\". Expected preferred, confirmed, a rank \
+                            from 1 or author:<name>",
+                            text
+                        ))),
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Candidates are kept ranked: the confirmed one first, then by confidence.
+/// Ties keep the order they were added in.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Entry {
+    #[serde(deserialize_with = "ranked_candidates")]
+    candidates: Vec<Candidate>,
+    notes: Vec<Note>,
+}
+
+/// Hand-edited files can list candidates in any order and confirm several.
+/// They are added one at a time, so the last one confirmed stays confirmed.
+fn ranked_candidates<'de, D>(deserializer: D) -> Result<Vec<Candidate>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let candidates = Vec::<Candidate>::deserialize(deserializer)?;
+    let entry = candidates.into_iter().fold(Entry::default(), Entry::with_candidate);
+
+    Ok(entry.candidates)
 }
 
 impl Entry {
+    /// An entry with the single candidate `definition` describes, if any
     pub fn new(definition: Definition, notes: Vec<Note>) -> Self {
-        Self { definition, notes }
+        Self {
+            candidates: vec![],
+            notes,
+        }
+        .with_definition(definition)
+    }
+
+    pub fn candidates(&self) -> &Vec<Candidate> {
+        &self.candidates
+    }
+
+    pub fn definition(&self) -> Definition {
+        self.definition_as(&Hypothesis::Preferred)
     }
 
-    pub fn definition(&self) -> &Definition {
-        &self.definition
+    /// Entries without a matching candidate are undefined
+    pub fn definition_as(&self, hypothesis: &Hypothesis) -> Definition {
+        self.candidate_for(hypothesis)
+            .map_or(Definition::Undefined, Candidate::to_definition)
+    }
+
+    fn candidate_for(&self, hypothesis: &Hypothesis) -> Option<&Candidate> {
+        match hypothesis {
+            Hypothesis::Preferred => self.candidates.first(),
+            Hypothesis::Confirmed => self.candidates.first().filter(|c| c.confirmed),
+            Hypothesis::Rank(rank) => self.candidates.get(rank.checked_sub(1)?),
+            Hypothesis::Author(author) => self
+                .candidates
+                .iter()
+                .find(|candidate| candidate.author() == Some(author.as_str())),
+        }
     }
 
     pub fn notes(&self) -> &Vec<Note> {
         &self.notes
     }
+
+    /// Replaces every candidate with the one `definition` describes
+    pub fn with_definition(self, definition: Definition) -> Self {
+        let candidates = match definition {
+            Definition::Undefined => vec![],
+            Definition::Tentative(text) => vec![Candidate::new(&text)],
+            Definition::Confirmed(text) => vec![Candidate::new(&text)
+                .with_confidence(100)
+                .with_confirmed_as(true)],
+        };
+
+        Self { candidates, ..self }
+    }
+
+    pub fn with_note(self, note: Note) -> Self {
+        let mut notes = self.notes;
+        notes.push(note);
+
+        Self { notes, ..self }
+    }
+
+    fn with_candidates_ranked(self, mut candidates: Vec<Candidate>) -> Self {
+        candidates.sort_by(|a, b| {
+            b.confirmed
+                .cmp(&a.confirmed)
+                .then(b.confidence.cmp(&a.confidence))
+        });
+
+        Self { candidates, ..self }
+    }
+
+    /// Only one candidate can be confirmed, so adding a confirmed one demotes the rest
+    pub fn with_candidate(self, candidate: Candidate) -> Self {
+        let mut candidates = self.candidates.clone();
+
+        if candidate.confirmed {
+            candidates = candidates
+                .into_iter()
+                .map(|candidate| candidate.with_confirmed_as(false))
+                .collect();
+        }
+
+        candidates.push(candidate);
+
+        self.with_candidates_ranked(candidates)
+    }
+
+    /// Replaces the candidate at `index`, which may move it in the ranking
+    pub fn with_candidate_at(self, index: usize, candidate: Candidate) -> Self {
+        if index >= self.candidates.len() {
+            return self;
+        }
+
+        let mut candidates = self.candidates.clone();
+        candidates.remove(index);
+
+        Self { candidates, ..self }.with_candidate(candidate)
+    }
+
+    pub fn without_candidate_at(self, index: usize) -> Self {
+        let mut candidates = self.candidates.clone();
+
+        if index < candidates.len() {
+            candidates.remove(index);
+        }
+
+        Self { candidates, ..self }
+    }
+
+    /// Confirms the candidate at `index`, or takes it back if it already is
+    pub fn with_confirmation_toggled_at(self, index: usize) -> Self {
+        match self.candidates.get(index).cloned() {
+            Some(candidate) => {
+                let confirmed = !candidate.confirmed;
+
+                self.with_candidate_at(index, candidate.with_confirmed_as(confirmed))
+            }
+            None => self,
+        }
+    }
+
+    /// Keeps only the candidate `hypothesis` picks, so `definition` shows it
+    pub fn as_hypothesis(&self, hypothesis: &Hypothesis) -> Self {
+        Self {
+            candidates: self.candidate_for(hypothesis).cloned().into_iter().collect(),
+            notes: self.notes.clone(),
+        }
+    }
 }
 
 impl fmt::Display for Entry {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let definition = self.definition.clone();
+        let definition = self.definition();
         let notes: String = self
             .notes
             .iter()
@@ -110,13 +434,17 @@ impl fmt::Display for Entry {
 
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct Dictionary {
+    version: usize,
     entries: HashMap<DictionaryWord, Entry>,
+    phrases: HashMap<DictionaryPhrase, Entry>,
 }
 
 impl Dictionary {
     pub fn new() -> Self {
         Self {
+            version: DICTIONARY_VERSION,
             entries: HashMap::new(),
+            phrases: HashMap::new(),
         }
     }
 
@@ -138,19 +466,41 @@ impl Dictionary {
     }
 
     pub fn with_annotation(self, tunic_word: &TunicWord, note: Note) -> Self {
-        let word: DictionaryWord = tunic_word.into();
-        let entry = if let Some(entry) = self.entries.get(&word) {
-            let mut entry = entry.clone();
-            entry.notes.push(note);
-            entry
-        } else {
-            Entry::default()
-        };
+        self.with_note_for(&tunic_word.into(), note)
+    }
 
-        let mut entries = self.entries.clone();
-        entries.insert(word.clone(), entry);
+    /// Sets the definition of `word`, keeping any notes it already has
+    pub fn with_definition_for(self, word: &DictionaryWord, definition: Definition) -> Self {
+        let entry = self
+            .entries
+            .get(word)
+            .cloned()
+            .unwrap_or_default()
+            .with_definition(definition);
 
-        Self { entries, ..self }
+        self.with_new_complete_definition(word, &entry)
+    }
+
+    pub fn with_candidate_for(self, word: &DictionaryWord, candidate: Candidate) -> Self {
+        let entry = self
+            .entries
+            .get(word)
+            .cloned()
+            .unwrap_or_default()
+            .with_candidate(candidate);
+
+        self.with_new_complete_definition(word, &entry)
+    }
+
+    pub fn with_note_for(self, word: &DictionaryWord, note: Note) -> Self {
+        let entry = self
+            .entries
+            .get(word)
+            .cloned()
+            .unwrap_or_default()
+            .with_note(note);
+
+        self.with_new_complete_definition(word, &entry)
     }
 
     pub fn get(&self, word: &DictionaryWord) -> Option<&Entry> {
@@ -160,4 +510,213 @@ impl Dictionary {
     pub fn entries(&self) -> &HashMap<DictionaryWord, Entry> {
         &self.entries
     }
+
+    pub fn with_phrase_entry(self, phrase: &DictionaryPhrase, entry: &Entry) -> Self {
+        let mut phrases = self.phrases.clone();
+        phrases.insert(phrase.clone(), entry.clone());
+
+        Self { phrases, ..self }
+    }
+
+    pub fn get_phrase(&self, phrase: &DictionaryPhrase) -> Option<&Entry> {
+        self.phrases.get(phrase)
+    }
+
+    pub fn phrases(&self) -> &HashMap<DictionaryPhrase, Entry> {
+        &self.phrases
+    }
+
+    /// The most words any phrase spans, or 0 without phrases
+    pub fn longest_phrase(&self) -> usize {
+        self.phrases.keys().map(DictionaryPhrase::word_count).max().unwrap_or(0)
+    }
+
+    /// A copy to display where every word shows the candidate `hypothesis` picks
+    pub fn as_hypothesis(&self, hypothesis: &Hypothesis) -> Self {
+        let entries = self
+            .entries
+            .iter()
+            .map(|(word, entry)| (word.clone(), entry.as_hypothesis(hypothesis)))
+            .collect();
+        let phrases = self
+            .phrases
+            .iter()
+            .map(|(phrase, entry)| (phrase.clone(), entry.as_hypothesis(hypothesis)))
+            .collect();
+
+        Self {
+            version: self.version,
+            entries,
+            phrases,
+        }
+    }
+
+    /// Combines the edits made here since `base` with `theirs`, another edit of
+    /// `base`. Entries changed differently on both sides are conflicts, listed by
+    /// word or phrase.
+    pub fn merged_with(&self, base: &Dictionary, theirs: &Dictionary) -> Result<Self, Vec<String>> {
+        let (entries, mut conflicts) =
+            merged_entries(&self.entries, &base.entries, &theirs.entries);
+        let (phrases, phrase_conflicts) =
+            merged_entries(&self.phrases, &base.phrases, &theirs.phrases);
+
+        conflicts.extend(phrase_conflicts);
+
+        if conflicts.is_empty() {
+            Ok(Self {
+                version: theirs.version,
+                entries,
+                phrases,
+            })
+        } else {
+            conflicts.sort();
+            Err(conflicts)
+        }
+    }
+}
+
+fn merged_entries<K>(
+    ours: &HashMap<K, Entry>,
+    base: &HashMap<K, Entry>,
+    theirs: &HashMap<K, Entry>,
+) -> (HashMap<K, Entry>, Vec<String>)
+where
+    K: Clone + Eq + Hash + fmt::Display,
+{
+    let keys: HashSet<&K> = ours.keys().chain(base.keys()).chain(theirs.keys()).collect();
+
+    let mut merged: HashMap<K, Entry> = HashMap::new();
+    let mut conflicts: Vec<String> = vec![];
+
+    for key in keys {
+        let (our_entry, base_entry, their_entry) = (ours.get(key), base.get(key), theirs.get(key));
+
+        let entry = if our_entry == base_entry || our_entry == their_entry {
+            their_entry
+        } else if their_entry == base_entry {
+            our_entry
+        } else {
+            conflicts.push(key.to_string());
+            continue;
+        };
+
+        if let Some(entry) = entry {
+            merged.insert(key.clone(), entry.clone());
+        }
+    }
+
+    (merged, conflicts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry() -> Entry {
+        Entry::default()
+            .with_candidate(Candidate::new("fox").with_confidence(40))
+            .with_candidate(Candidate::new("dog").with_confidence(70))
+            .with_candidate(
+                Candidate::new("cat")
+                    .with_confidence(20)
+                    .with_author(Some("ruin seeker".into())),
+            )
+    }
+
+    fn texts(entry: &Entry) -> Vec<&str> {
+        entry.candidates().iter().map(Candidate::text).collect()
+    }
+
+    #[test]
+    fn candidates_are_ranked_with_the_confirmed_one_first() {
+        let entry = entry();
+        assert_eq!(texts(&entry), vec!["dog", "fox", "cat"]);
+
+        let entry = entry.with_confirmation_toggled_at(2);
+        assert_eq!(texts(&entry), vec!["cat", "dog", "fox"]);
+        assert_eq!(entry.definition(), Definition::Confirmed("cat".into()));
+
+        let entry = entry.with_candidate(Candidate::new("fish").with_confirmed_as(true));
+        assert_eq!(texts(&entry), vec!["fish", "dog", "fox", "cat"]);
+        assert_eq!(entry.candidates().iter().filter(|c| c.is_confirmed()).count(), 1);
+    }
+
+    #[test]
+    fn hypotheses_pick_a_candidate() {
+        let entry = entry();
+
+        assert_eq!(
+            entry.definition_as(&Hypothesis::Preferred),
+            Definition::Tentative("dog".into())
+        );
+        assert_eq!(entry.definition_as(&Hypothesis::Confirmed), Definition::Undefined);
+        assert_eq!(entry.definition_as(&Hypothesis::Rank(2)), Definition::Tentative("fox".into()));
+        assert_eq!(entry.definition_as(&Hypothesis::Rank(4)), Definition::Undefined);
+        assert_eq!(
+            entry.definition_as(&"author:ruin seeker".parse().unwrap()),
+            Definition::Tentative("cat".into())
+        );
+        assert_eq!(
+            entry.as_hypothesis(&Hypothesis::Rank(3)).definition(),
+            Definition::Tentative("cat".into())
+        );
+    }
+
+    #[test]
+    fn merges_keep_edits_from_both_sides_unless_they_conflict() {
+        let word = |text: &str| -> DictionaryWord { text.parse().unwrap() };
+        let base = Dictionary::new()
+            .with_definition_for(&word("1,"), Definition::Tentative("one".into()))
+            .with_definition_for(&word("2,"), Definition::Tentative("two".into()));
+
+        let ours = base
+            .clone()
+            .with_definition_for(&word("1,"), Definition::Confirmed("one".into()));
+        let theirs = base
+            .clone()
+            .with_definition_for(&word("3,"), Definition::Tentative("three".into()));
+
+        let merged = ours.merged_with(&base, &theirs).unwrap();
+
+        let definition = |text: &str| merged.get(&word(text)).unwrap().definition();
+
+        assert_eq!(definition("1,"), Definition::Confirmed("one".into()));
+        assert_eq!(definition("3,"), Definition::Tentative("three".into()));
+
+        let theirs = theirs.with_definition_for(&word("1,"), Definition::Tentative("won".into()));
+
+        assert_eq!(ours.merged_with(&base, &theirs), Err(vec!["1,".to_owned()]));
+    }
+
+    #[test]
+    fn hypotheses_parse_from_text() {
+        for text in ["preferred", "confirmed", "2", "author:someone"] {
+            assert_eq!(text.parse::<Hypothesis>().unwrap().to_string(), text);
+        }
+
+        assert!("0".parse::<Hypothesis>().is_err());
+        assert!("best".parse::<Hypothesis>().is_err());
+    }
+
+    #[test]
+    fn candidates_are_normalized_when_loaded() {
+        let candidate: Candidate = serde_yaml::from_str("text: fox\nconfidence: 250").unwrap();
+
+        assert_eq!(candidate.confidence(), 100);
+
+        let yaml = "
+candidates:
+- { text: hound, confidence: 20 }
+- { text: fox, confidence: 250, confirmed: true }
+- { text: wolf, confidence: 90 }
+- { text: dog, confidence: 10, confirmed: true }
+notes: []
+";
+        let entry: Entry = serde_yaml::from_str(yaml).unwrap();
+        let texts: Vec<&str> = entry.candidates().iter().map(Candidate::text).collect();
+
+        assert_eq!(texts, vec!["dog", "fox", "wolf", "hound"]);
+        assert_eq!(entry.definition(), Definition::Confirmed("dog".into()));
+        assert_eq!(entry.candidates().iter().filter(|c| c.is_confirmed()).count(), 1);
+    }
 }
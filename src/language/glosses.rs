@@ -0,0 +1,121 @@
+use std::ops::Range;
+
+use crate::prelude::*;
+
+/// The dictionary entry covering a run of words in a snippet. Runs longer than
+/// one word are phrases.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Gloss {
+    pub words: Range<usize>,
+    pub entry: Option<Entry>,
+}
+
+impl Gloss {
+    pub fn is_phrase(&self) -> bool {
+        self.words.len() > 1
+    }
+
+    pub fn definition(&self) -> Definition {
+        self.entry
+            .as_ref()
+            .map_or(Definition::Undefined, Entry::definition)
+    }
+}
+
+/// Covers `words` from left to right, taking the longest defined phrase that
+/// starts at each word before falling back to the word on its own. English
+/// words never have an entry.
+pub fn glosses_for(words: &[Word], dictionary: &Dictionary) -> Vec<Gloss> {
+    let longest_phrase = dictionary.longest_phrase();
+    let mut glosses: Vec<Gloss> = vec![];
+    let mut start = 0;
+
+    while start < words.len() {
+        let longest_here = longest_phrase.min(words.len() - start);
+
+        let phrase = (2..=longest_here).rev().find_map(|length| {
+            let phrase = DictionaryPhrase::from_words(&words[start..start + length])?;
+
+            dictionary
+                .get_phrase(&phrase)
+                .filter(|entry| entry.definition() != Definition::Undefined)
+                .map(|entry| Gloss {
+                    words: start..start + length,
+                    entry: Some(entry.clone()),
+                })
+        });
+
+        let gloss = phrase.unwrap_or_else(|| Gloss {
+            words: start..start + 1,
+            entry: match &words[start].word_type {
+                WordType::Tunic(tunic_word) => dictionary.get(&tunic_word.into()).cloned(),
+                WordType::English(_) => None,
+            },
+        });
+
+        start = gloss.words.end;
+        glosses.push(gloss);
+    }
+
+    glosses
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word(text: &str) -> Word {
+        text.parse::<TunicWord>().unwrap().into()
+    }
+
+    fn dictionary() -> Dictionary {
+        let phrase: DictionaryPhrase = "1, / 2,".parse().unwrap();
+        let long_phrase: DictionaryPhrase = "1, / 2, / 3,".parse().unwrap();
+
+        Dictionary::new()
+            .with_new_definition(&"1,".parse().unwrap(), "holy".into())
+            .with_new_definition(&"2,".parse().unwrap(), "cross".into())
+            .with_phrase_entry(&phrase, &Entry::new(Definition::Tentative("fox".into()), vec![]))
+            .with_phrase_entry(&long_phrase, &Entry::default())
+    }
+
+    fn definitions(glosses: &[Gloss]) -> Vec<(Range<usize>, Definition)> {
+        glosses
+            .iter()
+            .map(|gloss| (gloss.words.clone(), gloss.definition()))
+            .collect()
+    }
+
+    #[test]
+    fn phrases_take_priority_over_words() {
+        let words = vec![word("2,"), word("1,"), word("2,"), word("3,")];
+
+        assert_eq!(
+            definitions(&glosses_for(&words, &dictionary())),
+            vec![
+                (0..1, Definition::Tentative("cross".into())),
+                (1..3, Definition::Tentative("fox".into())),
+                (3..4, Definition::Undefined),
+            ]
+        );
+    }
+
+    #[test]
+    fn english_words_break_phrases() {
+        let words = vec![word("1,"), "and".to_string().into(), word("2,")];
+
+        let glosses = glosses_for(&words, &dictionary());
+
+        assert_eq!(glosses.len(), 3);
+        assert!(!glosses.iter().any(Gloss::is_phrase));
+    }
+
+    #[test]
+    fn phrases_parse_and_print_with_slashes() {
+        let phrase: DictionaryPhrase = "fɑ / 1,2".parse().unwrap();
+
+        assert_eq!(phrase.word_count(), 2);
+        assert_eq!(phrase.to_string().parse::<DictionaryPhrase>().unwrap(), phrase);
+        assert!("1,2".parse::<DictionaryPhrase>().is_err());
+    }
+}
@@ -0,0 +1,99 @@
+use crate::prelude::*;
+
+/// A point as fractions of the glyph's width and height, from its top left
+pub type GlyphPoint = (f32, f32);
+
+/// Segment endpoints, from the egui experiment. The middle and far-left lines
+/// are split where the word line crosses them, so each half can be toggled on
+/// its own.
+pub const SEGMENT_LINES: [(Segment, GlyphPoint, GlyphPoint); 15] = [
+    (0, (0.0, 0.5), (1.0, 0.5)),
+    (1, (0.5, 0.0), (0.0, 0.25)),
+    (2, (0.5, 0.0), (0.5, 0.25)),
+    (3, (0.5, 0.0), (1.0, 0.25)),
+    (4, (0.0, 0.25), (0.0, 0.5)),
+    (5, (0.5, 0.5), (0.0, 0.25)),
+    (6, (0.5, 0.25), (0.5, 0.5)),
+    (7, (0.5, 0.5), (1.0, 0.25)),
+    (8, (0.0, 0.5), (0.0, 0.75)),
+    (9, (0.5, 0.5), (0.0, 0.75)),
+    (10, (0.5, 0.5), (0.5, 0.75)),
+    (11, (0.5, 0.5), (1.0, 0.75)),
+    (12, (0.5, 1.0), (0.0, 0.75)),
+    (13, (0.5, 0.75), (0.5, 1.0)),
+    (14, (0.5, 1.0), (1.0, 0.75)),
+];
+
+/// The one segment drawn as a circle instead of a line
+pub const REVERSAL_SEGMENT: Segment = 15;
+
+/// The reversal circle's radius as a fraction of the glyph's width
+pub const REVERSAL_RADIUS: f32 = 0.2;
+
+/// Center and radius of the reversal circle on a glyph this size, from the
+/// glyph's top left corner. The circle hangs just below the glyph.
+pub fn reversal_circle(width: f32, height: f32) -> ((f32, f32), f32) {
+    let radius = width * REVERSAL_RADIUS;
+
+    ((width * 0.5, height + radius), radius)
+}
+
+/// The segment nearest a point on a glyph this size, if any is within
+/// `tolerance`. The point and tolerance are in the same units as the size,
+/// measured from the glyph's top left corner.
+pub fn segment_at(point: (f32, f32), width: f32, height: f32, tolerance: f32) -> Option<Segment> {
+    let lines = SEGMENT_LINES.iter().map(|(segment, start, end)| {
+        let start = (start.0 * width, start.1 * height);
+        let end = (end.0 * width, end.1 * height);
+
+        (*segment, distance_to_line(point, start, end))
+    });
+
+    let (center, radius) = reversal_circle(width, height);
+    let circle = (REVERSAL_SEGMENT, (distance(point, center) - radius).max(0.0));
+
+    lines
+        .chain(std::iter::once(circle))
+        .filter(|(_, distance)| *distance <= tolerance)
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(segment, _)| segment)
+}
+
+fn distance(a: (f32, f32), b: (f32, f32)) -> f32 {
+    (a.0 - b.0).hypot(a.1 - b.1)
+}
+
+fn distance_to_line(point: (f32, f32), start: (f32, f32), end: (f32, f32)) -> f32 {
+    let (dx, dy) = (end.0 - start.0, end.1 - start.1);
+    let length_squared = dx * dx + dy * dy;
+
+    if length_squared <= 0.0 {
+        return distance(point, start);
+    }
+
+    let along = ((point.0 - start.0) * dx + (point.1 - start.1) * dy) / length_squared;
+    let along = along.clamp(0.0, 1.0);
+
+    distance(point, (start.0 + along * dx, start.1 + along * dy))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn segments_are_hit_along_their_lines() {
+        let (width, height) = (16.0, 26.0);
+
+        for (segment, start, end) in SEGMENT_LINES {
+            let middle = ((start.0 + end.0) / 2.0 * width, (start.1 + end.1) / 2.0 * height);
+
+            assert_eq!(segment_at(middle, width, height, 1.0), Some(segment));
+        }
+
+        let ((x, y), radius) = reversal_circle(width, height);
+
+        assert_eq!(segment_at((x + radius, y), width, height, 1.0), Some(REVERSAL_SEGMENT));
+        assert_eq!(segment_at((3.0, 11.5), width, height, 1.0), None);
+    }
+}
@@ -1,9 +1,9 @@
-use serde::{Deserialize, Serialize};
-
 use std::convert::From;
 use std::error::Error;
 use std::fmt;
 
+use crate::language::Segment;
+
 #[derive(Clone, Debug)]
 pub struct GlyphError {
     description: String,
@@ -27,14 +27,20 @@ impl Error for GlyphError {
     }
 }
 
-#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq, Serialize, Deserialize)]
-pub struct Glyph(pub u16);
+/// The connecting line drawn through every glyph in a word
+pub const WORD_LINE_MASK: u16 = 0b1000_0000_0000_0000;
 
-impl fmt::Display for Glyph {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "Glyph({})", self.0)
-    }
-}
+/// The outer lines of a glyph, read as its vowel: segments 1, 3, 4, 8, 12 and 14
+pub const VOWEL_MASK: u16 = 0b0101_1000_1000_1010;
+
+/// The inner lines of a glyph, read as its consonant: segments 2, 5, 6, 7, 9, 10, 11 and 13
+pub const CONSONANT_MASK: u16 = 0b0010_0111_0111_0100;
+
+/// The circle beneath a glyph that flips its reading to vowel-then-consonant
+pub const REVERSAL_MASK: u16 = 0b0000_0000_0000_0001;
+
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Glyph(pub u16);
 
 impl From<u16> for Glyph {
     fn from(item: u16) -> Self {
@@ -160,4 +166,54 @@ impl Glyph {
     pub fn is_blank(&self) -> bool {
         self.0 == 0
     }
+
+    pub fn vowel_segments(&self) -> Self {
+        Self(self.0 & VOWEL_MASK)
+    }
+
+    pub fn consonant_segments(&self) -> Self {
+        Self(self.0 & CONSONANT_MASK)
+    }
+
+    /// The number of the 16 segments that are set in one glyph but not the other
+    pub fn segment_distance(&self, other: &Glyph) -> u32 {
+        (self.0 ^ other.0).count_ones()
+    }
+
+    pub fn is_reversed(&self) -> bool {
+        (self.0 & REVERSAL_MASK) > 0
+    }
+
+    pub fn from_segments(segments: &[Segment]) -> Result<Self, GlyphError> {
+        let mut code: u16 = 0;
+
+        for segment in segments.iter() {
+            code |= Self::mask_from_usize(*segment)?;
+        }
+
+        Ok(Self(code))
+    }
+
+    pub fn segments(&self) -> Vec<Segment> {
+        (0..16)
+            .filter(|index| self.includes_segment(*index).unwrap_or(false))
+            .map(|index| index as Segment)
+            .collect()
+    }
+}
+
+/// Compares glyphs position by position. A glyph missing from the shorter word
+/// costs every segment of the glyph it lines up with.
+pub fn glyphs_segment_distance(first: &[Glyph], second: &[Glyph]) -> u32 {
+    let length = first.len().max(second.len());
+    let blank = Glyph::default();
+
+    (0..length)
+        .map(|index| {
+            let first = first.get(index).unwrap_or(&blank);
+            let second = second.get(index).unwrap_or(&blank);
+
+            first.segment_distance(second)
+        })
+        .sum()
 }
@@ -0,0 +1,460 @@
+use serde::de::{self, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::prelude::*;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct NotationError {
+    description: String,
+}
+
+impl NotationError {
+    pub fn new(description: String) -> Self {
+        Self { description }
+    }
+}
+
+impl fmt::Display for NotationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "NotationError: {}", self.description)
+    }
+}
+
+impl Error for NotationError {
+    fn description(&self) -> &str {
+        &self.description
+    }
+}
+
+impl From<GlyphError> for NotationError {
+    fn from(error: GlyphError) -> Self {
+        Self::new(error.to_string())
+    }
+}
+
+const BLANK_GLYPH_NOTATION: &str = "_";
+const HEX_PREFIX: &str = "0x";
+const REVERSAL_NOTATION: char = '+';
+const COLORED_NOTATION: char = '*';
+const BORDER_START_NOTATION: char = '[';
+const BORDER_END_NOTATION: char = ']';
+const PHRASE_WORD_SEPARATOR: char = '/';
+
+fn is_segment_list(text: &str) -> bool {
+    text.chars().all(|c| c.is_ascii_digit() || c == ',' || c == REVERSAL_NOTATION)
+        && text.chars().any(|c| c == ',' || c == REVERSAL_NOTATION)
+}
+
+fn parse_segment_list(text: &str) -> Result<Glyph, NotationError> {
+    let segments = text
+        .split([',', REVERSAL_NOTATION])
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            part.parse::<Segment>()
+                .map_err(|_| NotationError::new(format!("Invalid segment: {}", part)))
+        })
+        .collect::<Result<Vec<Segment>, NotationError>>()?;
+
+    Ok(Glyph::from_segments(&segments)?)
+}
+
+fn parse_hex(text: &str) -> Result<Glyph, NotationError> {
+    u16::from_str_radix(&text[HEX_PREFIX.len()..], 16)
+        .map(Glyph)
+        .map_err(|_| NotationError::new(format!("Invalid hex glyph: {}", text)))
+}
+
+/// Splits `text` into a leading phoneme from `first` and a trailing phoneme from `second`,
+/// either of which may be absent.
+fn split_phonemes<'a>(
+    text: &str,
+    first: &'a [PhonemeEntry],
+    second: &'a [PhonemeEntry],
+) -> Option<(Option<&'a PhonemeEntry>, Option<&'a PhonemeEntry>)> {
+    fn find<'a>(entries: &'a [PhonemeEntry], part: &str) -> Option<Option<&'a PhonemeEntry>> {
+        if part.is_empty() {
+            Some(None)
+        } else {
+            entries.iter().find(|entry| entry.phoneme == part).map(Some)
+        }
+    }
+
+    text.char_indices()
+        .map(|(index, _)| index)
+        .chain(std::iter::once(text.len()))
+        .find_map(|index| {
+            let (head, tail) = text.split_at(index);
+
+            match (find(first, head), find(second, tail)) {
+                (Some(head), Some(tail)) => Some((head, tail)),
+                _ => None,
+            }
+        })
+}
+
+fn parse_phonemes(text: &str, table: &PhonemeTable) -> Result<Glyph, NotationError> {
+    let (spelling, reversed) = match text.strip_suffix(REVERSAL_NOTATION) {
+        Some(spelling) => (spelling, true),
+        None => (text, false),
+    };
+
+    let split = if reversed {
+        split_phonemes(spelling, table.vowels(), table.consonants())
+            .map(|(vowel, consonant)| (consonant, vowel))
+    } else {
+        split_phonemes(spelling, table.consonants(), table.vowels())
+    };
+
+    let (consonant, vowel) = split
+        .ok_or_else(|| NotationError::new(format!("Unrecognized phonemes: {}", text)))?;
+
+    let mut segments: Vec<Segment> = vec![];
+
+    for entry in [consonant, vowel].iter().flatten() {
+        segments.extend(entry.segments.iter());
+    }
+
+    if reversed {
+        segments.push(15);
+    }
+
+    Ok(Glyph::from_segments(&segments)?)
+}
+
+impl Glyph {
+    /// Parses a single glyph written as hex (`0x1100`), a segment list (`1,3,9+15`),
+    /// phonemes (`fɑ`, or `ɑk+` when reversed), a decimal value (`4352`) or `_` for a blank glyph.
+    pub fn parse_with(text: &str, table: &PhonemeTable) -> Result<Self, NotationError> {
+        let text = text.trim();
+
+        if text.is_empty() {
+            Err(NotationError::new("Empty glyph".into()))
+        } else if text == BLANK_GLYPH_NOTATION {
+            Ok(Glyph(0))
+        } else if text.to_lowercase().starts_with(HEX_PREFIX) {
+            parse_hex(text)
+        } else if text.chars().all(|c| c.is_ascii_digit()) {
+            text.parse::<u16>()
+                .map(Glyph)
+                .map_err(|_| NotationError::new(format!("Invalid glyph value: {}", text)))
+        } else if is_segment_list(text) {
+            parse_segment_list(text)
+        } else {
+            parse_phonemes(text, table)
+        }
+    }
+
+    pub fn to_segment_notation(&self) -> String {
+        let segments: Vec<String> = self
+            .segments()
+            .iter()
+            .filter(|segment| **segment != 15)
+            .map(|segment| segment.to_string())
+            .collect();
+
+        let reversal = if self.is_reversed() { "+15" } else { "" };
+
+        match segments.len() {
+            0 if !self.is_reversed() => BLANK_GLYPH_NOTATION.to_owned(),
+            // A lone segment needs a separator so it isn't read back as a decimal value
+            1 if !self.is_reversed() => format!("{},", segments[0]),
+            _ => format!("{}{}", segments.join(","), reversal),
+        }
+    }
+
+    pub fn to_hex_notation(&self) -> String {
+        format!("{}{:04X}", HEX_PREFIX, self.0)
+    }
+
+    /// Falls back to the segment list for any glyph the table can't spell unambiguously
+    pub fn to_phoneme_notation(&self, table: &PhonemeTable) -> String {
+        let reading = table.read_glyph(self);
+
+        let spelling = if reading.reversed {
+            format!("{}{}", reading, REVERSAL_NOTATION)
+        } else {
+            reading.to_string()
+        };
+
+        match Self::parse_with(&spelling, table) {
+            Ok(glyph) if glyph == *self => spelling,
+            _ => self.to_segment_notation(),
+        }
+    }
+}
+
+impl FromStr for Glyph {
+    type Err = NotationError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        Self::parse_with(text, &PhonemeTable::default())
+    }
+}
+
+impl fmt::Display for Glyph {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_segment_notation())
+    }
+}
+
+/// Glyphs are stored as plain numbers, so files stay readable by older builds.
+/// Notation is accepted when reading, for files written by hand.
+impl Serialize for Glyph {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_u16(self.0)
+    }
+}
+
+struct GlyphVisitor;
+
+impl<'de> Visitor<'de> for GlyphVisitor {
+    type Value = Glyph;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a glyph value or glyph notation")
+    }
+
+    fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        u16::try_from(value)
+            .map(Glyph)
+            .map_err(|_| E::custom(format!("glyph value out of range: {}", value)))
+    }
+
+    fn visit_i64<E>(self, value: i64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        u16::try_from(value)
+            .map(Glyph)
+            .map_err(|_| E::custom(format!("glyph value out of range: {}", value)))
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        value.parse::<Glyph>().map_err(E::custom)
+    }
+}
+
+impl<'de> Deserialize<'de> for Glyph {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(GlyphVisitor)
+    }
+}
+
+fn parse_glyphs_with(text: &str, table: &PhonemeTable) -> Result<Vec<Glyph>, NotationError> {
+    text.split_whitespace()
+        .map(|token| Glyph::parse_with(token, table))
+        .collect()
+}
+
+fn format_glyphs<F>(glyphs: &[Glyph], formatter: F) -> String
+where
+    F: Fn(&Glyph) -> String,
+{
+    glyphs.iter().map(formatter).collect::<Vec<String>>().join(" ")
+}
+
+impl TunicWord {
+    /// Parses whitespace-separated glyphs. A leading `*` marks the word as colored and
+    /// wrapping it in `[` and `]` gives it a border, e.g. `*[fɑ k s]`.
+    pub fn parse_with(text: &str, table: &PhonemeTable) -> Result<Self, NotationError> {
+        let text = text.trim();
+
+        let (text, colored) = match text.strip_prefix(COLORED_NOTATION) {
+            Some(text) => (text.trim(), true),
+            None => (text, false),
+        };
+
+        let (text, has_border) = match text
+            .strip_prefix(BORDER_START_NOTATION)
+            .and_then(|text| text.strip_suffix(BORDER_END_NOTATION))
+        {
+            Some(text) => (text, true),
+            None => (text, false),
+        };
+
+        let glyphs = parse_glyphs_with(text, table)?;
+
+        Ok(TunicWord::new(glyphs)
+            .with_colored_as(colored)
+            .with_border_as(has_border))
+    }
+
+    fn with_markers(&self, glyphs: String) -> String {
+        let glyphs = if self.has_border() {
+            format!("{}{}{}", BORDER_START_NOTATION, glyphs, BORDER_END_NOTATION)
+        } else {
+            glyphs
+        };
+
+        if self.colored() {
+            format!("{}{}", COLORED_NOTATION, glyphs)
+        } else {
+            glyphs
+        }
+    }
+
+    pub fn to_hex_notation(&self) -> String {
+        self.with_markers(format_glyphs(&self.glyphs(), |glyph| glyph.to_hex_notation()))
+    }
+
+    pub fn to_phoneme_notation(&self, table: &PhonemeTable) -> String {
+        self.with_markers(format_glyphs(&self.glyphs(), |glyph| {
+            glyph.to_phoneme_notation(table)
+        }))
+    }
+}
+
+impl FromStr for TunicWord {
+    type Err = NotationError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        Self::parse_with(text, &PhonemeTable::default())
+    }
+}
+
+impl fmt::Display for TunicWord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let glyphs = format_glyphs(&self.glyphs(), |glyph| glyph.to_string());
+
+        write!(f, "{}", self.with_markers(glyphs))
+    }
+}
+
+impl FromStr for DictionaryWord {
+    type Err = NotationError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let glyphs = parse_glyphs_with(text, &PhonemeTable::default())?;
+
+        Ok(TunicWord::new(glyphs).into())
+    }
+}
+
+/// Words separated by `/`, e.g. `fɑ k s / 1,2`
+impl FromStr for DictionaryPhrase {
+    type Err = NotationError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let words = text
+            .split(PHRASE_WORD_SEPARATOR)
+            .map(str::parse)
+            .collect::<Result<Vec<DictionaryWord>, NotationError>>()?;
+
+        DictionaryPhrase::new(words)
+            .ok_or_else(|| NotationError::new("A phrase needs at least two words".into()))
+    }
+}
+
+impl fmt::Display for DictionaryPhrase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let words: Vec<String> = self.words().iter().map(DictionaryWord::to_string).collect();
+
+        write!(f, "{}", words.join(&format!(" {} ", PHRASE_WORD_SEPARATOR)))
+    }
+}
+
+impl fmt::Display for DictionaryWord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", format_glyphs(&self.glyphs(), |glyph| glyph.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(glyph: Glyph) {
+        let table = PhonemeTable::default();
+
+        assert_eq!(glyph.to_string().parse::<Glyph>().unwrap(), glyph);
+        assert_eq!(glyph.to_hex_notation().parse::<Glyph>().unwrap(), glyph);
+        assert_eq!(
+            Glyph::parse_with(&glyph.to_phoneme_notation(&table), &table).unwrap(),
+            glyph
+        );
+    }
+
+    #[test]
+    fn every_glyph_round_trips_through_each_notation() {
+        for code in 0..=u16::MAX {
+            round_trip(Glyph(code));
+        }
+    }
+
+    #[test]
+    fn parses_each_glyph_notation() {
+        let expected = Glyph::from_segments(&[1, 3, 9, 15]).unwrap();
+
+        assert_eq!("1,3,9+15".parse::<Glyph>().unwrap(), expected);
+        assert_eq!("0x5041".parse::<Glyph>().unwrap(), expected);
+        assert_eq!("20545".parse::<Glyph>().unwrap(), expected);
+        assert_eq!("_".parse::<Glyph>().unwrap(), Glyph(0));
+    }
+
+    #[test]
+    fn parses_phoneme_spelling() {
+        let f_ah = Glyph::from_segments(&[5, 7, 11, 1, 4, 8, 12, 14]).unwrap();
+        let ah_k = Glyph::from_segments(&[1, 4, 8, 12, 14, 2, 6, 7, 9, 15]).unwrap();
+
+        assert_eq!("fɑ".parse::<Glyph>().unwrap(), f_ah);
+        assert_eq!("ɑk+".parse::<Glyph>().unwrap(), ah_k);
+        assert!("fq".parse::<Glyph>().is_err());
+    }
+
+    #[test]
+    fn lone_segments_are_not_mistaken_for_decimal_values() {
+        let glyph = Glyph::from_segments(&[9]).unwrap();
+
+        assert_eq!(glyph.to_string(), "9,");
+        assert_eq!("9,".parse::<Glyph>().unwrap(), glyph);
+        assert_eq!("9".parse::<Glyph>().unwrap(), Glyph(9));
+    }
+
+    #[test]
+    fn tunic_words_round_trip_with_their_markers() {
+        let word: TunicWord = "*[fɑ k s]".parse().unwrap();
+
+        assert!(word.colored());
+        assert!(word.has_border());
+        assert_eq!(word.glyphs().len(), 3);
+        assert_eq!(word.to_string().parse::<TunicWord>().unwrap(), word);
+        assert_eq!(word.to_phoneme_notation(&PhonemeTable::default()), "*[fɑ k s]");
+    }
+
+    #[test]
+    fn legacy_decimal_words_still_parse() {
+        let word: TunicWord = "4352 2177 33".parse().unwrap();
+
+        assert_eq!(word, vec![4352, 2177, 33].into());
+    }
+
+    #[test]
+    fn glyphs_are_read_from_notation_and_written_as_numbers() {
+        let glyphs: Vec<Glyph> = serde_yaml::from_str("[4352, '2,6+15', fɑ]").unwrap();
+
+        assert_eq!(glyphs[0], Glyph(4352));
+        assert_eq!(glyphs[1], Glyph::from_segments(&[2, 6, 15]).unwrap());
+        assert_eq!(glyphs[2], "fɑ".parse().unwrap());
+
+        let yaml = serde_yaml::to_string(&glyphs).unwrap();
+        let reloaded: Vec<u16> = serde_yaml::from_str(&yaml).unwrap();
+
+        assert_eq!(reloaded, glyphs.iter().map(|glyph| glyph.0).collect::<Vec<u16>>());
+    }
+}
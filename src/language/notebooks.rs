@@ -2,7 +2,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::prelude::*;
 
-pub const VERSION: usize = 3;
+pub const VERSION: usize = 4;
 
 #[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
 pub struct Notebook {
@@ -18,3 +18,75 @@ impl From<Vec<Snippet>> for Notebook {
         }
     }
 }
+
+impl Notebook {
+    pub fn position_of(&self, id: &SnippetId) -> Option<usize> {
+        self.snippets.iter().position(|snippet| &snippet.id == id)
+    }
+
+    pub fn snippet_with_id(&self, id: &SnippetId) -> Option<&Snippet> {
+        self.snippets.iter().find(|snippet| &snippet.id == id)
+    }
+
+    /// A random ID that no snippet in the notebook has yet
+    pub fn new_snippet_id(&self) -> SnippetId {
+        loop {
+            let id = SnippetId::random();
+
+            if self.position_of(&id).is_none() {
+                return id;
+            }
+        }
+    }
+
+    /// Gives an ID to every snippet without one, like those added to the file
+    /// by hand. The IDs come from each snippet's position and content, so
+    /// every tool loading the same file gives the same ones until it is saved.
+    pub fn with_missing_snippet_ids(self) -> Self {
+        let mut notebook = self;
+
+        for index in 0..notebook.snippets.len() {
+            if notebook.snippets[index].id.is_empty() {
+                notebook.snippets[index].id = notebook.derived_snippet_id(index);
+            }
+        }
+
+        notebook
+    }
+
+    /// Hashes the snippet at `index` again with a counter while the ID is taken
+    fn derived_snippet_id(&self, index: usize) -> SnippetId {
+        let content = serde_yaml::to_string(&self.snippets[index]).unwrap_or_default();
+
+        for attempt in 0.. {
+            let id = SnippetId::hashed(&format!("{}/{}/{}", index, attempt, content));
+
+            if self.position_of(&id).is_none() {
+                return id;
+            }
+        }
+
+        unreachable!("a 64-bit hash can't be taken by every snippet")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snippets_without_ids_are_given_one() {
+        let kept = Snippet::default().with_id(SnippetId::for_position(0));
+        let notebook = Notebook::from(vec![kept.clone(), Snippet::default(), Snippet::default()])
+            .with_missing_snippet_ids();
+
+        assert_eq!(notebook.snippets[0], kept);
+        assert!(notebook.snippets.iter().all(|snippet| !snippet.id.is_empty()));
+        assert_ne!(notebook.snippets[1].id, notebook.snippets[2].id);
+
+        let loaded_again = Notebook::from(vec![kept, Snippet::default(), Snippet::default()])
+            .with_missing_snippet_ids();
+
+        assert_eq!(loaded_again, notebook);
+    }
+}
@@ -0,0 +1,210 @@
+use std::ops::Range;
+use std::str::FromStr;
+
+use crate::prelude::*;
+
+const ANY_GLYPH_NOTATION: &str = "?";
+const ANY_GLYPHS_NOTATION: &str = "*";
+const SAME_VOWEL_PREFIX: &str = "v:";
+const SAME_CONSONANT_PREFIX: &str = "c:";
+const WORD_SEPARATOR: char = '/';
+
+/// One position in a word pattern
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum GlyphPattern {
+    Exact(Glyph),
+    AnyGlyph,
+    AnyGlyphs,
+    SameVowel(Glyph),
+    SameConsonant(Glyph),
+}
+
+impl GlyphPattern {
+    pub fn parse_with(text: &str, table: &PhonemeTable) -> Result<Self, NotationError> {
+        if text == ANY_GLYPH_NOTATION {
+            Ok(Self::AnyGlyph)
+        } else if text == ANY_GLYPHS_NOTATION {
+            Ok(Self::AnyGlyphs)
+        } else if let Some(text) = text.strip_prefix(SAME_VOWEL_PREFIX) {
+            Ok(Self::SameVowel(Glyph::parse_with(text, table)?))
+        } else if let Some(text) = text.strip_prefix(SAME_CONSONANT_PREFIX) {
+            Ok(Self::SameConsonant(Glyph::parse_with(text, table)?))
+        } else {
+            Ok(Self::Exact(Glyph::parse_with(text, table)?))
+        }
+    }
+
+    /// `AnyGlyphs` matches any single glyph here. Runs are handled by `WordPattern`.
+    pub fn matches(&self, glyph: &Glyph) -> bool {
+        match self {
+            Self::Exact(expected) => glyph == expected,
+            Self::AnyGlyph | Self::AnyGlyphs => true,
+            Self::SameVowel(expected) => glyph.vowel_segments() == expected.vowel_segments(),
+            Self::SameConsonant(expected) => {
+                glyph.consonant_segments() == expected.consonant_segments()
+            }
+        }
+    }
+}
+
+/// The glyphs of a single Tunic word. English words never match.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WordPattern {
+    glyphs: Vec<GlyphPattern>,
+}
+
+impl WordPattern {
+    pub fn parse_with(text: &str, table: &PhonemeTable) -> Result<Self, NotationError> {
+        let glyphs = text
+            .split_whitespace()
+            .map(|glyph| GlyphPattern::parse_with(glyph, table))
+            .collect::<Result<Vec<GlyphPattern>, NotationError>>()?;
+
+        if glyphs.is_empty() {
+            Err(NotationError::new("Empty word pattern".into()))
+        } else {
+            Ok(Self { glyphs })
+        }
+    }
+
+    pub fn matches(&self, word: &Word) -> bool {
+        match &word.word_type {
+            WordType::Tunic(word) => glyphs_match(&self.glyphs, &word.glyphs()),
+            WordType::English(_) => false,
+        }
+    }
+}
+
+fn glyphs_match(patterns: &[GlyphPattern], glyphs: &[Glyph]) -> bool {
+    match patterns.split_first() {
+        None => glyphs.is_empty(),
+        Some((GlyphPattern::AnyGlyphs, patterns)) => {
+            (0..=glyphs.len()).any(|skipped| glyphs_match(patterns, &glyphs[skipped..]))
+        }
+        Some((pattern, patterns)) => match glyphs.split_first() {
+            Some((glyph, glyphs)) => pattern.matches(glyph) && glyphs_match(patterns, glyphs),
+            None => false,
+        },
+    }
+}
+
+/// A run of consecutive word patterns separated by `/`, e.g. `fɑ * / ? c:k`.
+/// Border and colour are ignored unless asked for.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SearchPattern {
+    words: Vec<WordPattern>,
+    has_border: Option<bool>,
+    colored: Option<bool>,
+}
+
+impl SearchPattern {
+    pub fn parse_with(text: &str, table: &PhonemeTable) -> Result<Self, NotationError> {
+        let words = text
+            .split(WORD_SEPARATOR)
+            .map(|word| WordPattern::parse_with(word, table))
+            .collect::<Result<Vec<WordPattern>, NotationError>>()?;
+
+        Ok(Self {
+            words,
+            has_border: None,
+            colored: None,
+        })
+    }
+
+    pub fn with_border_as(self, has_border: Option<bool>) -> Self {
+        Self { has_border, ..self }
+    }
+
+    pub fn with_colored_as(self, colored: Option<bool>) -> Self {
+        Self { colored, ..self }
+    }
+
+    fn matches_word(&self, pattern: &WordPattern, word: &Word) -> bool {
+        pattern.matches(word)
+            && self.has_border.is_none_or(|has_border| word.has_border() == has_border)
+            && self.colored.is_none_or(|colored| word.colored() == colored)
+    }
+
+    /// Every run of `words` that matches, in order. Runs may overlap.
+    pub fn find_in(&self, words: &[Word]) -> Vec<Range<usize>> {
+        let length = self.words.len();
+
+        if words.len() < length {
+            return vec![];
+        }
+
+        (0..=words.len() - length)
+            .filter(|start| {
+                self.words
+                    .iter()
+                    .zip(&words[*start..*start + length])
+                    .all(|(pattern, word)| self.matches_word(pattern, word))
+            })
+            .map(|start| start..start + length)
+            .collect()
+    }
+
+    pub fn is_found_in(&self, snippet: &Snippet) -> bool {
+        !self.find_in(&snippet.words).is_empty()
+    }
+}
+
+impl FromStr for SearchPattern {
+    type Err = NotationError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        Self::parse_with(text, &PhonemeTable::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word(text: &str) -> Word {
+        text.parse::<TunicWord>().unwrap().into()
+    }
+
+    #[test]
+    fn wildcards_match_glyphs() {
+        let words = vec![word("1, 2, 3,")];
+
+        assert_eq!("1, ? 3,".parse::<SearchPattern>().unwrap().find_in(&words), vec![0..1]);
+        assert_eq!("1, *".parse::<SearchPattern>().unwrap().find_in(&words), vec![0..1]);
+        assert_eq!("* 3,".parse::<SearchPattern>().unwrap().find_in(&words), vec![0..1]);
+        assert_eq!("1, * 2, 3,".parse::<SearchPattern>().unwrap().find_in(&words), vec![0..1]);
+        assert!("1, ?".parse::<SearchPattern>().unwrap().find_in(&words).is_empty());
+    }
+
+    #[test]
+    fn segment_masks_compare_half_of_a_glyph() {
+        let fa = word("fɑ");
+        let ka = word("kɑ");
+
+        let pattern: SearchPattern = "v:ɑ".parse().unwrap();
+        assert_eq!(pattern.find_in(&[fa.clone(), ka]).len(), 2);
+
+        let pattern: SearchPattern = "c:f".parse().unwrap();
+        assert_eq!(pattern.find_in(&[fa]).len(), 1);
+    }
+
+    #[test]
+    fn sequences_match_consecutive_words() {
+        let words = vec![word("1,"), word("2,"), word("3,")];
+
+        let pattern: SearchPattern = "2, / *".parse().unwrap();
+        assert_eq!(pattern.find_in(&words), vec![1..3]);
+
+        let pattern: SearchPattern = "1, / 3,".parse().unwrap();
+        assert!(pattern.find_in(&words).is_empty());
+    }
+
+    #[test]
+    fn style_is_ignored_unless_asked_for() {
+        let words = vec![word("*[1, 2,]")];
+        let pattern: SearchPattern = "1, 2,".parse().unwrap();
+
+        assert_eq!(pattern.find_in(&words).len(), 1);
+        assert!(pattern.with_border_as(Some(false)).find_in(&words).is_empty());
+    }
+}
@@ -0,0 +1,297 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::prelude::*;
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct PhonemeEntry {
+    pub segments: Vec<Segment>,
+    pub phoneme: String,
+}
+
+impl PhonemeEntry {
+    pub fn new(segments: &[Segment], phoneme: &str) -> Self {
+        Self {
+            segments: segments.to_vec(),
+            phoneme: phoneme.to_owned(),
+        }
+    }
+
+    pub fn to_glyph(&self) -> Result<Glyph, GlyphError> {
+        Glyph::from_segments(&self.segments)
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum PhonemeReading {
+    Silent,
+    Known(String),
+    Unknown(Glyph),
+}
+
+impl fmt::Display for PhonemeReading {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Silent => write!(f, ""),
+            Self::Known(phoneme) => write!(f, "{}", phoneme),
+            Self::Unknown(_) => write!(f, "?"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct GlyphReading {
+    pub consonant: PhonemeReading,
+    pub vowel: PhonemeReading,
+    pub reversed: bool,
+}
+
+impl GlyphReading {
+    pub fn is_fully_known(&self) -> bool {
+        !matches!(self.consonant, PhonemeReading::Unknown(_))
+            && !matches!(self.vowel, PhonemeReading::Unknown(_))
+    }
+}
+
+impl fmt::Display for GlyphReading {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Consonants come first unless the reversal circle is present
+        if self.reversed {
+            write!(f, "{}{}", self.vowel, self.consonant)
+        } else {
+            write!(f, "{}{}", self.consonant, self.vowel)
+        }
+    }
+}
+
+/// Maps the inner and outer segment groups of a glyph to phonemes.
+///
+/// The default table covers the commonly used English phonemes. Teams that read
+/// a glyph differently can load their own table from YAML instead.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct PhonemeTable {
+    vowels: Vec<PhonemeEntry>,
+    consonants: Vec<PhonemeEntry>,
+}
+
+impl PhonemeTable {
+    pub fn new(vowels: Vec<PhonemeEntry>, consonants: Vec<PhonemeEntry>) -> Self {
+        Self { vowels, consonants }
+    }
+
+    pub fn vowels(&self) -> &Vec<PhonemeEntry> {
+        &self.vowels
+    }
+
+    pub fn consonants(&self) -> &Vec<PhonemeEntry> {
+        &self.consonants
+    }
+
+    fn lookup(entries: &[PhonemeEntry], segments: Glyph) -> PhonemeReading {
+        if segments.is_blank() {
+            return PhonemeReading::Silent;
+        }
+
+        entries
+            .iter()
+            .find(|entry| entry.to_glyph().ok() == Some(segments))
+            .map_or(PhonemeReading::Unknown(segments), |entry| {
+                PhonemeReading::Known(entry.phoneme.clone())
+            })
+    }
+
+    pub fn vowel_for(&self, glyph: &Glyph) -> PhonemeReading {
+        Self::lookup(&self.vowels, glyph.vowel_segments())
+    }
+
+    pub fn consonant_for(&self, glyph: &Glyph) -> PhonemeReading {
+        Self::lookup(&self.consonants, glyph.consonant_segments())
+    }
+
+    pub fn read_glyph(&self, glyph: &Glyph) -> GlyphReading {
+        GlyphReading {
+            consonant: self.consonant_for(glyph),
+            vowel: self.vowel_for(glyph),
+            reversed: glyph.is_reversed(),
+        }
+    }
+
+    pub fn read_glyphs(&self, glyphs: &[Glyph]) -> String {
+        glyphs
+            .iter()
+            .map(|glyph| self.read_glyph(glyph).to_string())
+            .collect::<Vec<String>>()
+            .concat()
+    }
+
+    pub fn pronounce(&self, word: &TunicWord) -> String {
+        format!("/{}/", self.read_glyphs(&word.glyphs()))
+    }
+}
+
+impl Default for PhonemeTable {
+    fn default() -> Self {
+        // Outer lines: 1 (top left), 3 (top right), 4 + 8 (left side), 12 (bottom left), 14 (bottom right)
+        let vowels = vec![
+            PhonemeEntry::new(&[1, 3], "ə"),
+            PhonemeEntry::new(&[12, 14], "ɪ"),
+            PhonemeEntry::new(&[1, 3, 4, 8], "æ"),
+            PhonemeEntry::new(&[1, 4, 8, 12, 14], "ɑ"),
+            PhonemeEntry::new(&[4, 8, 12, 14], "ɛ"),
+            PhonemeEntry::new(&[3, 4, 8, 12, 14], "i"),
+            PhonemeEntry::new(&[1, 3, 4, 8, 14], "ɔ"),
+            PhonemeEntry::new(&[4, 8, 12], "ʊ"),
+            PhonemeEntry::new(&[1, 3, 12, 14], "u"),
+            PhonemeEntry::new(&[1, 3, 4, 8, 12, 14], "oʊ"),
+            PhonemeEntry::new(&[1], "aɪ"),
+            PhonemeEntry::new(&[3], "eɪ"),
+            PhonemeEntry::new(&[12], "ɔɪ"),
+            PhonemeEntry::new(&[14], "aʊ"),
+            PhonemeEntry::new(&[1, 3, 4, 8, 12], "ɜr"),
+            PhonemeEntry::new(&[1, 3, 12], "ɑr"),
+            PhonemeEntry::new(&[1, 3, 14], "ɪr"),
+            PhonemeEntry::new(&[3, 12, 14], "ɛr"),
+            PhonemeEntry::new(&[1, 12, 14], "ɔr"),
+        ];
+
+        // Inner lines: 2 + 6 (upper middle), 5 (upper left), 7 (upper right),
+        // 9 (lower left), 10 + 13 (lower middle), 11 (lower right)
+        let consonants = vec![
+            PhonemeEntry::new(&[9, 11], "m"),
+            PhonemeEntry::new(&[5, 9], "n"),
+            PhonemeEntry::new(&[5, 7, 9, 10, 11, 13], "ŋ"),
+            PhonemeEntry::new(&[7, 10, 13], "p"),
+            PhonemeEntry::new(&[2, 6, 9], "b"),
+            PhonemeEntry::new(&[7, 9, 10, 13], "t"),
+            PhonemeEntry::new(&[2, 5, 6, 9], "d"),
+            PhonemeEntry::new(&[2, 6, 7, 9], "k"),
+            PhonemeEntry::new(&[2, 6, 7, 11], "g"),
+            PhonemeEntry::new(&[5, 10, 13], "tʃ"),
+            PhonemeEntry::new(&[2, 6, 11], "dʒ"),
+            PhonemeEntry::new(&[5, 7, 11], "f"),
+            PhonemeEntry::new(&[5, 9, 11], "v"),
+            PhonemeEntry::new(&[2, 6, 7, 10, 11, 13], "θ"),
+            PhonemeEntry::new(&[2, 5, 6, 9, 10, 13], "ð"),
+            PhonemeEntry::new(&[2, 5, 6, 10, 11, 13], "s"),
+            PhonemeEntry::new(&[2, 6, 7, 9, 10, 13], "z"),
+            PhonemeEntry::new(&[2, 5, 6, 7, 9, 10, 13], "ʃ"),
+            PhonemeEntry::new(&[2, 5, 6, 7, 9, 10, 11, 13], "ʒ"),
+            PhonemeEntry::new(&[2, 6, 9, 10, 13], "h"),
+            PhonemeEntry::new(&[2, 6, 10, 13], "l"),
+            PhonemeEntry::new(&[2, 6, 7, 10, 13], "r"),
+            PhonemeEntry::new(&[5, 7], "w"),
+            PhonemeEntry::new(&[2, 6, 10, 11, 13], "j"),
+        ];
+
+        Self { vowels, consonants }
+    }
+}
+
+impl TunicWord {
+    pub fn pronunciation(&self, table: &PhonemeTable) -> String {
+        table.pronounce(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::HashSet;
+
+    fn glyph(segments: &[Segment]) -> Glyph {
+        Glyph::from_segments(segments).unwrap()
+    }
+
+    #[test]
+    fn segment_groups_cover_every_segment_exactly_once() {
+        assert_eq!(VOWEL_MASK & CONSONANT_MASK, 0);
+        assert_eq!(
+            VOWEL_MASK | CONSONANT_MASK | WORD_LINE_MASK | REVERSAL_MASK,
+            u16::MAX
+        );
+    }
+
+    #[test]
+    fn default_table_entries_stay_within_their_segment_groups() {
+        let table = PhonemeTable::default();
+
+        for entry in table.vowels() {
+            let glyph = entry.to_glyph().unwrap();
+            assert_eq!(glyph, glyph.vowel_segments(), "{}", entry.phoneme);
+        }
+
+        for entry in table.consonants() {
+            let glyph = entry.to_glyph().unwrap();
+            assert_eq!(glyph, glyph.consonant_segments(), "{}", entry.phoneme);
+        }
+    }
+
+    #[test]
+    fn default_table_entries_are_unique() {
+        let table = PhonemeTable::default();
+
+        let vowels: HashSet<Glyph> = table.vowels().iter().map(|e| e.to_glyph().unwrap()).collect();
+        let consonants: HashSet<Glyph> = table
+            .consonants()
+            .iter()
+            .map(|e| e.to_glyph().unwrap())
+            .collect();
+
+        assert_eq!(vowels.len(), table.vowels().len());
+        assert_eq!(consonants.len(), table.consonants().len());
+    }
+
+    #[test]
+    fn reads_consonant_before_vowel() {
+        let table = PhonemeTable::default();
+
+        let reading = table.read_glyph(&glyph(&[5, 7, 11, 1, 4, 8, 12, 14]));
+
+        assert_eq!(reading.to_string(), "fɑ");
+    }
+
+    #[test]
+    fn reversal_circle_reads_vowel_before_consonant() {
+        let table = PhonemeTable::default();
+
+        let reading = table.read_glyph(&glyph(&[2, 6, 7, 9, 1, 4, 8, 12, 14, 15]));
+
+        assert!(reading.reversed);
+        assert_eq!(reading.to_string(), "ɑk");
+    }
+
+    #[test]
+    fn word_line_does_not_affect_the_reading() {
+        let table = PhonemeTable::default();
+
+        let with_line = table.read_glyph(&glyph(&[0, 9, 11]));
+        let without_line = table.read_glyph(&glyph(&[9, 11]));
+
+        assert_eq!(with_line, without_line);
+    }
+
+    #[test]
+    fn unknown_segment_combinations_are_marked() {
+        let table = PhonemeTable::default();
+
+        let reading = table.read_glyph(&glyph(&[4]));
+
+        assert!(!reading.is_fully_known());
+        assert_eq!(reading.to_string(), "?");
+    }
+
+    #[test]
+    fn pronounces_whole_words() {
+        let table = PhonemeTable::default();
+
+        let word: TunicWord = vec![
+            glyph(&[5, 7, 11, 1, 4, 8, 12, 14]),
+            glyph(&[2, 6, 7, 9]),
+            glyph(&[2, 5, 6, 10, 11, 13]),
+        ]
+        .into();
+
+        assert_eq!(word.pronunciation(&table), "/fɑks/");
+    }
+}
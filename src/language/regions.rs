@@ -0,0 +1,167 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::prelude::*;
+
+/// Which page of a two page spread
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum PageSide {
+    Left,
+    Right,
+}
+
+/// Part of a source image, in the image's pixels from its top left corner
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct BoundingBox {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Where a snippet is on its source image. Bounds are measured on the whole
+/// image, whichever side they are on.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct SourceRegion {
+    #[serde(default)]
+    pub side: Option<PageSide>,
+    #[serde(default)]
+    pub bounds: Option<BoundingBox>,
+}
+
+/// Left, top, width and height as fractions of an image's size
+pub type ImageArea = (f32, f32, f32, f32);
+
+impl SourceRegion {
+    pub fn is_empty(&self) -> bool {
+        self.side.is_none() && self.bounds.is_none()
+    }
+
+    /// The part of an image of this size to highlight. Bounds win over the
+    /// side, and bounds reaching past the image are cut off at its edges.
+    pub fn area_within(&self, image_width: u32, image_height: u32) -> Option<ImageArea> {
+        if let Some(bounds) = self.bounds {
+            if image_width == 0 || image_height == 0 {
+                return None;
+            }
+
+            let (image_width, image_height) = (image_width as f32, image_height as f32);
+            let left = (bounds.x as f32 / image_width).min(1.0);
+            let top = (bounds.y as f32 / image_height).min(1.0);
+            let width = (bounds.width as f32 / image_width).min(1.0 - left);
+            let height = (bounds.height as f32 / image_height).min(1.0 - top);
+
+            return Some((left, top, width, height));
+        }
+
+        self.side.map(|side| match side {
+            PageSide::Left => (0.0, 0.0, 0.5, 1.0),
+            PageSide::Right => (0.5, 0.0, 0.5, 1.0),
+        })
+    }
+}
+
+/// Written as an optional `left` or `right` and optional `x,y,width,height`
+/// bounds, e.g. `right 120,40,300,64`
+impl fmt::Display for SourceRegion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut parts: Vec<String> = vec![];
+
+        match self.side {
+            Some(PageSide::Left) => parts.push("left".into()),
+            Some(PageSide::Right) => parts.push("right".into()),
+            None => {}
+        }
+
+        if let Some(bounds) = self.bounds {
+            parts.push(format!(
+                "{},{},{},{}",
+                bounds.x, bounds.y, bounds.width, bounds.height
+            ));
+        }
+
+        write!(f, "{}", parts.join(" "))
+    }
+}
+
+impl FromStr for SourceRegion {
+    type Err = FennecError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let invalid = || {
+            FennecError::Validation(format!(
+                "\"{}\" isn't a region. Expected left or right and/or x,y,width,height",
+                text
+            ))
+        };
+
+        let mut region = SourceRegion::default();
+
+        for part in text.split_whitespace() {
+            match part.to_lowercase().as_str() {
+                "left" if region.side.is_none() => region.side = Some(PageSide::Left),
+                "right" if region.side.is_none() => region.side = Some(PageSide::Right),
+                _ if region.bounds.is_none() => {
+                    let numbers = part
+                        .split(',')
+                        .map(|number| number.trim().parse::<u32>())
+                        .collect::<Result<Vec<u32>, _>>()
+                        .map_err(|_| invalid())?;
+
+                    match numbers[..] {
+                        [x, y, width, height] if width > 0 && height > 0 => {
+                            region.bounds = Some(BoundingBox {
+                                x,
+                                y,
+                                width,
+                                height,
+                            })
+                        }
+                        _ => return Err(invalid()),
+                    }
+                }
+                _ => return Err(invalid()),
+            }
+        }
+
+        Ok(region)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn regions_round_trip_through_text() {
+        let region: SourceRegion = " Right  120,40,300,64 ".parse().unwrap();
+
+        assert_eq!(region.side, Some(PageSide::Right));
+        assert_eq!(
+            region.bounds,
+            Some(BoundingBox {
+                x: 120,
+                y: 40,
+                width: 300,
+                height: 64
+            })
+        );
+        assert_eq!(region.to_string().parse::<SourceRegion>().unwrap(), region);
+        assert!("".parse::<SourceRegion>().unwrap().is_empty());
+        assert!("left right".parse::<SourceRegion>().is_err());
+        assert!("1,2,0,4".parse::<SourceRegion>().is_err());
+        assert!("1,2,3".parse::<SourceRegion>().is_err());
+    }
+
+    #[test]
+    fn areas_are_fractions_of_the_image() {
+        let side_only: SourceRegion = "left".parse().unwrap();
+        let bounds: SourceRegion = "right 100,50,200,400".parse().unwrap();
+
+        assert_eq!(side_only.area_within(800, 600), Some((0.0, 0.0, 0.5, 1.0)));
+        assert_eq!(bounds.area_within(400, 200), Some((0.25, 0.25, 0.5, 0.75)));
+        assert_eq!(bounds.area_within(0, 0), None);
+        assert_eq!(SourceRegion::default().area_within(400, 200), None);
+    }
+}
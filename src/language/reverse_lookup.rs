@@ -0,0 +1,107 @@
+use std::collections::{BTreeMap, HashSet};
+
+use crate::prelude::*;
+
+/// Lowercase words of a definition, split on anything that isn't a letter or digit
+pub fn definition_terms(text: &str) -> Vec<String> {
+    text.split(|character: char| !character.is_alphanumeric())
+        .filter(|term| !term.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Maps the words of every candidate reading back to the Tunic words they define
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ReverseIndex {
+    terms: BTreeMap<String, HashSet<DictionaryWord>>,
+}
+
+impl ReverseIndex {
+    pub fn new(dictionary: &Dictionary) -> Self {
+        let mut terms: BTreeMap<String, HashSet<DictionaryWord>> = BTreeMap::new();
+
+        for (word, entry) in dictionary.entries() {
+            for candidate in entry.candidates() {
+                for term in definition_terms(candidate.text()) {
+                    terms.entry(term).or_default().insert(word.clone());
+                }
+            }
+        }
+
+        Self { terms }
+    }
+
+    /// Words defined with a term starting with `prefix`
+    fn words_with_prefix(&self, prefix: &str) -> HashSet<DictionaryWord> {
+        self.terms
+            .range(prefix.to_owned()..)
+            .take_while(|(term, _)| term.starts_with(prefix))
+            .flat_map(|(_, words)| words.iter().cloned())
+            .collect()
+    }
+
+    /// Words whose definition has a term starting with each term of `text`, so
+    /// results show up while the last one is still being typed
+    pub fn lookup(&self, text: &str) -> Vec<DictionaryWord> {
+        let mut found: Option<HashSet<DictionaryWord>> = None;
+
+        for term in definition_terms(text) {
+            let words = self.words_with_prefix(&term);
+
+            found = Some(match found {
+                Some(found) => found.intersection(&words).cloned().collect(),
+                None => words,
+            });
+        }
+
+        let mut found: Vec<DictionaryWord> = found.unwrap_or_default().into_iter().collect();
+        found.sort_by_key(|word| word.to_string());
+
+        found
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word(text: &str) -> TunicWord {
+        text.parse().unwrap()
+    }
+
+    fn dictionary() -> Dictionary {
+        Dictionary::new()
+            .with_new_definition(&word("1,2"), "Sword".into())
+            .with_definition_for(&word("3,4").into(), Definition::Confirmed("old sword".into()))
+            .with_new_definition(&word("5,6"), "swordsman's shield".into())
+            .with_definition_for(&word("7,8").into(), Definition::Undefined)
+    }
+
+    #[test]
+    fn definitions_are_split_into_lowercase_terms() {
+        assert_eq!(
+            definition_terms("The Fox's, sword!"),
+            vec!["the", "fox", "s", "sword"]
+        );
+    }
+
+    #[test]
+    fn terms_match_case_insensitively_by_prefix() {
+        let index = ReverseIndex::new(&dictionary());
+
+        let found: Vec<String> = index.lookup("SWORD").iter().map(|word| word.to_string()).collect();
+        assert_eq!(found, vec!["1,2", "3,4", "5,6"]);
+
+        let found: Vec<String> = index.lookup("sword sh").iter().map(|word| word.to_string()).collect();
+        assert_eq!(found, vec!["5,6"]);
+    }
+
+    #[test]
+    fn empty_queries_find_nothing() {
+        let index = ReverseIndex::new(&dictionary());
+
+        assert!(index.lookup("").is_empty());
+        assert!(index.lookup(" ?! ").is_empty());
+        assert!(index.lookup("undefined").is_empty());
+    }
+}
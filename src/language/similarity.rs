@@ -0,0 +1,107 @@
+use std::collections::HashMap;
+
+use crate::prelude::*;
+
+/// A word close to the one searched for, from the notebook, the dictionary or both
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SimilarWord {
+    pub word: DictionaryWord,
+    pub distance: u32,
+    pub usages: usize,
+    pub entry: Option<Entry>,
+}
+
+/// Every word within `max_distance` segments of `query`, closest first and then
+/// most used first
+pub fn similar_words(
+    query: &DictionaryWord,
+    max_distance: u32,
+    notebook: &Notebook,
+    dictionary: &Dictionary,
+) -> Vec<SimilarWord> {
+    let mut usages: HashMap<DictionaryWord, usize> = HashMap::new();
+
+    for word in notebook
+        .snippets
+        .iter()
+        .flat_map(|snippet| snippet.words.iter())
+    {
+        if let WordType::Tunic(tunic_word) = &word.word_type {
+            *usages.entry(tunic_word.into()).or_insert(0) += 1;
+        }
+    }
+
+    for word in dictionary.entries().keys() {
+        usages.entry(word.clone()).or_insert(0);
+    }
+
+    let mut similar: Vec<SimilarWord> = usages
+        .into_iter()
+        .map(|(word, usages)| SimilarWord {
+            distance: word.segment_distance(query),
+            entry: dictionary.get(&word).cloned(),
+            word,
+            usages,
+        })
+        .filter(|similar_word| similar_word.distance <= max_distance)
+        .collect();
+
+    similar.sort_by(|a, b| {
+        a.distance
+            .cmp(&b.distance)
+            .then(b.usages.cmp(&a.usages))
+            .then(a.word.to_string().cmp(&b.word.to_string()))
+    });
+
+    similar
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word(text: &str) -> TunicWord {
+        text.parse().unwrap()
+    }
+
+    #[test]
+    fn segment_distance_counts_differing_segments() {
+        let glyph = Glyph::from_segments(&[1, 2, 3]).unwrap();
+        let other = Glyph::from_segments(&[1, 4]).unwrap();
+
+        assert_eq!(glyph.segment_distance(&other), 3);
+        assert_eq!(glyph.segment_distance(&glyph), 0);
+    }
+
+    #[test]
+    fn missing_glyphs_cost_all_their_segments() {
+        assert_eq!(word("1,2 3,").segment_distance(&word("1,2")), 1);
+        assert_eq!(word("[1,2]").segment_distance(&word("1,2")), 0);
+    }
+
+    #[test]
+    fn similar_words_come_from_the_notebook_and_dictionary() {
+        let notebook: Notebook = vec![Snippet {
+            words: vec![word("1,2").into(), word("1,2").into(), word("9,10").into()],
+            ..Snippet::default()
+        }]
+        .into();
+        let dictionary = Dictionary::new().with_new_definition(&word("1,3"), "fox".into());
+
+        let similar = similar_words(&word("1,2").into(), 2, &notebook, &dictionary);
+
+        let found: Vec<(String, u32, usize)> = similar
+            .iter()
+            .map(|similar_word| {
+                (
+                    similar_word.word.to_string(),
+                    similar_word.distance,
+                    similar_word.usages,
+                )
+            })
+            .collect();
+
+        assert_eq!(found, vec![("1,2".into(), 0, 2), ("1,3".into(), 2, 0)]);
+        assert!(similar[1].entry.is_some());
+    }
+}
@@ -1,33 +1,210 @@
+use chrono::{DateTime, NaiveDate, SecondsFormat, TimeZone, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::hash_map::RandomState;
+use std::fmt;
+use std::hash::{BuildHasher, Hasher};
+use std::str::FromStr;
 
 use crate::prelude::*;
 
+/// Migrated IDs are this long
+const SNIPPET_ID_LENGTH: usize = 8;
+/// New IDs are 64 random or hashed bits
+const RANDOM_SNIPPET_ID_LENGTH: usize = 16;
+
+/// 64-bit FNV-1a, which unlike the standard library's hashers is the same on
+/// every run and every build
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0100_0000_01b3;
+
+/// Notes refer to other snippets by their ID after this
+pub const SNIPPET_REFERENCE_PREFIX: char = '#';
+
+/// Stays with a snippet wherever it moves in the notebook. IDs are sixteen
+/// random hex digits for new snippets, and eight numbered by position for
+/// snippets that were in the notebook before IDs existed.
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct SnippetId(String);
+
+impl SnippetId {
+    /// Use `Notebook::new_snippet_id` to also avoid the IDs already in use
+    pub fn random() -> Self {
+        let mut bytes = [0u8; 8];
+
+        // Without the operating system's random source, the standard library's
+        // own random keys still give every ID all 64 bits
+        let bits = match getrandom::getrandom(&mut bytes) {
+            Ok(()) => u64::from_le_bytes(bytes),
+            Err(_) => RandomState::new().build_hasher().finish(),
+        };
+
+        Self(format!("{:0width$x}", bits, width = RANDOM_SNIPPET_ID_LENGTH))
+    }
+
+    /// The same text always gives the same ID, so tools that can't save one
+    /// still agree on it
+    pub fn hashed(text: &str) -> Self {
+        let hash = text.bytes().fold(FNV_OFFSET_BASIS, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(FNV_PRIME)
+        });
+
+        Self(format!("{:0width$x}", hash, width = RANDOM_SNIPPET_ID_LENGTH))
+    }
+
+    /// The ID given by migration to the snippet at `index` of an older notebook
+    pub fn for_position(index: usize) -> Self {
+        Self(format!("{:0width$x}", index + 1, width = SNIPPET_ID_LENGTH))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// How notes write a reference to this snippet
+    pub fn to_reference(&self) -> String {
+        format!("{}{}", SNIPPET_REFERENCE_PREFIX, self.0)
+    }
+}
+
+impl fmt::Display for SnippetId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Takes IDs with or without the reference prefix
+impl FromStr for SnippetId {
+    type Err = FennecError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let id = text.trim();
+        let id = id.strip_prefix(SNIPPET_REFERENCE_PREFIX).unwrap_or(id);
+
+        let is_hex = id.chars().all(|character| character.is_ascii_hexdigit());
+
+        let is_id_length = id.len() == SNIPPET_ID_LENGTH || id.len() == RANDOM_SNIPPET_ID_LENGTH;
+
+        if is_id_length && is_hex {
+            Ok(Self(id.to_lowercase()))
+        } else {
+            Err(FennecError::Validation(format!(
+                "\"{}\" isn't a snippet ID. Expected {} or {} hex digits",
+                text, SNIPPET_ID_LENGTH, RANDOM_SNIPPET_ID_LENGTH
+            )))
+        }
+    }
+}
+
+/// UTC to the second in RFC 3339, so that timestamps sort as text
+pub fn current_timestamp() -> String {
+    Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+/// Reads a timestamp like `current_timestamp` gives, or just a date like
+/// `2023-03-14` for the start of that day
+pub fn parse_date_or_timestamp(text: &str) -> FennecResult<DateTime<Utc>> {
+    let text = text.trim();
+
+    if let Ok(timestamp) = DateTime::parse_from_rfc3339(text) {
+        return Ok(timestamp.with_timezone(&Utc));
+    }
+
+    NaiveDate::parse_from_str(text, "%Y-%m-%d")
+        .ok()
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .map(|start_of_day| Utc.from_utc_datetime(&start_of_day))
+        .ok_or_else(|| {
+            FennecError::Validation(format!(
+                "\"{}\" isn't a date or timestamp. Expected something like 2023-03-14 or \
+                 2023-03-14T09:30:00Z",
+                text
+            ))
+        })
+}
+
 #[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
 pub struct Snippet {
+    /// Snippets sent without an ID, such as to the server, are given one
+    #[serde(default)]
+    pub id: SnippetId,
     pub source: Option<Source>,
+    /// Where on the source image the snippet is
+    #[serde(default)]
+    pub region: Option<SourceRegion>,
     pub description: String,
     pub transcribed: bool,
+    /// Kinds of text, like `sign`, `npc` or `manual`
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Where in the game the text is found
+    #[serde(default)]
+    pub location: Option<String>,
+    /// Timestamps from `current_timestamp`. Snippets from before timestamps
+    /// were kept have none.
+    #[serde(default)]
+    pub created: Option<String>,
+    #[serde(default)]
+    pub modified: Option<String>,
     pub notes: Vec<Note>,
     pub words: Vec<Word>,
 }
 
+/// Narrows snippets down by their metadata. Every condition given must hold.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct SnippetFilter {
+    pub tags: Vec<String>,
+    pub location: Option<String>,
+    /// From `parse_date_or_timestamp`
+    pub modified_since: Option<DateTime<Utc>>,
+}
+
+impl SnippetFilter {
+    pub fn retains(&self, snippet: &Snippet) -> bool {
+        self.tags.iter().all(|tag| snippet.has_tag(tag))
+            && self
+                .location
+                .as_ref()
+                .is_none_or(|location| snippet.is_at_location(location))
+            && self
+                .modified_since
+                .as_ref()
+                .is_none_or(|since| snippet.was_modified_since(*since))
+    }
+}
+
 impl Snippet {
     pub fn starting_snippet() -> Self {
         let word: Word = DEFAULT_GLYPH.into();
         let words = vec![word];
-        let source = Some(Source::Other("ADD_SOURCE_HERE".into()));
-        let description = "ADD_DESCRIPTION_HERE".into();
-        let note: Note = "ADD_NOTE_HERE".into();
+        let source = Some(Source::Other(PLACEHOLDER_SOURCE.into()));
+        let description = PLACEHOLDER_DESCRIPTION.into();
+        let note: Note = PLACEHOLDER_NOTE.into();
+        let now = current_timestamp();
 
         Self {
+            id: SnippetId::random(),
             words,
             source,
             description,
             notes: vec![note],
             transcribed: false,
+            region: None,
+            tags: vec![],
+            location: None,
+            created: Some(now.clone()),
+            modified: Some(now),
         }
     }
 
+    pub fn with_id(self, id: SnippetId) -> Self {
+        Self { id, ..self }
+    }
+
     pub fn with_transcription_state_toggled(self) -> Self {
         Self {
             transcribed: !self.transcribed,
@@ -35,7 +212,210 @@ impl Snippet {
         }
     }
 
+    pub fn with_description(self, description: String) -> Self {
+        Self {
+            description,
+            ..self
+        }
+    }
+
+    pub fn with_source(self, source: Option<Source>) -> Self {
+        Self { source, ..self }
+    }
+
+    /// Empty regions are removed
+    pub fn with_region(self, region: Option<SourceRegion>) -> Self {
+        let region = region.filter(|region| !region.is_empty());
+
+        Self { region, ..self }
+    }
+
+    /// Tags are trimmed and lowercased, and blank or repeated ones are dropped
+    pub fn with_tags(self, tags: Vec<String>) -> Self {
+        let mut unique_tags: Vec<String> = vec![];
+
+        for tag in tags.iter().map(|tag| tag.trim().to_lowercase()) {
+            if !tag.is_empty() && !unique_tags.contains(&tag) {
+                unique_tags.push(tag);
+            }
+        }
+
+        Self {
+            tags: unique_tags,
+            ..self
+        }
+    }
+
+    /// Tags written as a comma separated list, as the GUI edits them
+    pub fn with_tags_text(self, text: &str) -> Self {
+        self.with_tags(text.split(',').map(str::to_owned).collect())
+    }
+
+    pub fn tags_text(&self) -> String {
+        self.tags.join(", ")
+    }
+
+    /// Blank locations are removed
+    pub fn with_location(self, location: Option<String>) -> Self {
+        let location = location
+            .map(|location| location.trim().to_owned())
+            .filter(|location| !location.is_empty());
+
+        Self { location, ..self }
+    }
+
+    pub fn with_modified_at(self, timestamp: String) -> Self {
+        Self {
+            modified: Some(timestamp),
+            ..self
+        }
+    }
+
+    pub fn has_tag(&self, tag: &str) -> bool {
+        let tag = tag.trim().to_lowercase();
+
+        self.tags.contains(&tag)
+    }
+
+    /// Matches any part of the location, ignoring case
+    pub fn is_at_location(&self, text: &str) -> bool {
+        let text = text.to_lowercase();
+
+        self.location
+            .as_ref()
+            .is_some_and(|location| location.to_lowercase().contains(&text))
+    }
+
+    /// Snippets without a readable modified timestamp never match
+    pub fn was_modified_since(&self, since: DateTime<Utc>) -> bool {
+        self.modified
+            .as_deref()
+            .and_then(|modified| parse_date_or_timestamp(modified).ok())
+            .is_some_and(|modified| modified >= since)
+    }
+
+    /// Replaces the note at `index`, or adds it when `index` is just past the end
+    pub fn with_note_at(self, index: usize, note: Note) -> Self {
+        let mut notes = self.notes;
+
+        if let Some(existing_note) = notes.get_mut(index) {
+            *existing_note = note;
+        } else if index == notes.len() {
+            notes.push(note);
+        }
+
+        Self { notes, ..self }
+    }
+
+    pub fn without_note_at(self, index: usize) -> Self {
+        let mut notes = self.notes;
+
+        if index < notes.len() {
+            notes.remove(index);
+        }
+
+        Self { notes, ..self }
+    }
+
     pub fn contains_word(&self, word_to_find: &Word) -> bool {
         self.words.iter().any(|word| word == word_to_find)
     }
+
+    /// Every snippet the notes refer to, in order
+    pub fn referenced_snippets(&self) -> Vec<SnippetId> {
+        self.notes.iter().flat_map(Note::snippet_references).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ids_parse_with_or_without_the_reference_prefix() {
+        let id = SnippetId::for_position(41);
+
+        assert_eq!(id.as_str(), "0000002a");
+        assert_eq!(id.to_reference().parse::<SnippetId>().unwrap(), id);
+        assert_eq!("0000002A".parse::<SnippetId>().unwrap(), id);
+        assert!("2a".parse::<SnippetId>().is_err());
+        assert!("0000002g".parse::<SnippetId>().is_err());
+    }
+
+    #[test]
+    fn hashed_ids_only_depend_on_the_text() {
+        let id = SnippetId::hashed("0/West Garden");
+
+        assert_eq!(id, SnippetId::hashed("0/West Garden"));
+        assert_ne!(id, SnippetId::hashed("1/West Garden"));
+        assert_eq!(SnippetId::hashed("a").as_str(), "af63dc4c8601ec8c");
+    }
+
+    #[test]
+    fn random_ids_are_sixteen_hex_digits() {
+        let id = SnippetId::random();
+
+        assert_eq!(id.as_str().len(), 16);
+        assert_eq!(id.as_str().parse::<SnippetId>().unwrap(), id);
+        assert_ne!(SnippetId::random(), id);
+    }
+
+    #[test]
+    fn filters_check_tags_location_and_modified_time() {
+        let snippet = Snippet::default()
+            .with_tags_text(" Sign, npc,, sign")
+            .with_location(Some("West Garden".into()))
+            .with_modified_at("2023-03-14T09:30:00Z".into());
+
+        assert_eq!(snippet.tags, vec!["sign".to_owned(), "npc".to_owned()]);
+
+        let filter = SnippetFilter {
+            tags: vec!["SIGN".into()],
+            location: Some("garden".into()),
+            modified_since: Some(parse_date_or_timestamp("2023-03-14").unwrap()),
+        };
+
+        assert!(filter.retains(&snippet));
+        assert!(!SnippetFilter {
+            modified_since: Some(parse_date_or_timestamp("2023-03-15").unwrap()),
+            ..filter.clone()
+        }
+        .retains(&snippet));
+        assert!(SnippetFilter {
+            modified_since: Some(parse_date_or_timestamp("2023-03-14T10:00:00+01:00").unwrap()),
+            ..filter.clone()
+        }
+        .retains(&snippet));
+        assert!(!SnippetFilter {
+            tags: vec!["sign".into(), "manual".into()],
+            ..filter
+        }
+        .retains(&snippet));
+        assert!(SnippetFilter::default().retains(&Snippet::default()));
+    }
+
+    #[test]
+    fn unreadable_dates_are_rejected() {
+        assert!(matches!(
+            parse_date_or_timestamp("14/03/2023"),
+            Err(FennecError::Validation(_))
+        ));
+        assert!(parse_date_or_timestamp("2023-02-30").is_err());
+    }
+
+    #[test]
+    fn notes_refer_to_snippets_by_id() {
+        let snippet = Snippet {
+            notes: vec![
+                "Same word as #0000002a, see (#deadBEEF).".into(),
+                "Page #3 and ##12345678x aren't references".into(),
+            ],
+            ..Snippet::default()
+        };
+
+        assert_eq!(
+            snippet.referenced_snippets(),
+            vec![SnippetId::for_position(41), "deadbeef".parse().unwrap()]
+        );
+    }
 }
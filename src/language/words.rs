@@ -56,6 +56,11 @@ impl TunicWord {
     pub fn colored(&self) -> bool {
         self.colored
     }
+
+    /// Ignores border and colour
+    pub fn segment_distance(&self, other: &TunicWord) -> u32 {
+        glyphs_segment_distance(&self.glyphs, &other.glyphs)
+    }
 }
 
 impl From<Vec<Glyph>> for TunicWord {
@@ -0,0 +1,437 @@
+use serde::Serialize;
+use serde_yaml::Value;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use crate::prelude::*;
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LintKind {
+    BlankGlyphInWord,
+    WordLineOnlyGlyph,
+    PlaceholderSnippet,
+    PlaceholderInTranscribedSnippet,
+    NearDuplicateWords,
+    UnusedDictionaryEntry,
+    DuplicateDefinition,
+    DuplicateSnippetId,
+    UnknownSnippetReference,
+}
+
+/// A single problem found in the notebook or dictionary. Snippets are found
+/// by their ID, which stays put when other snippets are added. The snippet's
+/// index in the notebook is only kept for telling apart snippets sharing an
+/// ID. Words are indices into the snippet's words.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct Lint {
+    pub kind: LintKind,
+    pub snippet: Option<SnippetId>,
+    pub snippet_index: Option<usize>,
+    pub word: Option<usize>,
+    pub message: String,
+}
+
+impl Lint {
+    fn in_snippet(
+        kind: LintKind,
+        snippet_index: usize,
+        snippet: &Snippet,
+        message: String,
+    ) -> Self {
+        Self {
+            kind,
+            snippet: Some(snippet.id.clone()),
+            snippet_index: Some(snippet_index),
+            word: None,
+            message,
+        }
+    }
+
+    fn in_word(
+        kind: LintKind,
+        snippet_index: usize,
+        snippet: &Snippet,
+        word: usize,
+        message: String,
+    ) -> Self {
+        Self {
+            word: Some(word),
+            ..Self::in_snippet(kind, snippet_index, snippet, message)
+        }
+    }
+
+    fn in_dictionary(kind: LintKind, message: String) -> Self {
+        Self {
+            kind,
+            snippet: None,
+            snippet_index: None,
+            word: None,
+            message,
+        }
+    }
+}
+
+impl fmt::Display for Lint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.snippet, self.word) {
+            (Some(snippet), Some(word)) => {
+                write!(f, "snippet {}, word {}: ", snippet.to_reference(), word)?
+            }
+            (Some(snippet), None) => write!(f, "snippet {}: ", snippet.to_reference())?,
+            _ => write!(f, "dictionary: ")?,
+        };
+
+        write!(f, "{}", self.message)
+    }
+}
+
+/// Runs every check that needs the loaded notebook and dictionary
+pub fn lint_notebook(notebook: &Notebook, dictionary: &Dictionary) -> Vec<Lint> {
+    let mut lints: Vec<Lint> = vec![];
+
+    for (snippet_index, snippet) in notebook.snippets.iter().enumerate() {
+        lints.extend(lint_snippet(snippet_index, snippet));
+    }
+
+    lints.extend(lint_snippet_ids(notebook));
+    lints.extend(lint_near_duplicate_words(notebook, dictionary));
+    lints.extend(lint_unused_dictionary_entries(notebook, dictionary));
+    lints.extend(lint_candidate_snippets(notebook, dictionary));
+
+    lints
+}
+
+fn lint_snippet(snippet_index: usize, snippet: &Snippet) -> Vec<Lint> {
+    let mut lints: Vec<Lint> = vec![];
+
+    for (word_index, word) in snippet.words.iter().enumerate() {
+        let tunic_word = match &word.word_type {
+            WordType::Tunic(tunic_word) => tunic_word,
+            WordType::English(english_word) => {
+                if snippet.transcribed && english_word.text() == PLACEHOLDER_ENGLISH_WORD {
+                    lints.push(Lint::in_word(
+                        LintKind::PlaceholderInTranscribedSnippet,
+                        snippet_index,
+                        snippet,
+                        word_index,
+                        format!("{} in a transcribed snippet", PLACEHOLDER_ENGLISH_WORD),
+                    ));
+                }
+
+                continue;
+            }
+        };
+
+        let glyphs = tunic_word.glyphs();
+
+        if glyphs.len() > 1 && glyphs.iter().any(Glyph::is_blank) {
+            lints.push(Lint::in_word(
+                LintKind::BlankGlyphInWord,
+                snippet_index,
+                snippet,
+                word_index,
+                format!("Blank glyph inside word [{}]", tunic_word),
+            ));
+        }
+
+        if glyphs.iter().any(|glyph| glyph.0 == WORD_LINE_MASK) {
+            lints.push(Lint::in_word(
+                LintKind::WordLineOnlyGlyph,
+                snippet_index,
+                snippet,
+                word_index,
+                format!("Glyph with only a word line in [{}]", tunic_word),
+            ));
+        }
+    }
+
+    let has_placeholder_source = snippet.source == Some(Source::Other(PLACEHOLDER_SOURCE.into()));
+    let has_placeholder_description = snippet.description == PLACEHOLDER_DESCRIPTION;
+    let has_placeholder_note = snippet.notes.iter().any(|note| note.0 == PLACEHOLDER_NOTE);
+
+    if has_placeholder_source || has_placeholder_description || has_placeholder_note {
+        lints.push(Lint::in_snippet(
+            LintKind::PlaceholderSnippet,
+            snippet_index,
+            snippet,
+            "Still holds the new snippet placeholders".into(),
+        ));
+    }
+
+    lints
+}
+
+/// Only later snippets sharing an ID are reported, since references go to the
+/// first
+fn lint_snippet_ids(notebook: &Notebook) -> Vec<Lint> {
+    let mut lints: Vec<Lint> = vec![];
+    let mut seen_ids: HashSet<&SnippetId> = HashSet::new();
+
+    for (snippet_index, snippet) in notebook.snippets.iter().enumerate() {
+        if !seen_ids.insert(&snippet.id) {
+            lints.push(Lint::in_snippet(
+                LintKind::DuplicateSnippetId,
+                snippet_index,
+                snippet,
+                format!("The snippet at index {} has the same ID as an earlier one", snippet_index),
+            ));
+        }
+
+        for id in snippet.referenced_snippets() {
+            if notebook.position_of(&id).is_none() {
+                lints.push(Lint::in_snippet(
+                    LintKind::UnknownSnippetReference,
+                    snippet_index,
+                    snippet,
+                    format!("Notes refer to {}, which isn't in the notebook", id.to_reference()),
+                ));
+            }
+        }
+    }
+
+    lints
+}
+
+fn notebook_words(notebook: &Notebook) -> HashSet<DictionaryWord> {
+    notebook
+        .snippets
+        .iter()
+        .flat_map(|snippet| snippet.words.iter())
+        .filter_map(|word| match &word.word_type {
+            WordType::Tunic(tunic_word) => Some(tunic_word.into()),
+            WordType::English(_) => None,
+        })
+        .collect()
+}
+
+fn lint_near_duplicate_words(notebook: &Notebook, dictionary: &Dictionary) -> Vec<Lint> {
+    let mut words: Vec<DictionaryWord> = notebook_words(notebook)
+        .into_iter()
+        .chain(dictionary.entries().keys().cloned())
+        .collect::<HashSet<DictionaryWord>>()
+        .into_iter()
+        .collect();
+
+    words.sort_by_key(|word| word.to_string());
+
+    words
+        .iter()
+        .enumerate()
+        .flat_map(|(index, word)| {
+            words[index + 1..]
+                .iter()
+                .filter(|other| word.segment_distance(other) == 1)
+                .map(move |other| {
+                    Lint::in_dictionary(
+                        LintKind::NearDuplicateWords,
+                        format!("[{}] and [{}] differ by one segment", word, other),
+                    )
+                })
+        })
+        .collect()
+}
+
+fn lint_unused_dictionary_entries(notebook: &Notebook, dictionary: &Dictionary) -> Vec<Lint> {
+    let used_words = notebook_words(notebook);
+
+    let mut unused_words: Vec<&DictionaryWord> = dictionary
+        .entries()
+        .keys()
+        .filter(|word| !used_words.contains(word))
+        .collect();
+
+    unused_words.sort_by_key(|word| word.to_string());
+
+    unused_words
+        .into_iter()
+        .map(|word| {
+            Lint::in_dictionary(
+                LintKind::UnusedDictionaryEntry,
+                format!("[{}] is defined but no snippet uses it", word),
+            )
+        })
+        .collect()
+}
+
+fn lint_candidate_snippets(notebook: &Notebook, dictionary: &Dictionary) -> Vec<Lint> {
+    let mut lints: Vec<Lint> = dictionary
+        .entries()
+        .iter()
+        .flat_map(|(word, entry)| {
+            entry
+                .candidates()
+                .iter()
+                .flat_map(|candidate| candidate.snippets().iter().map(move |id| (candidate, id)))
+                .filter(|(_, id)| notebook.position_of(id).is_none())
+                .map(move |(candidate, id)| {
+                    Lint::in_dictionary(
+                        LintKind::UnknownSnippetReference,
+                        format!(
+                            "[{}] \"{}\" refers to {}, which isn't in the notebook",
+                            word,
+                            candidate.text(),
+                            id.to_reference()
+                        ),
+                    )
+                })
+        })
+        .collect();
+
+    lints.sort_by(|a, b| a.message.cmp(&b.message));
+
+    lints
+}
+
+/// Works on the dictionary file itself, since loading it keeps only the last of
+/// two keys whose glyphs are written differently, such as as a number and in
+/// segment notation, but are the same glyphs
+pub fn lint_dictionary_source(yaml: &Value) -> Vec<Lint> {
+    let mut counts: HashMap<DictionaryWord, usize> = HashMap::new();
+
+    let keys = yaml
+        .get("entries")
+        .and_then(Value::as_mapping)
+        .map(|entries| entries.keys().collect::<Vec<&Value>>())
+        .unwrap_or_default();
+
+    for key in keys {
+        if let Ok(word) = serde_yaml::from_value::<DictionaryWord>(key.clone()) {
+            *counts.entry(word).or_insert(0) += 1;
+        }
+    }
+
+    let mut duplicates: Vec<(DictionaryWord, usize)> =
+        counts.into_iter().filter(|(_, count)| *count > 1).collect();
+
+    duplicates.sort_by_key(|(word, _)| word.to_string());
+
+    duplicates
+        .into_iter()
+        .map(|(word, count)| {
+            Lint::in_dictionary(
+                LintKind::DuplicateDefinition,
+                format!("[{}] is defined {} times", word, count),
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word(text: &str) -> Word {
+        text.parse::<TunicWord>().unwrap().into()
+    }
+
+    fn kinds(lints: &[Lint]) -> Vec<LintKind> {
+        lints.iter().map(|lint| lint.kind).collect()
+    }
+
+    #[test]
+    fn word_problems_are_found() {
+        let snippet = Snippet {
+            words: vec![
+                word("1,2 _ 3,"),
+                word("0x8000"),
+                PLACEHOLDER_ENGLISH_WORD.to_string().into(),
+            ],
+            transcribed: true,
+            ..Snippet::default()
+        };
+        let notebook: Notebook = vec![snippet].into();
+
+        assert_eq!(
+            kinds(&lint_notebook(&notebook, &Dictionary::new())),
+            vec![
+                LintKind::BlankGlyphInWord,
+                LintKind::WordLineOnlyGlyph,
+                LintKind::PlaceholderInTranscribedSnippet,
+            ]
+        );
+    }
+
+    #[test]
+    fn placeholder_snippets_are_found() {
+        let notebook: Notebook = vec![Snippet::starting_snippet()].into();
+
+        assert_eq!(
+            kinds(&lint_notebook(&notebook, &Dictionary::new())),
+            vec![LintKind::PlaceholderSnippet]
+        );
+    }
+
+    #[test]
+    fn dictionary_problems_are_found() {
+        let notebook: Notebook = vec![Snippet {
+            words: vec![word("1,2")],
+            ..Snippet::default()
+        }]
+        .into();
+        let tunic_word: TunicWord = "1,2,3".parse().unwrap();
+        let dictionary = Dictionary::new().with_new_definition(&tunic_word, "fox".into());
+
+        assert_eq!(
+            kinds(&lint_notebook(&notebook, &dictionary)),
+            vec![
+                LintKind::NearDuplicateWords,
+                LintKind::UnusedDictionaryEntry
+            ]
+        );
+    }
+
+    #[test]
+    fn snippet_id_problems_are_found() {
+        let snippet = Snippet {
+            id: SnippetId::for_position(0),
+            notes: vec!["See #00000002".into()],
+            ..Snippet::default()
+        };
+        let notebook: Notebook = vec![snippet.clone(), snippet].into();
+        let candidate = Candidate::new("fox").with_snippets(vec![SnippetId::for_position(2)]);
+        let dictionary = Dictionary::new()
+            .with_candidate_for(&TunicWord::new(vec![]).into(), candidate);
+        let lints = lint_notebook(&notebook, &dictionary);
+
+        assert_eq!(lints[1].snippet, Some(SnippetId::for_position(0)));
+        assert_eq!(lints[1].snippet_index, Some(1));
+        assert!(lints[1].to_string().starts_with("snippet #00000001: "));
+        assert_eq!(
+            kinds(&lints),
+            vec![
+                LintKind::UnknownSnippetReference,
+                LintKind::DuplicateSnippetId,
+                LintKind::UnknownSnippetReference,
+                LintKind::UnusedDictionaryEntry,
+                LintKind::UnknownSnippetReference,
+            ]
+        );
+    }
+
+    #[test]
+    fn keys_with_the_same_glyphs_in_different_notation_are_duplicates() {
+        let glyph = Glyph::from_segments(&[2, 6, 15]).unwrap();
+        let yaml: Value = serde_yaml::from_str(&format!(
+            "
+entries:
+  ? glyphs: [{}]
+  : definition: Undefined
+    notes: []
+  ? glyphs: ['2,6+15']
+  : definition: Undefined
+    notes: []
+  ? glyphs: [4352]
+  : definition: Undefined
+    notes: []
+",
+            glyph.0
+        ))
+        .unwrap();
+
+        assert_eq!(
+            kinds(&lint_dictionary_source(&yaml)),
+            vec![LintKind::DuplicateDefinition]
+        );
+    }
+}
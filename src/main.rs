@@ -1,46 +1,78 @@
+mod config;
 mod cursors;
 mod editors;
+mod errors;
+mod exports;
 mod fennec_state;
 mod gui;
+mod image_viewports;
 mod language;
+mod lints;
+mod migrations;
 mod renderers;
 mod views;
 mod visibility;
+mod watchers;
 
 pub mod prelude {
     pub use bracket_lib::prelude::*;
 
+    pub use crate::config::*;
     pub use crate::cursors::*;
+    pub use crate::editors::dictionary_editors::*;
     pub use crate::editors::events::*;
     pub use crate::editors::file_editors::*;
     pub use crate::editors::glyph_editors::*;
+    pub use crate::editors::history::*;
+    pub use crate::editors::line_editors::*;
     pub use crate::editors::notebook_editors::*;
     pub use crate::editors::snippet_editors::*;
     pub use crate::editors::word_editors::english_word_editors::*;
     pub use crate::editors::word_editors::tunic_word_editors::*;
     pub use crate::editors::word_editors::*;
     pub use crate::editors::*;
+    pub use crate::errors::*;
+    pub use crate::exports::*;
     pub use crate::fennec_state::*;
     pub use crate::gui::*;
+    pub use crate::image_viewports::*;
     pub use crate::language::dictionary::*;
+    pub use crate::language::glosses::*;
+    pub use crate::language::glyph_shapes::*;
     pub use crate::language::glyphs::*;
+    pub use crate::language::notation::*;
     pub use crate::language::notebooks::*;
+    pub use crate::language::patterns::*;
+    pub use crate::language::phonology::*;
+    pub use crate::language::regions::*;
+    pub use crate::language::reverse_lookup::*;
+    pub use crate::language::similarity::*;
     pub use crate::language::snippets::*;
     pub use crate::language::words::*;
     pub use crate::language::*;
+    pub use crate::lints::*;
+    pub use crate::migrations::*;
+    pub use crate::renderers::dictionary_editor_renderers::*;
     pub use crate::renderers::file_editor_renderers::*;
     pub use crate::renderers::glyph_map_renderers::*;
+    pub use crate::renderers::line_editor_renderers::*;
     pub use crate::renderers::notebook_editor_renderers::*;
     pub use crate::renderers::snippet_editor_renderers::*;
+    pub use crate::renderers::svg_renderers::*;
     pub use crate::renderers::*;
     pub use crate::views::*;
     pub use crate::visibility::*;
+    pub use crate::watchers::*;
 
     pub const SCREEN_WIDTH: i32 = 80;
     pub const SCREEN_HEIGHT: i32 = 50;
     pub const DISPLAY_WIDTH: i32 = SCREEN_WIDTH / 2;
     pub const DISPLAY_HEIGHT: i32 = SCREEN_HEIGHT / 2;
 
+    /// The size of each glyph in the glyph font bitmap
+    pub const GLYPH_FONT_WIDTH: i32 = 24;
+    pub const GLYPH_FONT_HEIGHT: i32 = 32;
+
     pub const TRANSPARENT: RGBA = RGBA {
         r: 0.0,
         g: 0.0,
@@ -50,13 +82,23 @@ pub mod prelude {
 
     pub static DEFAULT_NOTEBOOK_FILE: &str = "notebook.yaml";
     pub static DEFAULT_DICTIONARY_FILE: &str = "dictionary.yaml";
+    pub static DEFAULT_PHONEME_TABLE_FILE: &str = "phonemes.yaml";
+    pub static DEFAULT_SOURCES_DIRECTORY: &str = "sources";
+    pub static CONFIG_FILE: &str = "fennec.toml";
 
     pub const DEFAULT_GLYPH: Glyph = Glyph(0);
 
+    pub static PLACEHOLDER_ENGLISH_WORD: &str = "PLACEHOLDER";
+    pub static PLACEHOLDER_SOURCE: &str = "ADD_SOURCE_HERE";
+    pub static PLACEHOLDER_DESCRIPTION: &str = "ADD_DESCRIPTION_HERE";
+    pub static PLACEHOLDER_NOTE: &str = "ADD_NOTE_HERE";
+
     pub const GLYPH_SEGMENT_COUNT: usize = 16;
     pub const FILE_CONSOLE: usize = 16;
     pub const NOTEBOOK_CONSOLE: usize = 17;
     pub const SNIPPET_CONSOLE: usize = 18;
 
     pub const MAX_VISIBLE_WORDS: usize = 20;
+
+    pub const MAX_UNDO_STEPS: usize = 100;
 }
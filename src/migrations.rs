@@ -0,0 +1,669 @@
+use serde::de::DeserializeOwned;
+use serde_yaml::value::{Tag, TaggedValue};
+use serde_yaml::{Mapping, Value};
+use std::error::Error;
+use std::fmt;
+use std::fs;
+
+use crate::prelude::*;
+
+#[derive(Clone, Debug)]
+pub struct MigrationError {
+    description: String,
+}
+
+impl MigrationError {
+    pub fn new(description: String) -> Self {
+        Self { description }
+    }
+}
+
+impl fmt::Display for MigrationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "MigrationError: {}", self.description)
+    }
+}
+
+impl Error for MigrationError {
+    fn description(&self) -> &str {
+        &self.description
+    }
+}
+
+/// A single upgrade from `from_version` to the version after it
+pub struct Migration {
+    pub from_version: usize,
+    pub description: &'static str,
+    migrate: fn(Value) -> Result<Value, MigrationError>,
+}
+
+/// Every upgrade step for one kind of file, plus a way to tell which version a
+/// file is at. Files written before the `version` field existed are recognised
+/// by their shape.
+pub struct Migrations {
+    current_version: usize,
+    detect_version: fn(&Value) -> usize,
+    steps: Vec<Migration>,
+}
+
+impl Migrations {
+    pub fn for_notebooks() -> Self {
+        Self {
+            current_version: VERSION,
+            detect_version: detect_notebook_version,
+            steps: vec![
+                Migration {
+                    from_version: 1,
+                    description: "Tunic words gain border and colour flags",
+                    migrate: notebook_v1_to_v2,
+                },
+                Migration {
+                    from_version: 2,
+                    description: "Words are wrapped in a word type",
+                    migrate: notebook_v2_to_v3,
+                },
+                Migration {
+                    from_version: 3,
+                    description: "Snippets get IDs, numbered by their position",
+                    migrate: notebook_v3_to_v4,
+                },
+            ],
+        }
+    }
+
+    pub fn for_dictionaries() -> Self {
+        Self {
+            current_version: DICTIONARY_VERSION,
+            detect_version: detect_dictionary_version,
+            steps: vec![
+                Migration {
+                    from_version: 1,
+                    description: "Entries are keyed by glyphs only and definitions are tentative",
+                    migrate: dictionary_v1_to_v2,
+                },
+                Migration {
+                    from_version: 2,
+                    description: "Definitions become ranked candidate readings",
+                    migrate: dictionary_v2_to_v3,
+                },
+                Migration {
+                    from_version: 3,
+                    description: "Phrases spanning several words get their own entries",
+                    migrate: dictionary_v3_to_v4,
+                },
+                Migration {
+                    from_version: 4,
+                    description: "Candidates refer to snippets by ID instead of position",
+                    migrate: dictionary_v4_to_v5,
+                },
+            ],
+        }
+    }
+
+    pub fn current_version(&self) -> usize {
+        self.current_version
+    }
+
+    pub fn version_of(&self, yaml: &Value) -> usize {
+        (self.detect_version)(yaml)
+    }
+
+    pub fn needs_migration(&self, yaml: &Value) -> bool {
+        self.version_of(yaml) < self.current_version
+    }
+
+    /// The steps that would run on `yaml`, oldest first
+    pub fn pending_steps(&self, yaml: &Value) -> Vec<&Migration> {
+        let version = self.version_of(yaml);
+
+        self.steps
+            .iter()
+            .filter(|step| step.from_version >= version && step.from_version < self.current_version)
+            .collect()
+    }
+
+    /// Chains every step from the file's version up to the current one
+    pub fn migrated(&self, yaml: Value) -> Result<Value, MigrationError> {
+        let version = self.version_of(&yaml);
+
+        if version > self.current_version {
+            return Err(MigrationError::new(format!(
+                "File is at version {version}, newer than the supported version {}",
+                self.current_version
+            )));
+        }
+
+        let migrated = (version..self.current_version).try_fold(yaml, |yaml, from_version| {
+            let step = self
+                .steps
+                .iter()
+                .find(|step| step.from_version == from_version)
+                .ok_or_else(|| {
+                    MigrationError::new(format!("No migration from version {from_version}"))
+                })?;
+
+            let yaml = (step.migrate)(yaml)?;
+
+            Ok(with_version(yaml, from_version + 1))
+        })?;
+
+        // Files from before the `version` field have none, even with no steps to run
+        Ok(with_version(migrated, self.current_version))
+    }
+}
+
+/// Parses `yaml` read from `target_file`, bringing it up to date first. The
+/// original text is kept next to it before anything is changed.
+pub fn from_migrated_yaml<T: DeserializeOwned>(
+    target_file: &str,
+    yaml: &str,
+    migrations: &Migrations,
+) -> FennecResult<T> {
+    let value: Value =
+        serde_yaml::from_str(yaml).map_err(|error| FennecError::yaml(target_file, error))?;
+
+    if !migrations.needs_migration(&value) {
+        // Parsed again from the text so that errors keep their line and column
+        return serde_yaml::from_str(yaml).map_err(|error| FennecError::yaml(target_file, error));
+    }
+
+    let backup_file = backup_file_for(target_file, migrations.version_of(&value));
+
+    eprintln!(
+        "Migrating {} (original kept in {})",
+        target_file, backup_file
+    );
+
+    fs::write(&backup_file, yaml).map_err(|error| FennecError::io(&backup_file, error))?;
+
+    serde_yaml::from_value(migrations.migrated(value)?)
+        .map_err(|error| FennecError::yaml(target_file, error))
+}
+
+pub fn backup_file_for(target_file: &str, version: usize) -> String {
+    format!("{}.v{}.bak", target_file, version)
+}
+
+fn with_version(yaml: Value, version: usize) -> Value {
+    match yaml {
+        Value::Mapping(mut mapping) => {
+            mapping.insert("version".into(), version.into());
+
+            Value::Mapping(mapping)
+        }
+        _ => yaml,
+    }
+}
+
+fn stated_version(yaml: &Value) -> Option<usize> {
+    yaml.get("version")
+        .and_then(Value::as_u64)
+        .map(|version| version as usize)
+}
+
+fn tagged(tag: &str, value: Value) -> Value {
+    Value::Tagged(Box::new(TaggedValue {
+        tag: Tag::new(tag),
+        value,
+    }))
+}
+
+fn with_each_snippet_word(
+    yaml: Value,
+    migrate_word: fn(Value) -> Result<Value, MigrationError>,
+) -> Result<Value, MigrationError> {
+    let mut yaml = yaml;
+
+    let snippets = yaml
+        .get_mut("snippets")
+        .and_then(Value::as_sequence_mut)
+        .ok_or_else(|| MigrationError::new("Notebook has no snippets".into()))?;
+
+    for snippet in snippets.iter_mut() {
+        if let Some(words) = snippet.get_mut("words").and_then(Value::as_sequence_mut) {
+            for word in words.iter_mut() {
+                *word = migrate_word(word.clone())?;
+            }
+        }
+    }
+
+    Ok(yaml)
+}
+
+fn snippet_words(yaml: &Value) -> Vec<&Value> {
+    yaml.get("snippets")
+        .and_then(Value::as_sequence)
+        .map(|snippets| {
+            snippets
+                .iter()
+                .filter_map(|snippet| snippet.get("words").and_then(Value::as_sequence))
+                .flatten()
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// v1 words are `!Tunic [glyphs]`, v2 words are `!Tunic { glyphs, .. }` and v3
+/// words are `{ word_type: !Tunic { glyphs, .. } }`
+fn detect_notebook_version(yaml: &Value) -> usize {
+    if let Some(version) = stated_version(yaml) {
+        return version;
+    }
+
+    let words = snippet_words(yaml);
+
+    if words.iter().any(|word| word.get("word_type").is_some()) {
+        3
+    } else if words.iter().any(|word| match word {
+        Value::Tagged(tagged) => tagged.tag == "Tunic" && tagged.value.is_sequence(),
+        _ => false,
+    }) {
+        1
+    } else {
+        2
+    }
+}
+
+fn notebook_v1_to_v2(yaml: Value) -> Result<Value, MigrationError> {
+    let mut yaml = with_each_snippet_word(yaml, |word| match word {
+        Value::Tagged(tagged) if tagged.tag == "Tunic" => {
+            let mut tunic_word = Mapping::new();
+            tunic_word.insert("glyphs".into(), tagged.value);
+            tunic_word.insert("has_border".into(), false.into());
+            tunic_word.insert("colored".into(), false.into());
+
+            Ok(self::tagged("Tunic", Value::Mapping(tunic_word)))
+        }
+        _ => Ok(word),
+    })?;
+
+    if let Some(snippets) = yaml.get_mut("snippets").and_then(Value::as_sequence_mut) {
+        for snippet in snippets.iter_mut().filter_map(Value::as_mapping_mut) {
+            if !snippet.contains_key("transcribed") {
+                snippet.insert("transcribed".into(), false.into());
+            }
+        }
+    }
+
+    Ok(yaml)
+}
+
+fn notebook_v2_to_v3(yaml: Value) -> Result<Value, MigrationError> {
+    with_each_snippet_word(yaml, |word| {
+        let word_type = match word {
+            Value::Tagged(tagged) if tagged.tag == "English" => {
+                let mut english_word = Mapping::new();
+                english_word.insert("text".into(), tagged.value);
+
+                self::tagged("English", Value::Mapping(english_word))
+            }
+            Value::Tagged(tagged) if tagged.tag == "Tunic" => Value::Tagged(tagged),
+            _ => {
+                return Err(MigrationError::new(format!(
+                    "Unrecognised word: {:?}",
+                    word
+                )))
+            }
+        };
+
+        let mut word = Mapping::new();
+        word.insert("word_type".into(), word_type);
+
+        Ok(Value::Mapping(word))
+    })
+}
+
+/// IDs follow the snippets' order, the same as dictionary migration numbers
+/// the snippets that candidates refer to
+fn notebook_v3_to_v4(yaml: Value) -> Result<Value, MigrationError> {
+    let mut yaml = yaml;
+
+    let snippets = yaml
+        .get_mut("snippets")
+        .and_then(Value::as_sequence_mut)
+        .ok_or_else(|| MigrationError::new("Notebook has no snippets".into()))?;
+
+    for (index, snippet) in snippets.iter_mut().enumerate() {
+        let snippet = snippet
+            .as_mapping_mut()
+            .ok_or_else(|| MigrationError::new(format!("Snippet {} is not a mapping", index)))?;
+
+        let id = SnippetId::for_position(index);
+        snippet.insert("id".into(), id.as_str().into());
+    }
+
+    Ok(yaml)
+}
+
+/// v1 dictionaries are keyed by `!Tunic [glyphs]` and `!English text` words
+fn detect_dictionary_version(yaml: &Value) -> usize {
+    if let Some(version) = stated_version(yaml) {
+        return version;
+    }
+
+    let has_tagged_keys = yaml
+        .get("entries")
+        .and_then(Value::as_mapping)
+        .map(|entries| entries.keys().any(|key| matches!(key, Value::Tagged(_))))
+        .unwrap_or(false);
+
+    if has_tagged_keys {
+        1
+    } else {
+        2
+    }
+}
+
+/// English keys have nowhere to go in a glyph-keyed dictionary and are
+/// dropped. They remain in the backup.
+fn dictionary_v1_to_v2(yaml: Value) -> Result<Value, MigrationError> {
+    let entries = yaml
+        .get("entries")
+        .and_then(Value::as_mapping)
+        .ok_or_else(|| MigrationError::new("Dictionary has no entries".into()))?;
+
+    let mut migrated_entries = Mapping::new();
+
+    for (key, entry) in entries {
+        let glyphs = match key {
+            Value::Tagged(tagged) if tagged.tag == "Tunic" => tagged.value.clone(),
+            Value::Tagged(tagged) if tagged.tag == "English" => continue,
+            _ => return Err(MigrationError::new(format!("Unrecognised word: {:?}", key))),
+        };
+
+        let mut word = Mapping::new();
+        word.insert("glyphs".into(), glyphs);
+
+        let mut entry = entry.clone();
+        if let Some(Value::String(text)) = entry.get("definition") {
+            let definition = tagged("Tentative", Value::String(text.clone()));
+            entry["definition"] = definition;
+        }
+
+        migrated_entries.insert(Value::Mapping(word), entry);
+    }
+
+    let mut dictionary = Mapping::new();
+    dictionary.insert("entries".into(), Value::Mapping(migrated_entries));
+
+    Ok(Value::Mapping(dictionary))
+}
+
+/// A v2 definition becomes the entry's only candidate. Confirmed definitions
+/// are fully confident.
+fn dictionary_v2_to_v3(yaml: Value) -> Result<Value, MigrationError> {
+    let mut yaml = yaml;
+
+    let entries = yaml
+        .get_mut("entries")
+        .and_then(Value::as_mapping_mut)
+        .ok_or_else(|| MigrationError::new("Dictionary has no entries".into()))?;
+
+    for (word, entry) in entries.iter_mut() {
+        let entry = entry
+            .as_mapping_mut()
+            .ok_or_else(|| MigrationError::new(format!("Unrecognised entry for {:?}", word)))?;
+
+        let (text, confirmed) = match entry.remove("definition") {
+            Some(Value::Tagged(tagged)) if tagged.tag == "Tentative" => (tagged.value, false),
+            Some(Value::Tagged(tagged)) if tagged.tag == "Confirmed" => (tagged.value, true),
+            Some(Value::String(text)) if text == "Undefined" => (Value::Null, false),
+            None => (Value::Null, false),
+            Some(definition) => {
+                return Err(MigrationError::new(format!(
+                    "Unrecognised definition: {:?}",
+                    definition
+                )))
+            }
+        };
+
+        let mut candidates: Vec<Value> = vec![];
+
+        if !text.is_null() {
+            let confidence = if confirmed { 100 } else { DEFAULT_CONFIDENCE };
+
+            let mut candidate = Mapping::new();
+            candidate.insert("text".into(), text);
+            candidate.insert("confidence".into(), confidence.into());
+            candidate.insert("confirmed".into(), confirmed.into());
+
+            candidates.push(Value::Mapping(candidate));
+        }
+
+        entry.insert("candidates".into(), Value::Sequence(candidates));
+    }
+
+    Ok(yaml)
+}
+
+fn dictionary_v3_to_v4(yaml: Value) -> Result<Value, MigrationError> {
+    match yaml {
+        Value::Mapping(mut dictionary) => {
+            dictionary.insert("phrases".into(), Value::Mapping(Mapping::new()));
+
+            Ok(Value::Mapping(dictionary))
+        }
+        _ => Err(MigrationError::new("Dictionary is not a mapping".into())),
+    }
+}
+
+/// Positions become the IDs that notebook migration gives the snippets there,
+/// so both files need to be migrated from the same notebook
+fn dictionary_v4_to_v5(yaml: Value) -> Result<Value, MigrationError> {
+    let mut yaml = yaml;
+
+    for entries in ["entries", "phrases"] {
+        let entries = match yaml.get_mut(entries).and_then(Value::as_mapping_mut) {
+            Some(entries) => entries,
+            None => continue,
+        };
+
+        let candidates = entries
+            .iter_mut()
+            .filter_map(|(_, entry)| entry.get_mut("candidates"))
+            .filter_map(Value::as_sequence_mut)
+            .flatten();
+
+        for candidate in candidates {
+            if let Some(snippets) = candidate.get_mut("snippets").and_then(Value::as_sequence_mut) {
+                for snippet in snippets.iter_mut() {
+                    let index = snippet.as_u64().ok_or_else(|| {
+                        MigrationError::new(format!("Unrecognised snippet index: {:?}", snippet))
+                    })?;
+
+                    *snippet = SnippetId::for_position(index as usize).as_str().into();
+                }
+            }
+        }
+    }
+
+    Ok(yaml)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static V1_NOTEBOOK: &str = "
+snippets:
+- words:
+  - !Tunic
+    - 153
+  - !English sword
+  source: !ManualPageNumber 2
+  description: Placeholder
+  notes:
+  - ADD_NOTE_HERE
+";
+
+    static V1_DICTIONARY: &str = "
+entries:
+  !English example:
+    definition: An example English word entry
+    notes: []
+  ? !Tunic
+  - 16384
+  : definition: An example Tunic word entry
+    notes:
+    - Example Note
+";
+
+    #[test]
+    fn v1_notebooks_migrate_to_the_current_version() {
+        let migrations = Migrations::for_notebooks();
+        let yaml: Value = serde_yaml::from_str(V1_NOTEBOOK).unwrap();
+
+        assert_eq!(migrations.version_of(&yaml), 1);
+        assert_eq!(migrations.pending_steps(&yaml).len(), 3);
+
+        let notebook: Notebook =
+            serde_yaml::from_value(migrations.migrated(yaml).unwrap()).unwrap();
+
+        assert_eq!(notebook.version, VERSION);
+        assert_eq!(notebook.snippets[0].id, SnippetId::for_position(0));
+        assert_eq!(notebook.snippets[0].words[0], vec![Glyph(153)].into());
+        assert_eq!(notebook.snippets[0].words[1], "sword".to_string().into());
+    }
+
+    #[test]
+    fn current_notebooks_are_left_alone() {
+        let notebook: Notebook = vec![Snippet::starting_snippet()].into();
+        let yaml = serde_yaml::to_value(&notebook).unwrap();
+
+        let migrations = Migrations::for_notebooks();
+
+        assert!(!migrations.needs_migration(&yaml));
+        assert_eq!(migrations.migrated(yaml.clone()).unwrap(), yaml);
+    }
+
+    #[test]
+    fn newer_files_are_rejected() {
+        let yaml: Value = serde_yaml::from_str("version: 99\nsnippets: []").unwrap();
+
+        assert!(Migrations::for_notebooks().migrated(yaml).is_err());
+    }
+
+    #[test]
+    fn v1_dictionaries_migrate_to_the_current_version() {
+        let migrations = Migrations::for_dictionaries();
+        let yaml: Value = serde_yaml::from_str(V1_DICTIONARY).unwrap();
+
+        assert_eq!(migrations.version_of(&yaml), 1);
+
+        let dictionary: Dictionary =
+            serde_yaml::from_value(migrations.migrated(yaml).unwrap()).unwrap();
+        let word: DictionaryWord = TunicWord::new(vec![Glyph(16384)]).into();
+
+        assert_eq!(dictionary.entries().len(), 1);
+        assert_eq!(
+            dictionary.get(&word).map(Entry::definition),
+            Some(Definition::Tentative("An example Tunic word entry".into()))
+        );
+    }
+
+    #[test]
+    fn dictionaries_without_a_version_migrate_to_the_current_version() {
+        // As the baseline wrote them: glyph keys and no `version`
+        let yaml: Value = serde_yaml::from_str(
+            "
+entries:
+  ? glyphs:
+    - 16384
+  : definition: !Tentative example
+    notes: []
+  ? glyphs:
+    - 4352
+  : definition: Undefined
+    notes: []
+",
+        )
+        .unwrap();
+
+        let migrations = Migrations::for_dictionaries();
+
+        assert_eq!(migrations.version_of(&yaml), 2);
+
+        let migrated = migrations.migrated(yaml).unwrap();
+
+        assert_eq!(migrations.version_of(&migrated), DICTIONARY_VERSION);
+
+        let dictionary: Dictionary = serde_yaml::from_value(migrated).unwrap();
+        let example = dictionary.get(&TunicWord::new(vec![Glyph(16384)]).into()).unwrap();
+
+        assert_eq!(example.definition(), Definition::Tentative("example".into()));
+        assert_eq!(dictionary.entries().len(), 2);
+    }
+
+    #[test]
+    fn v2_definitions_become_candidates() {
+        let migrations = Migrations::for_dictionaries();
+        let yaml: Value = serde_yaml::from_str(
+            "
+version: 2
+entries:
+  ? glyphs: [4352]
+  : definition: !Confirmed sword
+    notes: []
+  ? glyphs: [4353]
+  : definition: Undefined
+    notes: []
+",
+        )
+        .unwrap();
+
+        let dictionary: Dictionary =
+            serde_yaml::from_value(migrations.migrated(yaml).unwrap()).unwrap();
+        let confirmed = dictionary.get(&TunicWord::new(vec![Glyph(4352)]).into()).unwrap();
+        let undefined = dictionary.get(&TunicWord::new(vec![Glyph(4353)]).into()).unwrap();
+
+        assert_eq!(confirmed.definition(), Definition::Confirmed("sword".into()));
+        assert_eq!(confirmed.candidates()[0].confidence(), 100);
+        assert!(undefined.candidates().is_empty());
+    }
+
+    #[test]
+    fn candidate_snippets_become_the_migrated_snippet_ids() {
+        let notebook: Value = serde_yaml::from_str(
+            "
+version: 3
+snippets:
+- description: First
+  source: null
+  transcribed: false
+  notes: []
+  words: []
+- description: Second
+  source: null
+  transcribed: false
+  notes: []
+  words: []
+",
+        )
+        .unwrap();
+        let dictionary: Value = serde_yaml::from_str(
+            "
+version: 4
+entries:
+  ? glyphs: [4352]
+  : candidates:
+    - text: sword
+      snippets: [1]
+    notes: []
+phrases: {}
+",
+        )
+        .unwrap();
+
+        let notebook: Notebook =
+            serde_yaml::from_value(Migrations::for_notebooks().migrated(notebook).unwrap())
+                .unwrap();
+        let dictionary: Dictionary =
+            serde_yaml::from_value(Migrations::for_dictionaries().migrated(dictionary).unwrap())
+                .unwrap();
+
+        let entry = dictionary.get(&TunicWord::new(vec![Glyph(4352)]).into()).unwrap();
+        let id = &entry.candidates()[0].snippets()[0];
+
+        assert_eq!(notebook.snippet_with_id(id).unwrap().description, "Second");
+    }
+}
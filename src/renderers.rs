@@ -1,4 +1,21 @@
+pub mod dictionary_editor_renderers;
 pub mod file_editor_renderers;
 pub mod glyph_map_renderers;
+pub mod line_editor_renderers;
 pub mod notebook_editor_renderers;
 pub mod snippet_editor_renderers;
+pub mod svg_renderers;
+
+use crate::prelude::*;
+
+/// The text consoles' width and height in characters, which follow the window
+/// size in `fennec.toml`. Leaves the active console as it was.
+pub fn text_console_size(ctx: &mut BTerm) -> (u32, u32) {
+    let active_console = ctx.active_console;
+
+    ctx.set_active_console(FILE_CONSOLE);
+    let size = ctx.get_char_size();
+    ctx.set_active_console(active_console);
+
+    size
+}
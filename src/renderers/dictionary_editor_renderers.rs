@@ -0,0 +1,192 @@
+use std::error::Error;
+
+use crate::prelude::*;
+
+pub fn render_dictionary_editor_on(
+    view: &DictionaryEditorView,
+    ctx: &mut BTerm,
+    x: u32,
+    y: u32,
+) -> Result<(), Box<dyn Error>> {
+    ctx.set_active_console(NOTEBOOK_CONSOLE);
+    ctx.cls();
+
+    let x_offset: u32 = 13;
+
+    if let (Some(word), Some(entry)) = (&view.word, &view.entry) {
+        let definition_type = match entry.definition() {
+            Definition::Undefined => "Undefined",
+            Definition::Tentative(_) => "Tentative",
+            Definition::Confirmed(_) => "Confirmed",
+        };
+
+        ctx.print_color(x, y, YELLOW, BLACK, "Dictionary Entry");
+
+        ctx.print_color(x, y + 2, GREEN, BLACK, "       Word:");
+        ctx.print_color(x + x_offset, y + 2, WHITE, BLACK, word.to_string());
+
+        ctx.print_color(x, y + 3, GREEN, BLACK, "       Type:");
+        ctx.print_color(x + x_offset, y + 3, WHITE, BLACK, definition_type);
+
+        ctx.print_color(x, y + 4, GREEN, BLACK, " Candidates:");
+
+        let mut candidate_y = y + 4;
+
+        for (index, candidate) in entry.candidates().iter().enumerate() {
+            let selected = index == view.selected_candidate && view.line_editor_view.is_none();
+            let background = if selected { GRAY15 } else { BLACK };
+            let text_color = if candidate.is_confirmed() { WHITE } else { GRAY60 };
+            let label = format!("{:>3}% ", candidate.confidence());
+
+            ctx.print_color(x + x_offset, candidate_y, YELLOW, background, &label);
+
+            let text_x = x + x_offset + u32::try_from(label.len())?;
+
+            match &view.line_editor_view {
+                Some(line_editor_view)
+                    if view.editing_field == DictionaryField::Definition
+                        && index == view.selected_candidate =>
+                {
+                    render_line_editor_on(line_editor_view, ctx, text_x, candidate_y)?
+                }
+                _ => ctx.print_color(text_x, candidate_y, text_color, background, candidate.text()),
+            };
+
+            let provenance = format_provenance(candidate);
+
+            if !provenance.is_empty() {
+                ctx.print_color(text_x + 2, candidate_y + 1, GRAY40, BLACK, provenance);
+                candidate_y += 1;
+            }
+
+            candidate_y += 1;
+        }
+
+        match &view.line_editor_view {
+            Some(line_editor_view) if view.editing_field == DictionaryField::NewCandidate => {
+                ctx.print_color(x + x_offset, candidate_y, YELLOW, BLACK, "New:");
+                render_line_editor_on(line_editor_view, ctx, x + x_offset + 5, candidate_y)?;
+                candidate_y += 1;
+            }
+            _ if entry.candidates().is_empty() => {
+                ctx.print_color(x + x_offset, candidate_y, GRAY40, BLACK, "(none)");
+                candidate_y += 1;
+            }
+            _ => (),
+        };
+
+        let mut note_y = candidate_y + 1;
+
+        ctx.print_color(x, note_y, GREEN, BLACK, "      Notes:");
+
+        for note in entry.notes() {
+            ctx.print_color(x + x_offset, note_y, WHITE, BLACK, format!("- {}", note.as_text()));
+            note_y += 1;
+        }
+
+        match &view.line_editor_view {
+            Some(line_editor_view) if view.editing_field == DictionaryField::NewNote => {
+                ctx.print_color(x + x_offset, note_y, YELLOW, BLACK, "-");
+                render_line_editor_on(line_editor_view, ctx, x + x_offset + 2, note_y)?
+            }
+            _ => (),
+        };
+
+        let help_y = text_console_size(ctx).1.saturating_sub(5);
+        let help_text = if view.line_editor_view.is_some() {
+            "Enter: Confirm  Escape: Cancel"
+        } else {
+            "Enter: Edit  A: Add  Tab: Confirm  [/]: Confidence  Del: Remove  N: Add Note  \
+            F2: Save Dictionary  Esc: Back"
+        };
+
+        ctx.print_color(x, help_y, GRAY40, BLACK, help_text);
+    } else if let Some(reverse_lookup_view) = &view.reverse_lookup_view {
+        render_reverse_lookup_on(reverse_lookup_view, ctx, x, y)?;
+    }
+
+    render_dictionary_state_onto(&view.state, &view.target_file, &view.conflicts, ctx)
+}
+
+/// Like the notebook's file state, also naming the entries that changed both
+/// here and on disk
+pub fn render_dictionary_state_onto(
+    state: &FileEditorState,
+    target_file: &str,
+    conflicts: &[String],
+    ctx: &mut BTerm,
+) -> Result<(), Box<dyn Error>> {
+    let mut lines = file_state_lines(state, "dictionary", target_file);
+
+    if *state == FileEditorState::ChangedOnDisk && !conflicts.is_empty() {
+        let conflicts = format!("Changed on both sides: {}", conflicts.join(", "));
+
+        lines.insert(1, (WHITE, conflicts));
+    }
+
+    render_state_lines_onto(lines, ctx)
+}
+
+fn render_reverse_lookup_on(
+    view: &ReverseLookupView,
+    ctx: &mut BTerm,
+    x: u32,
+    y: u32,
+) -> Result<(), Box<dyn Error>> {
+    let x_offset: u32 = 13;
+    let help_y = text_console_size(ctx).1.saturating_sub(5);
+
+    ctx.print_color(x, y, YELLOW, BLACK, "Reverse Lookup");
+
+    ctx.print_color(x, y + 2, GREEN, BLACK, " Defined as:");
+    render_line_editor_on(&view.line_editor_view, ctx, x + x_offset, y + 2)?;
+
+    let mut result_y = y + 4;
+
+    // Results past the bottom of the screen are left out until the search narrows
+    for (index, (word, entry)) in view.results.iter().enumerate() {
+        if result_y >= help_y - 1 {
+            break;
+        }
+
+        let (definition_color, definition_text) = match entry.definition() {
+            Definition::Confirmed(text) => (WHITE, text),
+            Definition::Tentative(text) => (GRAY60, text),
+            Definition::Undefined => continue,
+        };
+        let background = if index == view.selected { GRAY15 } else { BLACK };
+
+        ctx.print_color(x + 1, result_y, GREEN, background, format!("[{}]", word));
+        ctx.print_color(x + x_offset, result_y, definition_color, background, definition_text);
+        result_y += 1;
+    }
+
+    if view.results.is_empty() {
+        ctx.print_color(x + x_offset, result_y, GRAY40, BLACK, "No definitions found");
+    }
+
+    ctx.print_color(x, help_y, GRAY40, BLACK, "Up/Down: Select  Enter: Open Entry  Esc: Back");
+
+    Ok(())
+}
+
+/// Who proposed a candidate, when, and the snippets backing it up
+fn format_provenance(candidate: &Candidate) -> String {
+    let mut parts: Vec<String> = vec![];
+
+    if let Some(author) = candidate.author() {
+        parts.push(format!("by {}", author));
+    }
+
+    if let Some(date) = candidate.date() {
+        parts.push(format!("on {}", date));
+    }
+
+    if !candidate.snippets().is_empty() {
+        let snippets: Vec<String> =
+            candidate.snippets().iter().map(SnippetId::to_reference).collect();
+        parts.push(format!("snippets {}", snippets.join(", ")));
+    }
+
+    parts.join(" ")
+}
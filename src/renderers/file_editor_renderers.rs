@@ -5,50 +5,89 @@ use crate::prelude::*;
 pub fn render_file_editor_view_onto(
     view: &FileEditorView,
     ctx: &mut BTerm,
+) -> Result<(), Box<dyn Error>> {
+    render_file_state_onto(&view.state, "notebook", &view.target_file, ctx)
+}
+
+pub fn render_file_state_onto(
+    state: &FileEditorState,
+    subject: &str,
+    target_file: &str,
+    ctx: &mut BTerm,
+) -> Result<(), Box<dyn Error>> {
+    render_state_lines_onto(file_state_lines(state, subject, target_file), ctx)
+}
+
+/// Prints `lines` at the bottom of the screen, over whatever was there
+pub fn render_state_lines_onto(
+    lines: Vec<((u8, u8, u8), String)>,
+    ctx: &mut BTerm,
 ) -> Result<(), Box<dyn Error>> {
     ctx.set_active_console(FILE_CONSOLE);
     ctx.cls();
 
     let x: i32 = 1;
-    let y: i32 = (SCREEN_HEIGHT - 3).try_into()?;
+    let screen_height = i32::try_from(text_console_size(ctx).1)?;
+    let first_y = screen_height - 2 - i32::try_from(lines.len())?;
+
+    for (offset, (color, text)) in lines.into_iter().enumerate() {
+        ctx.print_color(x, first_y + i32::try_from(offset)?, color, BLACK, text);
+    }
 
-    match &view.state {
+    Ok(())
+}
+
+/// What to tell the user about a file in this state, one colored line at a time
+pub fn file_state_lines(
+    state: &FileEditorState,
+    subject: &str,
+    target_file: &str,
+) -> Vec<((u8, u8, u8), String)> {
+    match state {
         FileEditorState::LoadRequestSucceeded => {
-            let text = format!("Loaded notebook from {}", view.target_file);
-            ctx.print_color(x, y, GREEN, BLACK, text);
-        }
-        FileEditorState::LoadRequestFailed(error) => {
-            let text = format!("Failed to load notebook from {}", view.target_file);
-            ctx.print_color(x, y - 1, RED, BLACK, text);
-            ctx.print_color(x, y, WHITE, BLACK, error.to_string());
+            vec![(GREEN, format!("Loaded {} from {}", subject, target_file))]
         }
+        FileEditorState::LoadRequestFailed(error) => vec![
+            (RED, format!("Failed to load {} from {}", subject, target_file)),
+            (WHITE, error.to_string()),
+        ],
         FileEditorState::SaveRequestSucceeded => {
-            let text = format!("Saved notebook to {}", view.target_file);
-            ctx.print_color(x, y, GREEN, BLACK, text);
-        }
-        FileEditorState::SaveRequestFailed(error) => {
-            let text = format!("Failed to save notebook to {}", view.target_file);
-            ctx.print_color(x, y - 1, RED, BLACK, text);
-            ctx.print_color(x, y, WHITE, BLACK, error.to_string());
+            vec![(GREEN, format!("Saved {} to {}", subject, target_file))]
         }
-        FileEditorState::ConfirmingLoadRequest => {
-            let text = format!(
-                "Load the notebook from {}? Press Enter/Return to confirm or Escape to cancel",
-                view.target_file
-            );
-            ctx.print_color(x, y, YELLOW, BLACK, text);
-        }
-        FileEditorState::ConfirmingSaveRequest => {
-            let text = format!(
-                "Save the notebook to {}? Press Enter/Return to confirm or Escape to cancel",
-                view.target_file
-            );
-            ctx.print_color(x, y, YELLOW, BLACK, text);
-        }
-        FileEditorState::LoadRequestConfirmed => (),
-        FileEditorState::SaveRequestConfirmed => (),
-        FileEditorState::Idle => (),
-    };
-
-    Ok(())
+        FileEditorState::SaveRequestFailed(error) => vec![
+            (RED, format!("Failed to save {} to {}", subject, target_file)),
+            (WHITE, error.to_string()),
+        ],
+        FileEditorState::ConfirmingLoadRequest => vec![(
+            YELLOW,
+            format!(
+                "Load the {} from {}? Press Enter/Return to confirm or Escape to cancel",
+                subject, target_file
+            ),
+        )],
+        FileEditorState::ConfirmingSaveRequest => vec![(
+            YELLOW,
+            format!(
+                "Save the {} to {}? Press Enter/Return to confirm or Escape to cancel",
+                subject, target_file
+            ),
+        )],
+        FileEditorState::ChangedOnDisk => vec![
+            (
+                YELLOW,
+                format!(
+                    "{} changed on disk while the {} had unsaved changes",
+                    target_file, subject
+                ),
+            ),
+            (
+                YELLOW,
+                "Press Enter/Return to load it and drop your changes, or Escape to keep yours"
+                    .into(),
+            ),
+        ],
+        FileEditorState::LoadRequestConfirmed => vec![],
+        FileEditorState::SaveRequestConfirmed => vec![],
+        FileEditorState::Idle => vec![],
+    }
 }
@@ -26,6 +26,17 @@ impl Error for GlyphRenderError {
     }
 }
 
+/// Where a glyph is drawn within its tile, as the left, top, width and height
+/// fractions of the tile. Matches the glyph font.
+const GLYPH_TILE_FRAME: (f32, f32, f32, f32) = (4.0 / 24.0, 2.0 / 32.0, 16.0 / 24.0, 26.0 / 32.0);
+
+/// How far from a segment the mouse still hovers it, as a fraction of the tile width
+const SEGMENT_HOVER_TOLERANCE: f32 = 0.125;
+
+/// Shows what a click would do to the hovered segment
+const SEGMENT_REMOVAL_PREVIEW_COLOR: (u8, u8, u8) = RED;
+const SEGMENT_ADDITION_PREVIEW_COLOR: (u8, u8, u8) = GRAY60;
+
 #[derive(Clone, Copy, Debug)]
 pub struct GlyphDrawing {
     pub glyph: Glyph,
@@ -37,6 +48,8 @@ pub struct GlyphMap {
     pub width: u32,
     pub height: u32,
     pub glyphs: Vec<Option<GlyphDrawing>>,
+    pub selected: Option<(u32, u32)>,
+    pub hovered_segment: Option<Segment>,
 }
 
 impl GlyphMap {
@@ -47,6 +60,8 @@ impl GlyphMap {
             width,
             height,
             glyphs: vec![None; size],
+            selected: None,
+            hovered_segment: None,
         })
     }
 
@@ -83,6 +98,36 @@ impl GlyphMap {
             .and_then(|glyph| *glyph)
     }
 
+    /// Finds the segment of the selected glyph under the mouse, for a map drawn
+    /// at x, y. The hovered segment is previewed when the map is drawn.
+    pub fn hover_mouse_on(&mut self, ctx: &mut BTerm, x: u32, y: u32) -> Option<Segment> {
+        ctx.set_active_console(0);
+
+        let (columns, rows) = ctx.get_char_size();
+
+        self.hovered_segment = match self.selected {
+            Some((gx, gy)) if columns > 0 && rows > 0 => {
+                let tile_width = ctx.width_pixels as f32 / columns as f32;
+                let tile_height = ctx.height_pixels as f32 / rows as f32;
+                let (left, top, width, height) = GLYPH_TILE_FRAME;
+
+                let glyph_x = ((x + gx) as f32 + left) * tile_width;
+                let glyph_y = ((y + gy) as f32 + top) * tile_height;
+                let (mouse_x, mouse_y) = ctx.mouse_pos;
+
+                segment_at(
+                    (mouse_x as f32 - glyph_x, mouse_y as f32 - glyph_y),
+                    width * tile_width,
+                    height * tile_height,
+                    SEGMENT_HOVER_TOLERANCE * tile_width,
+                )
+            }
+            _ => None,
+        };
+
+        self.hovered_segment
+    }
+
     pub fn draw_on(&self, ctx: &mut BTerm, x: u32, y: u32) -> Result<(), Box<dyn Error>> {
         for segment in 0..GLYPH_SEGMENT_COUNT {
             ctx.set_active_console(segment);
@@ -111,8 +156,20 @@ impl GlyphMap {
                 if let Some(glyph) = self.get_glyph(gx, gy) {
                     let color = glyph.color;
                     let glyph = glyph.glyph;
+                    let included = glyph.includes_segment(segment)?;
+
+                    let hovered = self.selected == Some((gx, gy))
+                        && self.hovered_segment == Some(segment.into());
+
+                    if hovered {
+                        let color = if included {
+                            SEGMENT_REMOVAL_PREVIEW_COLOR
+                        } else {
+                            SEGMENT_ADDITION_PREVIEW_COLOR
+                        };
 
-                    if glyph.includes_segment(segment)? {
+                        ctx.set(x + gx, y + gy, color, TRANSPARENT, segment)
+                    } else if included {
                         ctx.set(x + gx, y + gy, color, TRANSPARENT, segment)
                     }
                 }
@@ -165,6 +222,10 @@ impl GlyphMap {
     ) -> Result<(), Box<dyn Error>> {
         let color = if view.selected { YELLOW } else { WHITE };
 
+        if view.selected {
+            self.selected = Some((x, y));
+        }
+
         self.set_glyph(x, y, view.glyph, color.into())?;
 
         Ok(())
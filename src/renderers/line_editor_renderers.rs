@@ -0,0 +1,19 @@
+use std::error::Error;
+
+use crate::prelude::*;
+
+pub fn render_line_editor_on(
+    view: &LineEditorView,
+    ctx: &mut BTerm,
+    x: u32,
+    y: u32,
+) -> Result<(), Box<dyn Error>> {
+    ctx.print_color(x, y, WHITE, GRAY15, &view.text);
+
+    let cursor_x = x + u32::try_from(view.cursor)?;
+    let character_under_cursor = view.text.chars().nth(view.cursor).unwrap_or(' ');
+
+    ctx.print_color(cursor_x, y, BLACK, WHITE, character_under_cursor);
+
+    Ok(())
+}
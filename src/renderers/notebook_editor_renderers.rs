@@ -5,6 +5,7 @@ use crate::prelude::*;
 pub fn render_notebook_on(
     notebook_view: &NotebookView,
     dictionary: &Dictionary,
+    phoneme_table: &PhonemeTable,
     map: &mut GlyphMap,
     ctx: &mut BTerm,
     x: u32,
@@ -38,7 +39,11 @@ pub fn render_notebook_on(
                 };
 
                 let source_label = snippet_source_to_label(snippet_view);
-                let source_label = format!("        Source: {}", source_label);
+                let source_label = format!(
+                    "        Source: {}  {}",
+                    source_label,
+                    snippet_view.snippet.id.to_reference()
+                );
 
                 let description_color = description_color_for(&snippet_view);
                 let source_color = source_color_for(&snippet_view);
@@ -48,19 +53,169 @@ pub fn render_notebook_on(
             }
 
             let count_y = 1;
+            let (screen_width, _) = text_console_size(ctx);
+            let count_x = i32::try_from(screen_width)?;
 
-            ctx.print_color(SCREEN_WIDTH - 12, count_y, GREEN, BLACK, "Count: ");
-            ctx.print_color(SCREEN_WIDTH - 5, count_y, WHITE, BLACK, format!("{:3}", snippet_views.len()));
+            ctx.print_color(count_x - 12, count_y, GREEN, BLACK, "Count: ");
+            ctx.print_color(count_x - 5, count_y, WHITE, BLACK, format!("{:3}", snippet_views.len()));
+
+            render_filters_on(&notebook_view.filters, ctx, x)?;
         }
         NotebookEditorState::EditingSnippet => {
-            render_selected_snippet_on(notebook_view, dictionary, map, ctx, x, y)?
+            render_selected_snippet_on(notebook_view, dictionary, phoneme_table, map, ctx, x, y)?
+        }
+        NotebookEditorState::EditingSnippetDetails => {
+            render_selected_snippet_details_on(notebook_view, ctx, x, y)?
         }
     };
 
     Ok(())
 }
 
-fn description_color_for(view: &SnippetView) -> (u8, u8, u8) {
+fn render_filters_on(
+    filters: &NotebookEditorFilters,
+    ctx: &mut BTerm,
+    x: u32,
+) -> Result<(), Box<dyn Error>> {
+    let help_y = text_console_size(ctx).1.saturating_sub(2);
+
+    ctx.print_color(x, help_y, GRAY40, BLACK, filters_help_text(filters));
+
+    Ok(())
+}
+
+/// The keys for each filter and what it is set to
+pub fn filters_help_text(filters: &NotebookEditorFilters) -> String {
+    let transcribed = match filters.has_been_transcribed() {
+        Some(true) => "yes",
+        Some(false) => "no",
+        None => "any",
+    };
+
+    format!(
+        "T: Transcribed ({})  G: Tag ({})  L: Location ({})",
+        transcribed,
+        filters.tag().unwrap_or("any"),
+        filters.location().unwrap_or("any")
+    )
+}
+
+pub fn render_selected_snippet_details_on(
+    notebook_view: &NotebookView,
+    ctx: &mut BTerm,
+    x: u32,
+    y: u32,
+) -> Result<(), Box<dyn Error>> {
+    let selected_snippet_view = notebook_view
+        .snippet_views
+        .iter()
+        .find(|snippet_view| snippet_view.selected);
+
+    if let Some(snippet_view) = selected_snippet_view {
+        let snippet = &snippet_view.snippet;
+
+        let fields = snippet_detail_fields(snippet_view);
+
+        let mut line_y = y;
+
+        for (field, label, text) in fields {
+            let selected = field == snippet_view.selected_field;
+            let label_color = if selected { YELLOW } else { GREEN };
+            let marker = if selected { "->" } else { "  " };
+
+            ctx.print_color(x, line_y, label_color, BLACK, marker);
+            ctx.print_color(x + 3, line_y, label_color, BLACK, &label);
+
+            let text_x = x + 4 + u32::try_from(label.chars().count())?;
+
+            match &snippet_view.line_editor_view {
+                Some(line_editor_view) if selected => {
+                    render_line_editor_on(line_editor_view, ctx, text_x, line_y)?
+                }
+                _ => ctx.print_color(text_x, line_y, WHITE, BLACK, text),
+            };
+
+            line_y += 2;
+        }
+
+        let timestamps = format!(
+            "Created: {}  Modified: {}",
+            snippet.created.as_deref().unwrap_or("unknown"),
+            snippet.modified.as_deref().unwrap_or("unknown")
+        );
+
+        ctx.print_color(x + 3, line_y, GRAY40, BLACK, timestamps);
+
+        let help_y = text_console_size(ctx).1.saturating_sub(2);
+
+        ctx.print_color(x, help_y, GRAY40, BLACK, snippet_details_help_text(snippet_view));
+    }
+
+    Ok(())
+}
+
+pub fn snippet_details_help_text(snippet_view: &SnippetView) -> &'static str {
+    if snippet_view.line_editor_view.is_some() {
+        "Enter: Confirm  Escape: Cancel"
+    } else {
+        "Enter: Edit  Tab: Source Type  N: New Note  Delete: Remove Note  Esc: Back"
+    }
+}
+
+/// The label and text of each field on the details screen, in order
+pub fn snippet_detail_fields(snippet_view: &SnippetView) -> Vec<(SnippetField, String, String)> {
+    let snippet = &snippet_view.snippet;
+
+    let source_kind = match &snippet.source {
+        Some(Source::ManualPageNumber(_)) => "     Source: Manual Page",
+        Some(Source::ScreenshotFilename(_)) => "     Source: Screenshot",
+        Some(Source::Other(_)) => "     Source: Other",
+        None => "     Source: (Unknown)",
+    };
+
+    let mut fields: Vec<(SnippetField, String, String)> = vec![
+        (
+            SnippetField::Description,
+            "Description:".into(),
+            snippet.description.clone(),
+        ),
+        (
+            SnippetField::Source,
+            source_kind.into(),
+            snippet.source.as_ref().map_or(String::new(), |source| source.text()),
+        ),
+        (
+            SnippetField::Region,
+            "     Region:".into(),
+            snippet.region.map_or(String::new(), |region| region.to_string()),
+        ),
+        (SnippetField::Tags, "       Tags:".into(), snippet.tags_text()),
+        (
+            SnippetField::Location,
+            "   Location:".into(),
+            snippet.location.clone().unwrap_or_default(),
+        ),
+    ];
+
+    for (index, note) in snippet.notes.iter().enumerate() {
+        fields.push((SnippetField::Note(index), note_label(index), note.as_text()));
+    }
+
+    // A note that is still being written is shown after the saved ones
+    let new_note = SnippetField::Note(snippet.notes.len());
+
+    if snippet_view.selected_field == new_note {
+        fields.push((new_note, note_label(snippet.notes.len()), String::new()));
+    }
+
+    fields
+}
+
+fn note_label(index: usize) -> String {
+    format!("{:>11}:", format!("Note {}", index))
+}
+
+pub fn description_color_for(view: &SnippetView) -> (u8, u8, u8) {
     let SnippetView {
         selected,
         transcribed,
@@ -99,6 +254,7 @@ fn source_color_for(view: &SnippetView) -> (u8, u8, u8) {
 pub fn render_selected_snippet_on(
     notebook_view: &NotebookView,
     dictionary: &Dictionary,
+    phoneme_table: &PhonemeTable,
     map: &mut GlyphMap,
     ctx: &mut BTerm,
     x: u32,
@@ -110,7 +266,7 @@ pub fn render_selected_snippet_on(
         .find(|snippet_view| snippet_view.selected);
 
     if let Some(snippet_view) = selected_snippet_view {
-        let y_from_bottom: u32 = SCREEN_HEIGHT.try_into()?;
+        let (_, y_from_bottom) = text_console_size(ctx);
 
         let (selected_word, has_border, colored) = snippet_view
             .word_views
@@ -124,32 +280,15 @@ pub fn render_selected_snippet_on(
 
         render_snippet_on(snippet_view, map, ctx, x, y)?;
 
-        let translation = snippet_view
-            .word_views
-            .iter()
-            .map(|view| match &view.word.word_type {
-                WordType::Tunic(word) => dictionary
-                    .get(&word.into())
-                    .map(|entry| entry.clone()),
-                WordType::English(word) => {
-                    let definition = Definition::Confirmed(word.text().clone());
-                    Some(Entry::new(definition, vec![]))
-                }
-            })
-            .map(|entry| match entry {
-                Some(e) => match e.definition() {
-                    Definition::Tentative(text) => text.to_owned(),
-                    Definition::Confirmed(text) => text.to_owned(),
-                    Definition::Undefined => "___".to_owned(),
-                },
-                None => "___".to_owned(),
-            })
-            .reduce(|translation, word| format!("{translation} {word}"))
-            .unwrap_or("___".to_owned());
+        let words = &snippet_view.snippet.words;
+
+        let translation = translation_of(words, dictionary);
 
         render_translation(&translation, ctx, x, y_from_bottom - 8)?;
 
         if let Some(word) = &selected_word {
+            render_selected_word_pronunciation(word, phoneme_table, ctx, x, y_from_bottom - 9)?;
+
             if let WordType::Tunic(tunic_word) = &word.word_type {
                 let dict_word: DictionaryWord = tunic_word.into();
                 let entry = dictionary.get(&dict_word);
@@ -168,6 +307,21 @@ pub fn render_selected_snippet_on(
 
     Ok(())
 }
+/// The snippet's words read through the dictionary, with blanks for the
+/// words nobody has defined yet
+pub fn translation_of(words: &[Word], dictionary: &Dictionary) -> String {
+    glosses_for(words, dictionary)
+        .iter()
+        .map(|gloss| match (&words[gloss.words.start].word_type, gloss.definition()) {
+            (WordType::English(word), _) => word.text(),
+            (_, Definition::Tentative(text)) => text,
+            (_, Definition::Confirmed(text)) => text,
+            (_, Definition::Undefined) => "___".to_owned(),
+        })
+        .reduce(|translation, word| format!("{translation} {word}"))
+        .unwrap_or("___".to_owned())
+}
+
 fn format_glyphs_for_reading(glyphs: Vec<Glyph>) -> String {
     glyphs
         .iter()
@@ -200,13 +354,13 @@ fn render_word_definition(
 
     let definition = match entry {
         Some(entry) => entry.definition(),
-        None => &Definition::Undefined,
+        None => Definition::Undefined,
     };
 
     let definition = match definition {
         Definition::Undefined => "(pending)".into(),
-        Definition::Tentative(text) => text.clone(),
-        Definition::Confirmed(text) => text.clone(),
+        Definition::Tentative(text) => text,
+        Definition::Confirmed(text) => text,
     };
 
     ctx.print_color(x, y, GREEN, BLACK, " Definition:");
@@ -215,6 +369,30 @@ fn render_word_definition(
     Ok(())
 }
 
+fn render_selected_word_pronunciation(
+    word: &Word,
+    phoneme_table: &PhonemeTable,
+    ctx: &mut BTerm,
+    x: u32,
+    y: u32,
+) -> Result<(), Box<dyn Error>> {
+    let x_offset: u32 = 13;
+
+    ctx.print_color(x, y, GREEN, BLACK, " Pronounced:");
+
+    match &word.word_type {
+        WordType::Tunic(tunic_word) => {
+            let pronunciation = tunic_word.pronunciation(phoneme_table);
+            ctx.print_color(x + x_offset, y, WHITE, BLACK, pronunciation);
+        }
+        WordType::English(_) => {
+            ctx.print_color(x + x_offset, y, GRAY40, BLACK, "n/a");
+        }
+    };
+
+    Ok(())
+}
+
 fn render_selected_word_glyphs_as_base10(
     word: Word,
     ctx: &mut BTerm,
@@ -329,7 +507,7 @@ fn render_colored_status(
     Ok(())
 }
 
-fn snippet_source_to_label(snippet_view: &SnippetView) -> String {
+pub fn snippet_source_to_label(snippet_view: &SnippetView) -> String {
     if let Some(source) = &snippet_view.snippet.source {
         match source {
             Source::ManualPageNumber(number) => format!("Manual: Page {}", number),
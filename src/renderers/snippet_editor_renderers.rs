@@ -56,7 +56,10 @@ pub fn render_snippet_on(
                 let y = (absolute_index * 2) + y + y_offset;
                 let color = if view.selected { YELLOW } else { WHITE };
 
-                ctx.print_color(x, y, color, BLACK, word.text());
+                match &view.line_editor_view {
+                    Some(line_editor_view) => render_line_editor_on(line_editor_view, ctx, x, y)?,
+                    None => ctx.print_color(x, y, color, BLACK, word.text()),
+                };
                 ctx.print_color(x + translation_offset, y, WHITE, BLACK, definition);
             }
             WordType::Tunic(_) => {
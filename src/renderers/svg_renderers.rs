@@ -0,0 +1,350 @@
+use crate::prelude::*;
+
+type Point = (f32, f32);
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SvgStyle {
+    pub glyph_width: f32,
+    pub glyph_height: f32,
+    pub stroke_width: f32,
+    pub word_spacing: f32,
+    pub line_spacing: f32,
+    /// Snippets wrap onto a new line before going wider than this
+    pub max_width: f32,
+    pub color: String,
+    /// Used for words in the colored state
+    pub colored_color: String,
+    pub background: Option<String>,
+    pub font_family: String,
+}
+
+impl Default for SvgStyle {
+    fn default() -> Self {
+        Self {
+            glyph_width: 24.0,
+            glyph_height: 45.0,
+            stroke_width: 2.0,
+            word_spacing: 16.0,
+            line_spacing: 16.0,
+            max_width: 800.0,
+            color: "#202020".into(),
+            colored_color: "#c03020".into(),
+            background: None,
+            font_family: "monospace".into(),
+        }
+    }
+}
+
+impl SvgStyle {
+    pub fn with_glyph_size(self, glyph_width: f32, glyph_height: f32) -> Self {
+        Self {
+            glyph_width,
+            glyph_height,
+            ..self
+        }
+    }
+
+    pub fn with_max_width(self, max_width: f32) -> Self {
+        Self { max_width, ..self }
+    }
+
+    pub fn with_colors(self, color: &str, colored_color: &str) -> Self {
+        Self {
+            color: color.into(),
+            colored_color: colored_color.into(),
+            ..self
+        }
+    }
+
+    pub fn with_background(self, background: Option<String>) -> Self {
+        Self { background, ..self }
+    }
+
+    fn reversal_radius(&self) -> f32 {
+        self.glyph_width * REVERSAL_RADIUS
+    }
+
+    /// Glyphs sit on top of room for the reversal circle
+    fn cell_height(&self) -> f32 {
+        self.glyph_height + self.reversal_radius() * 2.0 + self.stroke_width
+    }
+
+    /// English words are sized to roughly the height of a glyph
+    fn font_size(&self) -> f32 {
+        self.glyph_height * 0.5
+    }
+
+    fn english_word_width(&self, word: &EnglishWord) -> f32 {
+        word.text().chars().count() as f32 * self.font_size() * 0.6
+    }
+
+    fn tunic_word_width(&self, word: &TunicWord) -> f32 {
+        word.glyphs().len() as f32 * self.glyph_width + self.border_padding() * 2.0
+    }
+
+    fn border_padding(&self) -> f32 {
+        self.stroke_width * 2.0
+    }
+}
+
+/// A single glyph as a standalone SVG document. Only the segments the glyph has
+/// are drawn, so the word line only shows when segment 0 is set.
+pub fn glyph_to_svg(glyph: &Glyph, style: &SvgStyle) -> String {
+    let padding = style.stroke_width;
+    let elements = glyph_elements(glyph, &style.color, style, (padding, padding));
+
+    svg_document(
+        style.glyph_width + padding * 2.0,
+        style.cell_height() + padding * 2.0,
+        elements,
+        style,
+    )
+}
+
+/// A word as a standalone SVG document, with the word line through every glyph
+pub fn tunic_word_to_svg(word: &TunicWord, style: &SvgStyle) -> String {
+    let padding = style.stroke_width;
+    let elements = tunic_word_elements(word, style, (padding, padding));
+
+    svg_document(
+        style.tunic_word_width(word) + padding * 2.0,
+        style.cell_height() + padding * 2.0,
+        elements,
+        style,
+    )
+}
+
+/// A snippet as a standalone SVG document, with its words laid out left to
+/// right and wrapped at `style.max_width`
+pub fn snippet_to_svg(snippet: &Snippet, style: &SvgStyle) -> String {
+    let padding = style.stroke_width;
+    let row_height = style.cell_height() + style.line_spacing;
+
+    let mut elements: Vec<String> = vec![];
+    let mut x = padding;
+    let mut y = padding;
+    let mut width: f32 = 0.0;
+
+    for word in snippet.words.iter() {
+        let word_width = match &word.word_type {
+            WordType::Tunic(tunic_word) => style.tunic_word_width(tunic_word),
+            WordType::English(english_word) => style.english_word_width(english_word),
+        };
+
+        if x > padding && x + word_width > style.max_width {
+            x = padding;
+            y += row_height;
+        }
+
+        match &word.word_type {
+            WordType::Tunic(tunic_word) => {
+                elements.extend(tunic_word_elements(tunic_word, style, (x, y)))
+            }
+            WordType::English(english_word) => {
+                elements.push(english_word_element(english_word, style, (x, y)))
+            }
+        }
+
+        width = width.max(x + word_width);
+        x += word_width + style.word_spacing;
+    }
+
+    svg_document(
+        width + padding,
+        y + style.cell_height() + padding,
+        elements,
+        style,
+    )
+}
+
+fn tunic_word_elements(word: &TunicWord, style: &SvgStyle, origin: Point) -> Vec<String> {
+    let (x, y) = origin;
+    let color = if word.colored() {
+        &style.colored_color
+    } else {
+        &style.color
+    };
+    let inset = style.border_padding();
+
+    let mut elements: Vec<String> = vec![];
+
+    if word.has_border() {
+        elements.push(format!(
+            r#"<rect x="{}" y="{}" width="{}" height="{}" fill="none" stroke="{}" stroke-width="{}"/>"#,
+            x,
+            y,
+            style.tunic_word_width(word),
+            style.cell_height(),
+            escape_xml(color),
+            style.stroke_width / 2.0,
+        ));
+    }
+
+    for (index, glyph) in word.glyphs().iter().enumerate() {
+        let glyph = Glyph(glyph.0 | WORD_LINE_MASK);
+        let glyph_x = x + inset + index as f32 * style.glyph_width;
+
+        elements.extend(glyph_elements(&glyph, color, style, (glyph_x, y)));
+    }
+
+    elements
+}
+
+fn glyph_elements(glyph: &Glyph, color: &str, style: &SvgStyle, origin: Point) -> Vec<String> {
+    let (x, y) = origin;
+    let point = |(px, py): Point| (x + px * style.glyph_width, y + py * style.glyph_height);
+
+    let segments = glyph.segments();
+
+    let mut elements: Vec<String> = SEGMENT_LINES
+        .iter()
+        .filter(|(segment, _, _)| segments.contains(segment))
+        .map(|(_, start, end)| {
+            let (x1, y1) = point(*start);
+            let (x2, y2) = point(*end);
+
+            format!(
+                r#"<line x1="{}" y1="{}" x2="{}" y2="{}" stroke="{}" stroke-width="{}" stroke-linecap="round"/>"#,
+                x1,
+                y1,
+                x2,
+                y2,
+                escape_xml(color),
+                style.stroke_width
+            )
+        })
+        .collect();
+
+    if segments.contains(&REVERSAL_SEGMENT) {
+        let ((cx, cy), radius) = reversal_circle(style.glyph_width, style.glyph_height);
+
+        elements.push(format!(
+            r#"<circle cx="{}" cy="{}" r="{}" fill="none" stroke="{}" stroke-width="{}"/>"#,
+            x + cx,
+            y + cy,
+            radius,
+            escape_xml(color),
+            style.stroke_width
+        ));
+    }
+
+    elements
+}
+
+fn english_word_element(word: &EnglishWord, style: &SvgStyle, origin: Point) -> String {
+    let (x, y) = origin;
+
+    format!(
+        r#"<text x="{}" y="{}" font-family="{}" font-size="{}" fill="{}" dominant-baseline="middle">{}</text>"#,
+        x,
+        y + style.glyph_height * 0.5,
+        escape_xml(&style.font_family),
+        style.font_size(),
+        escape_xml(&style.color),
+        escape_xml(&word.text())
+    )
+}
+
+fn svg_document(width: f32, height: f32, elements: Vec<String>, style: &SvgStyle) -> String {
+    let mut lines = vec![format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}">"#,
+        w = width,
+        h = height
+    )];
+
+    if let Some(background) = &style.background {
+        lines.push(format!(
+            r#"<rect width="100%" height="100%" fill="{}"/>"#,
+            escape_xml(background)
+        ));
+    }
+
+    lines.extend(elements);
+    lines.push("</svg>".into());
+
+    lines.join("\n") + "\n"
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn count(svg: &str, element: &str) -> usize {
+        svg.matches(&format!("<{} ", element)).count()
+    }
+
+    #[test]
+    fn glyphs_draw_one_line_per_segment() {
+        let svg = glyph_to_svg(&Glyph(VOWEL_MASK), &SvgStyle::default());
+
+        assert_eq!(count(&svg, "line"), 6);
+        assert_eq!(count(&svg, "circle"), 0);
+
+        let svg = glyph_to_svg(&Glyph(0xffff), &SvgStyle::default());
+
+        assert_eq!(count(&svg, "line"), 15);
+        assert_eq!(count(&svg, "circle"), 1);
+    }
+
+    #[test]
+    fn glyphs_are_padded_like_words() {
+        let style = SvgStyle::default();
+        let height = format!("height=\"{}\"", style.cell_height() + 2.0 * style.stroke_width);
+
+        assert!(glyph_to_svg(&Glyph(0), &style).contains(&height));
+        assert!(tunic_word_to_svg(&TunicWord::new(vec![Glyph(0)]), &style).contains(&height));
+    }
+
+    #[test]
+    fn colors_are_escaped() {
+        let style = SvgStyle::default()
+            .with_colors("red\" onload=\"x", "blue")
+            .with_background(Some("<black>".into()));
+
+        let svg = glyph_to_svg(&Glyph(VOWEL_MASK), &style);
+
+        assert!(svg.contains("stroke=\"red&quot; onload=&quot;x\""));
+        assert!(svg.contains("fill=\"&lt;black&gt;\""));
+    }
+
+    #[test]
+    fn words_add_the_word_line_border_and_color() {
+        let word = TunicWord::new(vec![Glyph(REVERSAL_MASK), Glyph(REVERSAL_MASK)])
+            .with_border_as(true)
+            .with_colored_as(true);
+        let style = SvgStyle::default();
+
+        let svg = tunic_word_to_svg(&word, &style);
+
+        assert_eq!(count(&svg, "line"), 2);
+        assert_eq!(count(&svg, "circle"), 2);
+        assert_eq!(count(&svg, "rect"), 1);
+        assert!(svg.contains(&style.colored_color));
+        assert!(!svg.contains(&format!("\"{}\"", style.color)));
+    }
+
+    #[test]
+    fn snippets_wrap_and_escape_english_words() {
+        let word: Word = TunicWord::new(vec![Glyph(0), Glyph(0)]).into();
+        let snippet = Snippet {
+            words: vec![word.clone(), "<HP>".into(), word],
+            ..Snippet::default()
+        };
+        let style = SvgStyle::default().with_max_width(100.0);
+
+        let svg = snippet_to_svg(&snippet, &style);
+
+        assert!(svg.contains(">&lt;HP&gt;</text>"));
+        let three_rows =
+            3.0 * style.cell_height() + 2.0 * style.line_spacing + 2.0 * style.stroke_width;
+
+        assert!(svg.contains(&format!("height=\"{}\"", three_rows)));
+    }
+}
@@ -16,6 +16,7 @@ pub struct WordView {
     pub selected: bool,
     pub glyph_views: Vec<GlyphView>,
     pub definition: Definition,
+    pub line_editor_view: Option<LineEditorView>,
 }
 
 #[derive(Clone, Debug)]
@@ -26,6 +27,12 @@ pub struct WordViewParams {
     pub definition: Definition,
 }
 
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct LineEditorView {
+    pub text: String,
+    pub cursor: usize,
+}
+
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct SnippetView {
     pub snippet: Snippet,
@@ -33,12 +40,36 @@ pub struct SnippetView {
     pub word_views: Vec<WordView>,
     pub transcribed: bool,
     pub retained: bool,
+    pub selected_field: SnippetField,
+    pub line_editor_view: Option<LineEditorView>,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct NotebookView {
     pub state: NotebookEditorState,
     pub snippet_views: Vec<SnippetView>,
+    pub filters: NotebookEditorFilters,
+}
+
+#[derive(Clone, Debug)]
+pub struct DictionaryEditorView {
+    pub word: Option<DictionaryWord>,
+    pub entry: Option<Entry>,
+    pub selected_candidate: usize,
+    pub editing_field: DictionaryField,
+    pub line_editor_view: Option<LineEditorView>,
+    pub state: FileEditorState,
+    pub target_file: String,
+    /// Entries changed both here and on disk, while the state is `ChangedOnDisk`
+    pub conflicts: Vec<String>,
+    pub reverse_lookup_view: Option<ReverseLookupView>,
+}
+
+#[derive(Clone, Debug)]
+pub struct ReverseLookupView {
+    pub line_editor_view: LineEditorView,
+    pub results: Vec<(DictionaryWord, Entry)>,
+    pub selected: usize,
 }
 
 #[derive(Clone, Debug)]
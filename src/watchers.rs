@@ -0,0 +1,89 @@
+use std::fs;
+use std::time::{Duration, SystemTime};
+
+/// How often the GUI and server check watched files
+pub const FILE_WATCH_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct WatchedFile {
+    path: String,
+    modified: Option<SystemTime>,
+}
+
+/// Notices files changing on disk by polling their modification times. Both
+/// our own saves and other tools' edits count as changes, so whoever reloads
+/// should compare with what they already have.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FileWatcher {
+    files: Vec<WatchedFile>,
+}
+
+impl FileWatcher {
+    pub fn new(paths: &[&str]) -> Self {
+        let files = paths
+            .iter()
+            .map(|path| WatchedFile {
+                path: (*path).to_owned(),
+                modified: modified_time(path),
+            })
+            .collect();
+
+        Self { files }
+    }
+
+    /// Files modified since the last check. Removed files aren't reported
+    /// until they come back.
+    pub fn changed_files(&mut self) -> Vec<String> {
+        let mut changed: Vec<String> = vec![];
+
+        for file in self.files.iter_mut() {
+            let modified = modified_time(&file.path);
+
+            if modified.is_some() && modified != file.modified {
+                changed.push(file.path.clone());
+            }
+
+            file.modified = modified;
+        }
+
+        changed
+    }
+}
+
+fn modified_time(path: &str) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use std::fs::File;
+
+    #[test]
+    fn reports_each_modification_once() {
+        let path = env::temp_dir().join(format!("fennec-watch-{}.yaml", std::process::id()));
+        let path = path.to_string_lossy().into_owned();
+        let missing = format!("{}.missing", path);
+
+        fs::write(&path, "version: 1").unwrap();
+
+        let mut watcher = FileWatcher::new(&[&path, &missing]);
+
+        assert!(watcher.changed_files().is_empty());
+
+        let later = SystemTime::now() + Duration::from_secs(10);
+        File::options()
+            .write(true)
+            .open(&path)
+            .and_then(|file| file.set_modified(later))
+            .unwrap();
+
+        assert_eq!(watcher.changed_files(), vec![path.clone()]);
+        assert!(watcher.changed_files().is_empty());
+
+        fs::remove_file(&path).unwrap();
+
+        assert!(watcher.changed_files().is_empty());
+    }
+}